@@ -0,0 +1,375 @@
+//! Processing Benchmark
+//! Times data preparation and group statistics on synthetic data, comparing the
+//! vectorized Polars path with the original row-by-row implementation.
+//!
+//! Run with `cargo bench --bench processing -- [rows]`.
+
+use chartify_pro::data::{DataMode, DataProcessor};
+use chartify_pro::stats::{DataTypeStats, StatsCalculator, TestMethod};
+use polars::prelude::*;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Rows generated when no count is given.
+const DEFAULT_ROWS: usize = 1_000_000;
+
+const GROUPS: [&str; 5] = ["Control", "A", "B", "C", "D"];
+const DATA_TYPES: [&str; 4] = ["Thickness", "Width", "Resistance", "Yield"];
+
+fn main() -> PolarsResult<()> {
+    // Cargo passes `--bench` along with any arguments after `--`
+    let rows = std::env::args()
+        .skip(1)
+        .find_map(|arg| arg.parse().ok())
+        .unwrap_or(DEFAULT_ROWS);
+    run(rows)
+}
+
+/// Run both paths in single and multi column mode and print their timings.
+fn run(rows: usize) -> PolarsResult<()> {
+    println!("Generating {} rows of synthetic data...", rows);
+    let df = synthetic_data(rows)?;
+    let data_cols: Vec<String> = DATA_TYPES.iter().map(|dt| dt.to_string()).collect();
+
+    println!();
+    println!(
+        "{:<8} {:>14} {:>14} {:>10}",
+        "Mode", "Row-by-row", "Vectorized", "Speedup"
+    );
+    for mode in [DataMode::Single, DataMode::Multi] {
+        let (legacy_time, legacy_stats) = timed(|| legacy::analyse(&df, mode, &data_cols));
+        let (vectorized_time, vectorized_stats) = timed(|| vectorized(&df, mode, &data_cols));
+
+        let name = match mode {
+            DataMode::Single => "Single",
+            DataMode::Multi => "Multi",
+        };
+        println!(
+            "{:<8} {:>12.2?} {:>12.2?} {:>9.1}x",
+            name,
+            legacy_time,
+            vectorized_time,
+            legacy_time.as_secs_f64() / vectorized_time.as_secs_f64()
+        );
+        check_agreement(&legacy_stats?, &vectorized_stats?);
+    }
+
+    Ok(())
+}
+
+/// Long-format data with a group, a data type and a value per row, plus one wide
+/// column per data type for multi column mode. Every 97th value is missing.
+fn synthetic_data(rows: usize) -> PolarsResult<DataFrame> {
+    // Small linear congruential generator so runs are reproducible
+    let mut state: u64 = 0x2545_F491_4F6C_DD1D;
+    let mut next = move || {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        (state >> 11) as f64 / (1u64 << 53) as f64
+    };
+
+    let groups: Vec<&str> = (0..rows).map(|i| GROUPS[i % GROUPS.len()]).collect();
+    let data_types: Vec<&str> = (0..rows)
+        .map(|i| DATA_TYPES[(i / GROUPS.len()) % DATA_TYPES.len()])
+        .collect();
+    let mut columns = vec![
+        Column::new("group".into(), groups),
+        Column::new("data_type".into(), data_types),
+    ];
+    for name in std::iter::once("value").chain(DATA_TYPES) {
+        let values: Vec<Option<f64>> = (0..rows)
+            .map(|i| (i % 97 != 0).then(|| 100.0 + 10.0 * next()))
+            .collect();
+        columns.push(Column::new(name.into(), values));
+    }
+
+    DataFrame::new(columns)
+}
+
+/// The current path: Polars unpivot and a single group_by.
+fn vectorized(
+    df: &DataFrame,
+    mode: DataMode,
+    data_cols: &[String],
+) -> PolarsResult<HashMap<String, DataTypeStats>> {
+    let prepared = DataProcessor::prepare_data(
+        df,
+        mode,
+        "group",
+        Some("data_type"),
+        Some("value"),
+        Some(data_cols),
+        None,
+    )
+    .map_err(|e| PolarsError::ComputeError(e.to_string().into()))?;
+    let grouped = StatsCalculator::group_values(&prepared.df)?;
+    Ok(StatsCalculator::compute_all_stats_parallel(
+        &grouped,
+        GROUPS[0],
+        TestMethod::Welch,
+    ))
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> (Duration, T) {
+    let start = Instant::now();
+    let result = f();
+    (start.elapsed(), result)
+}
+
+/// Report whether both paths produced the same counts and means.
+fn check_agreement(
+    legacy: &HashMap<String, DataTypeStats>,
+    vectorized: &HashMap<String, DataTypeStats>,
+) {
+    let mismatches: Vec<String> = legacy
+        .iter()
+        .flat_map(|(data_type, stats)| {
+            stats
+                .group_stats
+                .iter()
+                .filter_map(move |(group, expected)| {
+                    let actual = vectorized.get(data_type)?.group_stats.get(group);
+                    let agrees = actual.is_some_and(|actual| {
+                        actual.count == expected.count
+                            && (actual.mean - expected.mean).abs() <= 1e-9 * expected.mean.abs()
+                    });
+                    (!agrees).then(|| format!("{}/{}", data_type, group))
+                })
+        })
+        .collect();
+
+    if mismatches.is_empty() {
+        println!("         results agree");
+    } else {
+        println!("         MISMATCH in {}", mismatches.join(", "));
+    }
+}
+
+/// The original row-by-row implementation, kept as the benchmark baseline.
+mod legacy {
+    use chartify_pro::data::{DataMode, DropCounts};
+    use chartify_pro::stats::{DataTypeStats, StatsCalculator, TestMethod};
+    use polars::prelude::*;
+    use std::collections::HashMap;
+
+    /// Prepare the data and compute statistics and plot values, as the old
+    /// `run_calculation` did.
+    pub fn analyse(
+        df: &DataFrame,
+        mode: DataMode,
+        data_cols: &[String],
+    ) -> PolarsResult<HashMap<String, DataTypeStats>> {
+        let (long, _excluded, _unassigned) = match mode {
+            DataMode::Single => prepare_single(df, "group", "data_type", "value")?,
+            DataMode::Multi => stack_to_long(df, "group", data_cols)?,
+        };
+
+        let stats: HashMap<String, DataTypeStats> = unique_strings(&long, "data_type")
+            .into_iter()
+            .map(|data_type| {
+                let stats = compute_data_type_stats(&long, &data_type, super::GROUPS[0]);
+                (data_type, stats)
+            })
+            .collect();
+
+        // Plot values were gathered with one more filter per group
+        for (data_type, stat) in &stats {
+            for group in stat.get_ordered_groups() {
+                let _ = values_for(&long, Some(data_type), &group);
+            }
+        }
+
+        Ok(stats)
+    }
+
+    type Prepared = (DataFrame, HashMap<(String, String), DropCounts>, usize);
+
+    /// Why a single value was left out.
+    #[derive(Clone, Copy)]
+    enum DropReason {
+        Unparseable,
+        Null,
+        NaN,
+    }
+
+    /// Count one dropped value.
+    fn record(counts: &mut DropCounts, reason: DropReason) {
+        match reason {
+            DropReason::Unparseable => counts.unparseable += 1,
+            DropReason::Null => counts.null += 1,
+            DropReason::NaN => counts.nan += 1,
+        }
+    }
+
+    fn drop_reason(raw_is_null: bool, value: Option<f64>) -> Option<DropReason> {
+        match value {
+            Some(v) if v.is_nan() => Some(DropReason::NaN),
+            Some(_) => None,
+            None if raw_is_null => Some(DropReason::Null),
+            None => Some(DropReason::Unparseable),
+        }
+    }
+
+    fn prepare_single(
+        df: &DataFrame,
+        group_col: &str,
+        data_type_col: &str,
+        value_col: &str,
+    ) -> PolarsResult<Prepared> {
+        let group_series = df.column(group_col)?;
+        let dtype_series = df.column(data_type_col)?;
+        let value_series = df.column(value_col)?;
+        let raw_null = value_series.is_null();
+        let value_f64 = value_series.cast(&DataType::Float64)?;
+        let value_ca = value_f64.f64()?;
+
+        let mut groups: Vec<String> = Vec::new();
+        let mut data_types: Vec<String> = Vec::new();
+        let mut values: Vec<f64> = Vec::new();
+        let mut excluded: HashMap<(String, String), DropCounts> = HashMap::new();
+        let mut unassigned = 0;
+
+        for i in 0..df.height() {
+            let (Ok(g), Ok(dt)) = (group_series.get(i), dtype_series.get(i)) else {
+                continue;
+            };
+            if g.is_null() || dt.is_null() {
+                unassigned += 1;
+                continue;
+            }
+            let group = g.to_string().trim_matches('"').to_string();
+            let data_type = dt.to_string().trim_matches('"').to_string();
+            let v = value_ca.get(i);
+
+            match drop_reason(raw_null.get(i).unwrap_or(true), v) {
+                Some(reason) => record(excluded.entry((data_type, group)).or_default(), reason),
+                None => {
+                    groups.push(group);
+                    data_types.push(data_type);
+                    values.push(v.unwrap_or(f64::NAN));
+                }
+            }
+        }
+
+        let long = DataFrame::new(vec![
+            Column::new("group".into(), groups),
+            Column::new("data_type".into(), data_types),
+            Column::new("value".into(), values),
+        ])?;
+        Ok((long, excluded, unassigned))
+    }
+
+    fn stack_to_long(
+        df: &DataFrame,
+        group_col: &str,
+        data_cols: &[String],
+    ) -> PolarsResult<Prepared> {
+        let mut groups: Vec<String> = Vec::new();
+        let mut data_types: Vec<String> = Vec::new();
+        let mut values: Vec<f64> = Vec::new();
+        let mut excluded: HashMap<(String, String), DropCounts> = HashMap::new();
+        let mut unassigned = 0;
+
+        let group_series = df.column(group_col)?;
+
+        for data_col in data_cols {
+            if let Ok(value_series) = df.column(data_col) {
+                let raw_null = value_series.is_null();
+                let value_f64 = value_series.cast(&DataType::Float64)?;
+                let value_ca = value_f64.f64()?;
+
+                for i in 0..df.height() {
+                    let Ok(g) = group_series.get(i) else {
+                        continue;
+                    };
+                    if g.is_null() {
+                        unassigned += 1;
+                        continue;
+                    }
+                    let group = g.to_string().trim_matches('"').to_string();
+                    let v = value_ca.get(i);
+
+                    match drop_reason(raw_null.get(i).unwrap_or(true), v) {
+                        Some(reason) => record(
+                            excluded.entry((data_col.clone(), group)).or_default(),
+                            reason,
+                        ),
+                        None => {
+                            groups.push(group);
+                            data_types.push(data_col.clone());
+                            values.push(v.unwrap_or(f64::NAN));
+                        }
+                    }
+                }
+            }
+        }
+
+        let long = DataFrame::new(vec![
+            Column::new("group".into(), groups),
+            Column::new("data_type".into(), data_types),
+            Column::new("value".into(), values),
+        ])?;
+        Ok((long, excluded, unassigned))
+    }
+
+    fn unique_strings(df: &DataFrame, column: &str) -> Vec<String> {
+        df.column(column)
+            .ok()
+            .and_then(|col| col.unique().ok())
+            .map(|unique| {
+                unique
+                    .as_materialized_series()
+                    .iter()
+                    .filter(|v| !v.is_null())
+                    .map(|v| v.to_string().trim_matches('"').to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Filter the whole frame down to one group (and optionally one data type).
+    fn values_for(df: &DataFrame, data_type: Option<&str>, group: &str) -> Vec<f64> {
+        let mut predicate = col("group").eq(lit(group));
+        if let Some(data_type) = data_type {
+            predicate = predicate.and(col("data_type").eq(lit(data_type)));
+        }
+        df.clone()
+            .lazy()
+            .filter(predicate)
+            .select([col("value")])
+            .collect()
+            .ok()
+            .and_then(|df| df.column("value").ok().cloned())
+            .and_then(|col| col.f64().ok().map(|ca| ca.into_iter().flatten().collect()))
+            .unwrap_or_default()
+    }
+
+    fn compute_data_type_stats(
+        df: &DataFrame,
+        data_type: &str,
+        control_group: &str,
+    ) -> DataTypeStats {
+        let type_df = df
+            .clone()
+            .lazy()
+            .filter(col("data_type").eq(lit(data_type)))
+            .collect()
+            .unwrap_or_default();
+
+        let groups: HashMap<String, Vec<f64>> = unique_strings(&type_df, "group")
+            .into_iter()
+            .map(|group| {
+                let values = values_for(&type_df, None, &group);
+                (group, values)
+            })
+            .collect();
+
+        StatsCalculator::compute_data_type_stats(
+            data_type,
+            &groups,
+            control_group,
+            TestMethod::Welch,
+        )
+    }
+}
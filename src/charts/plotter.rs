@@ -1,14 +1,24 @@
 //! Chart Plotter Module
 //! Creates interactive visualizations using egui_plot.
 
-use crate::stats::DataTypeStats;
+use crate::stats::{DataTypeStats, Outcome, SpecLimits};
 use egui::{Color32, RichText};
-use egui_plot::{BoxElem, BoxPlot, BoxSpread, Legend, Line, Plot, PlotPoints, Points};
+use egui_plot::{
+    BoxElem, BoxPlot, BoxSpread, HLine, Line, LineStyle, MarkerShape, Plot, PlotPoints, PlotUi,
+    Points, VLine,
+};
 use std::collections::HashMap;
 
 /// Color palette for groups
 pub const CONTROL_COLOR: Color32 = Color32::from_rgb(52, 152, 219); // Blue
 
+/// Colors of the spec limit and target lines
+pub const SPEC_LIMIT_COLOR: Color32 = Color32::from_rgb(192, 57, 43);
+pub const SPEC_TARGET_COLOR: Color32 = Color32::from_rgb(39, 174, 96);
+
+/// Color of the outlier markers
+pub const OUTLIER_COLOR: Color32 = Color32::from_rgb(20, 20, 20);
+
 pub const PALETTE: [Color32; 10] = [
     Color32::from_rgb(231, 76, 60),  // Red
     Color32::from_rgb(46, 204, 113), // Green
@@ -27,13 +37,107 @@ pub const PALETTE: [Color32; 10] = [
 pub struct ChartData {
     pub data_type: String,
     pub data_by_group: HashMap<String, Vec<f64>>,
+    /// Group minus control differences of each pair ID, in paired mode
+    pub differences: HashMap<String, Vec<f64>>,
+    /// Outliers of each group found by the outlier rule
+    pub outliers: HashMap<String, Vec<f64>>,
     pub stats: DataTypeStats,
 }
 
+impl ChartData {
+    /// Whether the distribution plot shows paired differences.
+    pub fn is_paired(&self) -> bool {
+        !self.differences.is_empty()
+    }
+
+    /// Title of the distribution plot.
+    pub fn distribution_title(&self) -> &'static str {
+        if self.is_paired() {
+            "Paired Differences"
+        } else {
+            "Distribution by Group"
+        }
+    }
+
+    /// Groups of the distribution plot with their values: every group's values,
+    /// control first, or in paired mode each group's differences from the control.
+    pub fn distribution_series(&self) -> Vec<(String, &[f64])> {
+        let (source, control) = if self.is_paired() {
+            (&self.differences, Some(&self.stats.control_group))
+        } else {
+            (&self.data_by_group, None)
+        };
+        self.stats
+            .get_ordered_groups()
+            .into_iter()
+            .filter(|group| Some(group) != control)
+            .map(|group| {
+                let values = source.get(&group).map(Vec::as_slice).unwrap_or_default();
+                (group, values)
+            })
+            .collect()
+    }
+
+    /// Outliers of a group to mark, none for the paired differences.
+    pub fn outliers_of(&self, group: &str) -> &[f64] {
+        if self.is_paired() {
+            return &[];
+        }
+        self.outliers
+            .get(group)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Values of a group for the quantile plot in ascending order, each flagged
+    /// when it is an outlier; excluded outliers are put back so they can be marked.
+    pub fn quantile_series(&self, group: &str) -> Vec<(f64, bool)> {
+        let mut outliers = self.outliers_of(group).to_vec();
+        outliers.sort_by(f64::total_cmp);
+        let mut values: Vec<f64> = self
+            .data_by_group
+            .get(group)
+            .into_iter()
+            .flatten()
+            .copied()
+            .filter(|v| !v.is_nan())
+            .collect();
+        if self
+            .stats
+            .outlier_filter
+            .is_some_and(|(filter, _)| filter.exclude)
+        {
+            values.extend(&outliers);
+        }
+        values.sort_by(f64::total_cmp);
+
+        let mut next = 0;
+        values
+            .into_iter()
+            .map(|value| {
+                let outlier = outliers.get(next) == Some(&value);
+                if outlier {
+                    next += 1;
+                }
+                (value, outlier)
+            })
+            .collect()
+    }
+}
+
 /// Creates scientific visualization charts using egui_plot.
 pub struct ChartPlotter;
 
 impl ChartPlotter {
+    /// Color of a comparison outcome: red, amber or green.
+    pub fn outcome_color(outcome: Outcome) -> Color32 {
+        match outcome {
+            Outcome::Different => Color32::from_rgb(220, 53, 69),
+            Outcome::Inconclusive => Color32::from_rgb(230, 160, 60),
+            Outcome::Equivalent => Color32::from_rgb(40, 167, 69),
+        }
+    }
+
     /// Get color for a group.
     pub fn get_group_color(group: &str, control_group: &str, group_index: usize) -> Color32 {
         if group == control_group {
@@ -43,6 +147,39 @@ impl ChartPlotter {
         }
     }
 
+    /// Draw dashed horizontal lines at the spec limits and the target.
+    fn draw_spec_lines(plot_ui: &mut PlotUi, limits: Option<SpecLimits>) {
+        let Some(limits) = limits else {
+            return;
+        };
+        for (value, name, color) in [
+            (limits.lsl, "LSL", SPEC_LIMIT_COLOR),
+            (limits.target, "Target", SPEC_TARGET_COLOR),
+            (limits.usl, "USL", SPEC_LIMIT_COLOR),
+        ] {
+            if let Some(value) = value {
+                plot_ui.hline(
+                    HLine::new(value)
+                        .color(color)
+                        .width(1.5)
+                        .style(LineStyle::dashed_loose())
+                        .name(name),
+                );
+            }
+        }
+    }
+
+    /// Mark outliers with crosses.
+    fn draw_outliers(plot_ui: &mut PlotUi, points: Vec<[f64; 2]>) {
+        plot_ui.points(
+            Points::new(PlotPoints::from(points))
+                .shape(MarkerShape::Cross)
+                .radius(5.0)
+                .color(OUTLIER_COLOR)
+                .name("Outliers"),
+        );
+    }
+
     /// Calculate beeswarm positions for points with duplicate values.
     pub fn beeswarm_positions(y_values: &[f64], center: f64, width: f64) -> Vec<f64> {
         let n = y_values.len();
@@ -78,15 +215,17 @@ impl ChartPlotter {
     }
 
     /// Draw boxplot with scatter overlay for a chart
-    /// X-axis: groups, Y-axis: values
+    /// X-axis: groups, Y-axis: values, or in paired mode the differences from the
+    /// control against a zero line
     pub fn draw_boxplot_chart(ui: &mut egui::Ui, chart_data: &ChartData, full_size: bool) {
-        let ordered_groups = chart_data.stats.get_ordered_groups();
+        let series = chart_data.distribution_series();
         let control_group = &chart_data.stats.control_group;
+        let paired = chart_data.is_paired();
 
         let height = if full_size { 300.0 } else { 180.0 };
 
         // Create custom x-axis labels
-        let x_labels: Vec<String> = ordered_groups.clone();
+        let x_labels: Vec<String> = series.iter().map(|(group, _)| group.clone()).collect();
 
         Plot::new(format!("boxplot_{}", chart_data.data_type))
             .height(height)
@@ -94,7 +233,7 @@ impl ChartPlotter {
             .allow_drag(full_size)
             .allow_scroll(false)
             .x_axis_label("Group")
-            .y_axis_label("Value")
+            .y_axis_label(if paired { "Group − control" } else { "Value" })
             .x_axis_formatter(move |mark, _range| {
                 let idx = mark.value.round() as usize;
                 if idx < x_labels.len() {
@@ -107,12 +246,14 @@ impl ChartPlotter {
                 let mut non_control_idx = 0;
                 let mut means: Vec<(f64, f64)> = Vec::new();
 
-                for (i, group) in ordered_groups.iter().enumerate() {
-                    let values = chart_data
-                        .data_by_group
-                        .get(group)
-                        .cloned()
-                        .unwrap_or_default();
+                if paired {
+                    plot_ui.hline(HLine::new(0.0).color(Color32::GRAY).width(1.0));
+                } else {
+                    Self::draw_spec_lines(plot_ui, chart_data.stats.spec_limits);
+                }
+
+                for (i, (group, values)) in series.iter().enumerate() {
+                    let values = values.to_vec();
                     if values.is_empty() {
                         continue;
                     }
@@ -175,10 +316,18 @@ impl ChartPlotter {
                             .color(color.gamma_multiply(0.7))
                             .name(format!("{} points", group)),
                     );
+
+                    let outliers = chart_data.outliers_of(group);
+                    if !outliers.is_empty() {
+                        Self::draw_outliers(
+                            plot_ui,
+                            outliers.iter().map(|&y| [i as f64, y]).collect(),
+                        );
+                    }
                 }
 
-                // Draw mean line
-                if means.len() > 1 {
+                // Draw mean line; paired differences are read against zero instead
+                if means.len() > 1 && !paired {
                     let line_points: PlotPoints = means.iter().map(|&(x, y)| [x, y]).collect();
                     plot_ui.line(
                         Line::new(line_points)
@@ -190,6 +339,80 @@ impl ChartPlotter {
             });
     }
 
+    /// Draw the mean difference of every group versus the control as an error bar
+    /// spanning its confidence interval, one row per group.
+    pub fn draw_ci_chart(ui: &mut egui::Ui, chart_data: &ChartData, height: f32) {
+        let stats = &chart_data.stats;
+        let groups: Vec<String> = stats
+            .get_ordered_groups()
+            .into_iter()
+            .filter(|g| g != &stats.control_group)
+            .collect();
+        let y_labels = groups.clone();
+
+        Plot::new(format!("ci_{}", chart_data.data_type))
+            .height(height)
+            .allow_zoom(false)
+            .allow_drag(false)
+            .allow_scroll(false)
+            .show_grid([true, false])
+            .x_axis_label(format!("{} of M-C", stats.ci_header()))
+            .y_axis_formatter(move |mark, _range| {
+                let idx = -mark.value.round();
+                if idx >= 0.0 && (idx as usize) < y_labels.len() && mark.value.fract() == 0.0 {
+                    y_labels[idx as usize].clone()
+                } else {
+                    String::new()
+                }
+            })
+            .include_y(0.5)
+            .include_y(-(groups.len() as f64) + 0.5)
+            .include_x(0.0)
+            .show(ui, |plot_ui| {
+                plot_ui.vline(VLine::new(0.0).color(Color32::GRAY).width(1.0));
+
+                for (i, group) in groups.iter().enumerate() {
+                    let Some(gs) = stats.group_stats.get(group) else {
+                        continue;
+                    };
+                    let color = Self::get_group_color(group, &stats.control_group, i);
+                    let y = -(i as f64);
+
+                    if let Some((low, high)) = gs.mean_diff_ci {
+                        let bar = [[low, y], [high, y]];
+                        plot_ui.line(
+                            Line::new(PlotPoints::from(bar.to_vec()))
+                                .color(color)
+                                .width(2.0),
+                        );
+                        for x in [low, high] {
+                            let cap = vec![[x, y - 0.15], [x, y + 0.15]];
+                            plot_ui.line(Line::new(PlotPoints::from(cap)).color(color).width(2.0));
+                        }
+                    }
+
+                    let diff = match &gs.paired {
+                        Some(paired) => paired.mean_diff,
+                        None => {
+                            gs.mean
+                                - stats
+                                    .group_stats
+                                    .get(&stats.control_group)
+                                    .map_or(f64::NAN, |c| c.mean)
+                        }
+                    };
+                    if diff.is_finite() {
+                        plot_ui.points(
+                            Points::new(vec![[diff, y]])
+                                .radius(4.0)
+                                .color(color)
+                                .name(group),
+                        );
+                    }
+                }
+            });
+    }
+
     /// Draw Normal Quantile Plot for a chart
     /// X-axis: theoretical Z-scores, Y-axis: sample value at that quantile
     pub fn draw_qq_chart(ui: &mut egui::Ui, chart_data: &ChartData, full_size: bool) {
@@ -220,12 +443,9 @@ impl ChartPlotter {
                 let mut non_control_idx = 0;
 
                 for group in &ordered_groups {
-                    let values = chart_data
-                        .data_by_group
-                        .get(group)
-                        .cloned()
-                        .unwrap_or_default();
-                    if values.is_empty() {
+                    // Sorted values for the quantile plot, outliers included
+                    let series = chart_data.quantile_series(group);
+                    if series.is_empty() {
                         continue;
                     }
 
@@ -234,62 +454,45 @@ impl ChartPlotter {
                         non_control_idx += 1;
                     }
 
-                    // Sort values for quantile plot
-                    let mut sorted = values.clone();
-                    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
-
                     // Calculate Z-scores (theoretical normal quantiles)
                     // Using (i + 0.5) / n formula for probability, then convert to Z
-                    let n = sorted.len();
-                    let points_vec: Vec<[f64; 2]> = sorted
+                    let n = series.len();
+                    let points_vec: Vec<([f64; 2], bool)> = series
                         .iter()
                         .enumerate()
-                        .map(|(i, &val)| {
+                        .map(|(i, &(val, outlier))| {
                             // Probability position
                             let p = (i as f64 + 0.5) / n as f64;
                             // Convert to Z-score
                             let z = Self::normal_ppf(p);
-                            [z, val]
+                            ([z, val], outlier)
                         })
                         .collect();
 
                     plot_ui.line(
-                        Line::new(PlotPoints::from_iter(points_vec.iter().copied()))
+                        Line::new(PlotPoints::from_iter(points_vec.iter().map(|(p, _)| *p)))
                             .color(color)
                             .width(1.5)
                             .name(group),
                     );
 
+                    let (outliers, inliers): (Vec<_>, Vec<_>) =
+                        points_vec.into_iter().partition(|(_, outlier)| *outlier);
                     plot_ui.points(
-                        Points::new(PlotPoints::from_iter(points_vec.iter().copied()))
+                        Points::new(PlotPoints::from_iter(inliers.into_iter().map(|(p, _)| p)))
                             .radius(3.0)
                             .color(color),
                     );
+                    if !outliers.is_empty() {
+                        Self::draw_outliers(
+                            plot_ui,
+                            outliers.into_iter().map(|(p, _)| p).collect(),
+                        );
+                    }
                 }
-            });
-    }
-
-    /// Standard normal CDF (cumulative distribution function)
-    fn normal_cdf(x: f64) -> f64 {
-        0.5 * (1.0 + Self::erf(x / std::f64::consts::SQRT_2))
-    }
-
-    /// Error function approximation
-    fn erf(x: f64) -> f64 {
-        let a1 = 0.254829592;
-        let a2 = -0.284496736;
-        let a3 = 1.421413741;
-        let a4 = -1.453152027;
-        let a5 = 1.061405429;
-        let p = 0.3275911;
 
-        let sign = if x < 0.0 { -1.0 } else { 1.0 };
-        let x = x.abs();
-
-        let t = 1.0 / (1.0 + p * x);
-        let y = 1.0 - (((((a5 * t + a4) * t) + a3) * t + a2) * t + a1) * t * (-x * x).exp();
-
-        sign * y
+                Self::draw_spec_lines(plot_ui, chart_data.stats.spec_limits);
+            });
     }
 
     /// Standard normal quantile function (inverse CDF) - approximation
@@ -305,7 +508,7 @@ impl ChartPlotter {
             -3.969683028665376e+01,
             2.209460984245205e+02,
             -2.759285104469687e+02,
-            1.383577518672690e+02,
+            1.38357751867269e+02,
             -3.066479806614716e+01,
             2.506628277459239e+00,
         ];
@@ -352,6 +555,12 @@ impl ChartPlotter {
 
     /// Draw statistics table
     pub fn draw_stats_table(ui: &mut egui::Ui, stats: &DataTypeStats) {
+        let adjusted = stats.has_adjusted_p_values();
+        let bootstrap = stats.bootstrap_headers();
+        let paired = stats.is_paired();
+        let capability = stats.spec_limits.is_some();
+        let outliers = stats.outlier_filter;
+
         egui::Frame::none()
             .fill(ui.visuals().widgets.noninteractive.bg_fill)
             .rounding(5.0)
@@ -366,13 +575,79 @@ impl ChartPlotter {
                         // Headers
                         ui.label(RichText::new("Group").strong().size(11.0));
                         ui.label(RichText::new("N").strong().size(11.0));
-                        ui.label(RichText::new("Mean").strong().size(11.0));
-                        ui.label(RichText::new("Median").strong().size(11.0));
-                        ui.label(RichText::new("Std").strong().size(11.0));
-                        ui.label(RichText::new("P05").strong().size(11.0));
-                        ui.label(RichText::new("P95").strong().size(11.0));
-                        ui.label(RichText::new("(M-C)/σ").strong().size(11.0));
+                        ui.label(RichText::new("Excl.").strong().size(11.0))
+                            .on_hover_text("Values excluded as null, NaN or unparseable");
+                        if let Some((filter, _)) = outliers {
+                            ui.label(RichText::new("Outl.").strong().size(11.0))
+                                .on_hover_text(if filter.exclude {
+                                    "Outliers left out of N and the statistics"
+                                } else {
+                                    "Outliers marked in the plots, included in N"
+                                });
+                        }
+                        if paired {
+                            ui.label(RichText::new("Pairs").strong().size(11.0))
+                                .on_hover_text("IDs matched with the control");
+                        }
+                        for column in &stats.columns {
+                            ui.label(RichText::new(column.name()).strong().size(11.0))
+                                .on_hover_text(column.description());
+                        }
+                        if capability {
+                            for (header, hover) in [
+                                ("Cp", "Potential capability, within σ from moving ranges"),
+                                ("Cpk", "Capability to the nearest limit, within σ"),
+                                ("Pp", "Potential performance, overall σ"),
+                                ("Ppk", "Performance to the nearest limit, overall σ"),
+                                ("% OOS", "Share of values outside the spec limits"),
+                            ] {
+                                ui.label(RichText::new(header).strong().size(11.0))
+                                    .on_hover_text(hover);
+                            }
+                        }
+                        ui.label(RichText::new("SW p").strong().size(11.0))
+                            .on_hover_text("Shapiro–Wilk normality test (up to 5000 values)");
+                        ui.label(RichText::new("AD p").strong().size(11.0))
+                            .on_hover_text("Anderson–Darling normality test");
+                        ui.label(
+                            RichText::new(stats.effect_size_method.name())
+                                .strong()
+                                .size(11.0),
+                        )
+                        .on_hover_text(format!(
+                            "Effect size versus the control with its {}",
+                            stats.ci_header()
+                        ));
+                        ui.label(RichText::new(stats.ci_header()).strong().size(11.0))
+                            .on_hover_text(
+                                "Confidence interval of the mean minus the control mean",
+                            );
+                        ui.label(RichText::new("σ ratio").strong().size(11.0))
+                            .on_hover_text("Standard deviation over the control's");
+                        ui.label(RichText::new("Var. p").strong().size(11.0))
+                            .on_hover_text(format!(
+                                "{} test of equal variance with the control",
+                                stats.variance_test.name()
+                            ));
+                        if let Some((mean_header, median_header)) = &bootstrap {
+                            ui.label(RichText::new(mean_header).strong().size(11.0))
+                                .on_hover_text(
+                                    "Bootstrap interval of the mean minus the control mean",
+                                );
+                            ui.label(RichText::new(median_header).strong().size(11.0))
+                                .on_hover_text(
+                                    "Bootstrap interval of the median minus the control median",
+                                );
+                        }
+                        ui.label(RichText::new("TOST p").strong().size(11.0))
+                            .on_hover_text(
+                                "Two one-sided tests of |M-C| below the equivalence margin",
+                            );
                         ui.label(RichText::new("P-value").strong().size(11.0));
+                        if adjusted {
+                            ui.label(RichText::new("Adj. P").strong().size(11.0))
+                                .on_hover_text("P-value corrected for multiple testing");
+                        }
                         ui.end_row();
 
                         // Get default text color from theme
@@ -382,48 +657,156 @@ impl ChartPlotter {
                         for group_name in stats.get_ordered_groups() {
                             if let Some(gs) = stats.group_stats.get(&group_name) {
                                 let is_control = group_name == stats.control_group;
-                                let text_color = if is_control {
-                                    CONTROL_COLOR
-                                } else if gs.is_significant {
-                                    Color32::from_rgb(220, 53, 69)
-                                } else {
-                                    default_text_color
+                                let text_color = match gs.outcome {
+                                    _ if is_control => CONTROL_COLOR,
+                                    Outcome::Equivalent => default_text_color,
+                                    outcome => ChartPlotter::outcome_color(outcome),
                                 };
 
                                 ui.label(
                                     RichText::new(&gs.group_name).size(11.0).color(text_color),
                                 );
                                 ui.label(RichText::new(gs.count.to_string()).size(11.0));
-                                ui.label(RichText::new(format!("{:.3}", gs.mean)).size(11.0));
-                                ui.label(RichText::new(format!("{:.3}", gs.median)).size(11.0));
-                                ui.label(RichText::new(format!("{:.3}", gs.std)).size(11.0));
-                                ui.label(RichText::new(format!("{:.3}", gs.p05)).size(11.0));
-                                ui.label(RichText::new(format!("{:.3}", gs.p95)).size(11.0));
-
-                                if let Some(diff) = gs.std_diff_from_control {
-                                    ui.label(RichText::new(format!("{:.3}", diff)).size(11.0));
-                                } else {
-                                    ui.label(RichText::new("-").size(11.0));
+                                ui.label(RichText::new(gs.excluded.to_string()).size(11.0));
+                                if outliers.is_some() {
+                                    ui.label(RichText::new(gs.outliers.to_string()).size(11.0));
                                 }
-
-                                if let Some(p) = gs.p_value {
-                                    let p_color = if gs.is_significant {
-                                        Color32::from_rgb(220, 53, 69)
-                                    } else {
-                                        default_text_color
-                                    };
+                                if paired {
+                                    match &gs.paired {
+                                        Some(paired) if !paired.unmatched.is_empty() => {
+                                            ui.label(
+                                                RichText::new(paired.pairs.to_string()).size(11.0),
+                                            )
+                                            .on_hover_text(format!(
+                                                "{} unmatched IDs: {}",
+                                                paired.unmatched.len(),
+                                                paired.unmatched_label()
+                                            ));
+                                        }
+                                        Some(paired) => {
+                                            ui.label(
+                                                RichText::new(paired.pairs.to_string()).size(11.0),
+                                            );
+                                        }
+                                        None => {
+                                            ui.label(RichText::new("-").size(11.0));
+                                        }
+                                    }
+                                }
+                                for column in &stats.columns {
+                                    let value = column.value(gs);
                                     ui.label(
-                                        RichText::new(format!("{:.4}", p))
-                                            .size(11.0)
-                                            .color(p_color),
+                                        RichText::new(
+                                            value.map_or("-".to_string(), |v| format!("{v:.3}")),
+                                        )
+                                        .size(11.0),
+                                    );
+                                }
+                                if capability {
+                                    let cap = gs.capability;
+                                    for index in [
+                                        cap.and_then(|c| c.cp),
+                                        cap.and_then(|c| c.cpk),
+                                        cap.and_then(|c| c.pp),
+                                        cap.and_then(|c| c.ppk),
+                                    ] {
+                                        ui.label(
+                                            RichText::new(
+                                                index
+                                                    .map_or("-".to_string(), |v| format!("{v:.2}")),
+                                            )
+                                            .size(11.0),
+                                        );
+                                    }
+                                    ui.label(
+                                        RichText::new(cap.map_or("-".to_string(), |c| {
+                                            format!("{:.1}", c.out_of_spec)
+                                        }))
+                                        .size(11.0),
                                     );
+                                }
+                                for (test, symbol) in [
+                                    (gs.normality.shapiro_wilk, "W"),
+                                    (gs.normality.anderson_darling, "A²"),
+                                ] {
+                                    match test {
+                                        Some(test) => {
+                                            ui.label(
+                                                RichText::new(format!("{:.4}", test.p_value))
+                                                    .size(11.0),
+                                            )
+                                            .on_hover_text(format!(
+                                                "{symbol} = {:.4}",
+                                                test.statistic
+                                            ));
+                                        }
+                                        None => {
+                                            ui.label(RichText::new("-").size(11.0));
+                                        }
+                                    }
+                                }
+
+                                ui.label(RichText::new(gs.effect_size_label(3)).size(11.0));
+                                let ci_cell = |ci: Option<(f64, f64)>| match ci {
+                                    Some((low, high)) => {
+                                        RichText::new(format!("[{:.3}, {:.3}]", low, high))
+                                            .size(11.0)
+                                    }
+                                    None => RichText::new("-").size(11.0),
+                                };
+                                ui.label(ci_cell(gs.mean_diff_ci));
+                                let optional = |value: Option<f64>, decimals: usize| {
+                                    RichText::new(
+                                        value
+                                            .map_or("-".to_string(), |v| format!("{v:.decimals$}")),
+                                    )
+                                    .size(11.0)
+                                };
+                                ui.label(optional(gs.sigma_ratio, 3));
+                                ui.label(optional(gs.variance_p, 4));
+                                if bootstrap.is_some() {
+                                    let boot = gs.bootstrap;
+                                    ui.label(ci_cell(boot.and_then(|b| b.mean_diff)));
+                                    ui.label(ci_cell(boot.and_then(|b| b.median_diff)));
+                                }
+
+                                // The p-value significance is decided on is colored
+                                let p_color = if gs.is_significant {
+                                    Color32::from_rgb(220, 53, 69)
                                 } else {
-                                    ui.label(RichText::new("-").size(11.0));
+                                    default_text_color
+                                };
+                                let p_cell = |p: Option<f64>, color: Color32| match p {
+                                    Some(p) => {
+                                        RichText::new(format!("{:.4}", p)).size(11.0).color(color)
+                                    }
+                                    None => RichText::new("-").size(11.0),
+                                };
+                                ui.label(p_cell(gs.tost_p, default_text_color));
+                                if adjusted {
+                                    ui.label(p_cell(gs.p_value, default_text_color));
+                                    ui.label(p_cell(gs.p_adjusted, p_color));
+                                } else {
+                                    ui.label(p_cell(gs.p_value, p_color));
                                 }
                                 ui.end_row();
                             }
                         }
                     });
+
+                for label in [
+                    stats.test_label(),
+                    stats.paired_label(),
+                    stats.outlier_label(),
+                    stats.spec_label(),
+                    stats.equivalence_label(),
+                ]
+                .into_iter()
+                .flatten()
+                {
+                    ui.add_space(4.0);
+                    ui.label(RichText::new(label).size(10.0).weak());
+                }
             });
     }
 }
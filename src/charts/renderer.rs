@@ -2,15 +2,13 @@
 //! Generates SVG vector images matching the dynamic chart layout.
 
 use crate::charts::ChartData;
-use crate::stats::DataTypeStats;
+use crate::stats::{DataTypeStats, Outcome, SpecLimits};
 use plotters::coord::ranged1d::{KeyPointHint, NoDefaultFormatting, Ranged, ValueFormatter};
+use plotters::element::DashedPathElement;
 use plotters::prelude::*;
 use plotters::style::text_anchor::{HPos, Pos, VPos};
 use plotters::style::FontTransform;
-use std::collections::HashMap;
-use std::fs;
 use std::ops::Range;
-use std::path::Path;
 
 /// Custom X-axis range for QQ plot that displays p-values at specific Z positions
 #[derive(Clone)]
@@ -78,6 +76,10 @@ impl ValueFormatter<f64> for ProbabilityAxisRange {
 const CONTROL_COLOR: RGBColor = RGBColor(52, 152, 219); // Blue
 const SIGNIFICANT_COLOR: RGBColor = RGBColor(220, 53, 69); // Red
 const MATCH_COLOR: RGBColor = RGBColor(40, 167, 69); // Green
+const INCONCLUSIVE_COLOR: RGBColor = RGBColor(230, 160, 60); // Amber
+const SPEC_LIMIT_COLOR: RGBColor = RGBColor(192, 57, 43); // Dark red
+const SPEC_TARGET_COLOR: RGBColor = RGBColor(39, 174, 96); // Dark green
+const OUTLIER_COLOR: RGBColor = RGBColor(20, 20, 20); // Near black
 
 /// Color palette for non-control groups
 const PALETTE: [RGBColor; 10] = [
@@ -97,6 +99,15 @@ const PALETTE: [RGBColor; 10] = [
 pub struct ChartRenderer;
 
 impl ChartRenderer {
+    /// Border and title color of an outcome
+    fn outcome_color(outcome: Outcome) -> RGBColor {
+        match outcome {
+            Outcome::Different => SIGNIFICANT_COLOR,
+            Outcome::Inconclusive => INCONCLUSIVE_COLOR,
+            Outcome::Equivalent => MATCH_COLOR,
+        }
+    }
+
     /// Get color for a group
     fn get_group_color(group: &str, control_group: &str, group_index: usize) -> RGBColor {
         if group == control_group {
@@ -106,104 +117,50 @@ impl ChartRenderer {
         }
     }
 
-    /// Render a complete chart card to PNG file (using BitMapBackend for font rotation support)
-    pub fn render_chart_card(
-        chart_data: &ChartData,
-        output_path: &Path,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        use plotters::prelude::BitMapBackend;
-
-        let width = 1400u32;
-        let height = 1000u32;
-
-        let root = BitMapBackend::new(output_path, (width, height)).into_drawing_area();
-        root.fill(&WHITE)?;
-
-        let is_sig = chart_data.stats.has_significant_results();
-        let border_color = if is_sig {
-            SIGNIFICANT_COLOR
-        } else {
-            MATCH_COLOR
+    /// Spec limit and target lines with their labels and colors
+    fn spec_lines(limits: Option<SpecLimits>) -> Vec<(f64, &'static str, RGBColor)> {
+        let Some(limits) = limits else {
+            return Vec::new();
         };
-
-        // Draw border
-        root.draw(&Rectangle::new(
-            [(5, 5), (width as i32 - 5, height as i32 - 5)],
-            border_color.stroke_width(3),
-        ))?;
-
-        // Split into regions
-        let (title_area, rest) = root.split_vertically(60);
-        let (legend_area, rest) = rest.split_vertically(40);
-        let (charts_area, table_area) = rest.split_vertically(550);
-        let (boxplot_area, qq_area) = charts_area.split_horizontally(width / 2);
-
-        // Draw title
-        Self::render_title(&title_area, chart_data, is_sig, border_color)?;
-
-        // Draw legend
-        Self::render_legend(&legend_area, chart_data)?;
-
-        // Draw charts
-        Self::render_boxplot(&boxplot_area, chart_data)?;
-        Self::render_qq_plot(&qq_area, chart_data)?;
-
-        // Draw stats table
-        Self::render_stats_table(&table_area, &chart_data.stats)?;
-
-        root.present()?;
-        Ok(())
+        [
+            (limits.lsl, "LSL", SPEC_LIMIT_COLOR),
+            (limits.target, "Target", SPEC_TARGET_COLOR),
+            (limits.usl, "USL", SPEC_LIMIT_COLOR),
+        ]
+        .into_iter()
+        .filter_map(|(value, name, color)| Some((value?, name, color)))
+        .collect()
     }
 
-    /// Render a complete chart card to PNG file (for PPT embedding)
-    pub fn render_chart_card_png(
-        chart_data: &ChartData,
-        output_path: &Path,
-        width: u32,
-        height: u32,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        use plotters::prelude::BitMapBackend;
-
-        let root = BitMapBackend::new(output_path, (width, height)).into_drawing_area();
-        root.fill(&WHITE)?;
-
-        let is_sig = chart_data.stats.has_significant_results();
-        let border_color = if is_sig {
-            SIGNIFICANT_COLOR
-        } else {
-            MATCH_COLOR
-        };
-
-        // Draw border
-        root.draw(&Rectangle::new(
-            [(3, 3), (width as i32 - 3, height as i32 - 3)],
-            border_color.stroke_width(2),
-        ))?;
-
-        // Split into regions (proportionally scaled)
-        let title_height = (height as f64 * 0.06) as u32;
-        let legend_height = (height as f64 * 0.04) as u32;
-        let charts_height = (height as f64 * 0.55) as u32;
-
-        let (title_area, rest) = root.split_vertically(title_height);
-        let (legend_area, rest) = rest.split_vertically(legend_height);
-        let (charts_area, table_area) = rest.split_vertically(charts_height);
-        let (boxplot_area, qq_area) = charts_area.split_horizontally(width / 2);
-
-        // Draw title
-        Self::render_title(&title_area, chart_data, is_sig, border_color)?;
-
-        // Draw legend
-        Self::render_legend(&legend_area, chart_data)?;
-
-        // Draw charts
-        Self::render_boxplot(&boxplot_area, chart_data)?;
-        Self::render_qq_plot(&qq_area, chart_data)?;
-
-        // Draw stats table
-        Self::render_stats_table(&table_area, &chart_data.stats)?;
-
-        root.present()?;
+    /// Draw dashed horizontal spec lines from `x_start` to `x_end`, labelled at the left
+    fn draw_spec_lines<DB, X, Y>(
+        chart: &mut ChartContext<DB, Cartesian2d<X, Y>>,
+        lines: &[(f64, &'static str, RGBColor)],
+        x_start: f64,
+        x_end: f64,
+    ) -> Result<(), Box<dyn std::error::Error>>
+    where
+        DB: DrawingBackend,
+        DB::ErrorType: 'static,
+        X: Ranged<ValueType = f64>,
+        Y: Ranged<ValueType = f64>,
+    {
+        for &(value, name, color) in lines {
+            chart.draw_series(std::iter::once(DashedPathElement::new(
+                vec![(x_start, value), (x_end, value)],
+                8,
+                6,
+                color.stroke_width(2),
+            )))?;
+            let style = TextStyle::from(("sans-serif", 14).into_font())
+                .color(&color)
+                .pos(Pos::new(HPos::Left, VPos::Bottom));
+            chart.draw_series(std::iter::once(Text::new(
+                name.to_string(),
+                (x_start, value),
+                style,
+            )))?;
+        }
         Ok(())
     }
 
@@ -222,12 +179,8 @@ impl ChartRenderer {
             let root = BitMapBackend::with_buffer(&mut buffer, (width, height)).into_drawing_area();
             root.fill(&WHITE)?;
 
-            let is_sig = chart_data.stats.has_significant_results();
-            let border_color = if is_sig {
-                SIGNIFICANT_COLOR
-            } else {
-                MATCH_COLOR
-            };
+            let outcome = chart_data.stats.outcome();
+            let border_color = Self::outcome_color(outcome);
 
             // Draw border
             root.draw(&Rectangle::new(
@@ -245,7 +198,7 @@ impl ChartRenderer {
             let (charts_area, table_area) = rest.split_vertically(charts_height);
             let (boxplot_area, qq_area) = charts_area.split_horizontally(width / 2);
 
-            Self::render_title(&title_area, chart_data, is_sig, border_color)?;
+            Self::render_title(&title_area, chart_data, outcome, border_color)?;
             Self::render_legend(&legend_area, chart_data)?;
             Self::render_boxplot(&boxplot_area, chart_data)?;
             Self::render_qq_plot(&qq_area, chart_data)?;
@@ -270,13 +223,17 @@ impl ChartRenderer {
     fn render_title<DB: DrawingBackend>(
         area: &DrawingArea<DB, plotters::coord::Shift>,
         chart_data: &ChartData,
-        is_sig: bool,
+        outcome: Outcome,
         color: RGBColor,
     ) -> Result<(), Box<dyn std::error::Error>>
     where
         DB::ErrorType: 'static,
     {
-        let icon = if is_sig { "!" } else { "OK" };
+        let icon = match outcome {
+            Outcome::Different => "!",
+            Outcome::Inconclusive => "?",
+            Outcome::Equivalent => "OK",
+        };
         let title = format!("[{}] Analysis: {}", icon, chart_data.data_type);
 
         area.draw(&Text::new(
@@ -285,6 +242,15 @@ impl ChartRenderer {
             ("sans-serif", 28).into_font().color(&color),
         ))?;
 
+        // Omnibus result right-aligned on the title line
+        if let Some(omnibus) = chart_data.stats.omnibus() {
+            let (width, _) = area.dim_in_pixel();
+            let style = TextStyle::from(("sans-serif", 22).into_font())
+                .color(&RGBColor(100, 100, 100))
+                .pos(Pos::new(HPos::Right, VPos::Top));
+            area.draw(&Text::new(omnibus.label(), (width as i32 - 30, 24), style))?;
+        }
+
         Ok(())
     }
 
@@ -333,7 +299,8 @@ impl ChartRenderer {
         Ok(())
     }
 
-    /// Render boxplot chart
+    /// Render boxplot chart, or in paired mode the differences from the control
+    /// against a zero line
     fn render_boxplot<DB: DrawingBackend>(
         area: &DrawingArea<DB, plotters::coord::Shift>,
         chart_data: &ChartData,
@@ -341,18 +308,30 @@ impl ChartRenderer {
     where
         DB::ErrorType: 'static,
     {
-        let ordered_groups = chart_data.stats.get_ordered_groups();
+        let series = chart_data.distribution_series();
+        let ordered_groups: Vec<String> = series.iter().map(|(group, _)| group.clone()).collect();
         let control_group = &chart_data.stats.control_group;
+        let paired = chart_data.is_paired();
+        let spec_lines = if paired {
+            Vec::new()
+        } else {
+            Self::spec_lines(chart_data.stats.spec_limits)
+        };
 
-        // Calculate y range from data
+        // Calculate y range from data and outliers, keeping zero and the spec lines in view
         let mut all_values: Vec<f64> = Vec::new();
-        for values in chart_data.data_by_group.values() {
+        for (group, values) in &series {
             all_values.extend(values.iter().filter(|v| !v.is_nan()));
+            all_values.extend(chart_data.outliers_of(group));
         }
 
         if all_values.is_empty() {
             return Ok(());
         }
+        if paired {
+            all_values.push(0.0);
+        }
+        all_values.extend(spec_lines.iter().map(|(value, _, _)| *value));
 
         let y_min = all_values.iter().cloned().fold(f64::INFINITY, f64::min);
         let y_max = all_values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
@@ -362,7 +341,7 @@ impl ChartRenderer {
             .margin(20)
             .x_label_area_size(50)
             .y_label_area_size(80)
-            .caption("Distribution by Group", ("sans-serif", 24))
+            .caption(chart_data.distribution_title(), ("sans-serif", 24))
             .build_cartesian_2d(
                 -0.5f64..(ordered_groups.len() as f64 - 0.5),
                 (y_min - y_margin)..(y_max + y_margin),
@@ -375,20 +354,28 @@ impl ChartRenderer {
                 let idx = x.round() as usize;
                 ordered_groups.get(idx).cloned().unwrap_or_default()
             })
-            .y_desc("Value")
+            .y_desc(if paired { "Group − control" } else { "Value" })
             .label_style(("sans-serif", 18))
             .axis_desc_style(("sans-serif", 24))
             .draw()?;
 
+        if paired {
+            chart.draw_series(std::iter::once(PathElement::new(
+                vec![(-0.5, 0.0), (ordered_groups.len() as f64 - 0.5, 0.0)],
+                RGBColor(150, 150, 150).stroke_width(1),
+            )))?;
+        }
+        Self::draw_spec_lines(
+            &mut chart,
+            &spec_lines,
+            -0.5,
+            ordered_groups.len() as f64 - 0.5,
+        )?;
+
         let mut non_ctrl_idx = 0;
         let mut means: Vec<(f64, f64)> = Vec::new();
 
-        for (i, group) in ordered_groups.iter().enumerate() {
-            let values = chart_data
-                .data_by_group
-                .get(group)
-                .cloned()
-                .unwrap_or_default();
+        for (i, (group, values)) in series.iter().enumerate() {
             if values.is_empty() {
                 continue;
             }
@@ -513,10 +500,18 @@ impl ChartRenderer {
                     .iter()
                     .map(|&(px, py)| Circle::new((px, py), 3, color.mix(0.6).filled())),
             )?;
+
+            // Mark outliers with crosses
+            chart.draw_series(
+                chart_data
+                    .outliers_of(group)
+                    .iter()
+                    .map(|&y| Cross::new((x, y), 6, OUTLIER_COLOR.stroke_width(2))),
+            )?;
         }
 
-        // Draw mean line
-        if means.len() > 1 {
+        // Draw mean line; paired differences are read against zero instead
+        if means.len() > 1 && !paired {
             chart.draw_series(std::iter::once(PathElement::new(
                 means.clone(),
                 BLACK.stroke_width(2),
@@ -536,16 +531,19 @@ impl ChartRenderer {
     {
         let ordered_groups = chart_data.stats.get_ordered_groups();
         let control_group = &chart_data.stats.control_group;
+        let spec_lines = Self::spec_lines(chart_data.stats.spec_limits);
 
-        // Calculate y range from data
+        // Calculate y range from data, outliers and the spec lines
         let mut all_values: Vec<f64> = Vec::new();
-        for values in chart_data.data_by_group.values() {
+        for (group, values) in &chart_data.data_by_group {
             all_values.extend(values.iter().filter(|v| !v.is_nan()));
+            all_values.extend(chart_data.outliers_of(group));
         }
 
         if all_values.is_empty() {
             return Ok(());
         }
+        all_values.extend(spec_lines.iter().map(|(value, _, _)| *value));
 
         let y_min = all_values.iter().cloned().fold(f64::INFINITY, f64::min);
         let y_max = all_values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
@@ -572,16 +570,15 @@ impl ChartRenderer {
             .label_style(("sans-serif", 18))
             .axis_desc_style(("sans-serif", 24))
             .draw()?;
+        Self::draw_spec_lines(&mut chart, &spec_lines, -3.0, 3.0)?;
 
         let mut non_ctrl_idx = 0;
 
         for group in &ordered_groups {
-            let values = chart_data
-                .data_by_group
-                .get(group)
-                .cloned()
-                .unwrap_or_default();
-            if values.is_empty() {
+            // Sorted values for the quantile plot, outliers included
+            let series = chart_data.quantile_series(group);
+            let n = series.len();
+            if n == 0 {
                 continue;
             }
 
@@ -590,38 +587,36 @@ impl ChartRenderer {
                 non_ctrl_idx += 1;
             }
 
-            // Sort values for quantile plot
-            let mut sorted: Vec<f64> = values.iter().filter(|v| !v.is_nan()).cloned().collect();
-            sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
-
-            let n = sorted.len();
-            if n == 0 {
-                continue;
-            }
-
             // Calculate theoretical normal quantiles (Z-scores)
-            let points: Vec<(f64, f64)> = sorted
+            let points: Vec<(f64, f64, bool)> = series
                 .iter()
                 .enumerate()
-                .map(|(i, &val)| {
+                .map(|(i, &(val, outlier))| {
                     // Use (i + 0.5) / n for probability, then convert to Z-score
                     let p = (i as f64 + 0.5) / n as f64;
                     let z = Self::probit(p);
-                    (z, val)
+                    (z, val, outlier)
                 })
                 .collect();
 
             // Draw line
             chart.draw_series(std::iter::once(PathElement::new(
-                points.clone(),
+                points.iter().map(|&(x, y, _)| (x, y)).collect::<Vec<_>>(),
                 color.stroke_width(2),
             )))?;
 
-            // Draw points
+            // Draw points, outliers as crosses
+            chart.draw_series(
+                points
+                    .iter()
+                    .filter(|(_, _, outlier)| !outlier)
+                    .map(|&(x, y, _)| Circle::new((x, y), 3, color.filled())),
+            )?;
             chart.draw_series(
                 points
                     .iter()
-                    .map(|&(x, y)| Circle::new((x, y), 3, color.filled())),
+                    .filter(|(_, _, outlier)| *outlier)
+                    .map(|&(x, y, _)| Cross::new((x, y), 6, OUTLIER_COLOR.stroke_width(2))),
             )?;
         }
 
@@ -643,15 +638,45 @@ impl ChartRenderer {
     where
         DB::ErrorType: 'static,
     {
-        let headers = [
-            "Group", "N", "Mean", "Median", "Std", "P05", "P95", "(M-C)/σ", "P-value",
-        ];
+        let adjusted = stats.has_adjusted_p_values();
+        let ci_header = stats.ci_header();
+        let bootstrap = stats.bootstrap_headers();
+        let paired = stats.is_paired();
+        let mut headers = vec!["Group", "N", "Excl."];
+        let outliers = stats.outlier_filter.is_some();
+        if outliers {
+            headers.push("Outl.");
+        }
+        if paired {
+            headers.push("Pairs");
+        }
+        headers.extend(stats.columns.iter().map(|column| column.name()));
+        let capability = stats.spec_limits.is_some();
+        if capability {
+            headers.extend(["Cp", "Cpk", "Pp", "Ppk", "% OOS"]);
+        }
+        headers.extend([
+            "SW p",
+            "AD p",
+            stats.effect_size_method.name(),
+            ci_header.as_str(),
+            "σ ratio",
+            "Var. p",
+        ]);
+        if let Some((mean_header, median_header)) = &bootstrap {
+            headers.extend([mean_header.as_str(), median_header.as_str()]);
+        }
+        headers.push("TOST p");
+        headers.push("P-value");
+        if adjusted {
+            headers.push("Adj. P");
+        }
         let num_cols = headers.len();
         let num_rows = stats.group_stats.len() + 1; // +1 for header
 
-        // Table dimensions - centered with 900px width
-        let table_width = 900i32;
+        // Table dimensions - centered, at most 1000px wide and within the canvas
         let (canvas_width, canvas_height) = area.dim_in_pixel();
+        let table_width = (canvas_width as i32 - 40).min(1000);
         let start_x = (canvas_width as i32 - table_width) / 2; // Center horizontally
 
         // Evenly split column widths
@@ -664,7 +689,8 @@ impl ChartRenderer {
         // Center table vertically in the area
         let start_y = (canvas_height as i32 - table_height) / 2;
 
-        let font_size = 24;
+        // Shrink the text when the columns get narrow
+        let font_size = (col_width / 5).clamp(10, 24);
 
         let light_gray = RGBColor(180, 180, 180);
 
@@ -714,37 +740,82 @@ impl ChartRenderer {
         for group_name in stats.get_ordered_groups() {
             if let Some(gs) = stats.group_stats.get(&group_name) {
                 let is_control = group_name == stats.control_group;
-                let text_color = if is_control {
-                    CONTROL_COLOR
-                } else if gs.is_significant {
-                    SIGNIFICANT_COLOR
-                } else {
-                    BLACK
+                let text_color = match gs.outcome {
+                    _ if is_control => CONTROL_COLOR,
+                    Outcome::Different => SIGNIFICANT_COLOR,
+                    Outcome::Inconclusive => INCONCLUSIVE_COLOR,
+                    Outcome::Equivalent => BLACK,
                 };
 
-                let row_data = [
+                let format_p =
+                    |p: Option<f64>| p.map(|p| format!("{:.4}", p)).unwrap_or("-".to_string());
+                let format_ci = |ci: Option<(f64, f64)>| {
+                    ci.map(|(low, high)| format!("[{:.2}, {:.2}]", low, high))
+                        .unwrap_or("-".to_string())
+                };
+                let mut row_data = vec![
                     gs.group_name.clone(),
                     gs.count.to_string(),
-                    format!("{:.3}", gs.mean),
-                    format!("{:.3}", gs.median),
-                    format!("{:.3}", gs.std),
-                    format!("{:.3}", gs.p05),
-                    format!("{:.3}", gs.p95),
-                    gs.std_diff_from_control
-                        .map(|d| format!("{:.3}", d))
-                        .unwrap_or("-".to_string()),
-                    gs.p_value
-                        .map(|p| format!("{:.4}", p))
-                        .unwrap_or("-".to_string()),
+                    gs.excluded.to_string(),
                 ];
+                if outliers {
+                    row_data.push(gs.outliers.to_string());
+                }
+                if paired {
+                    row_data.push(
+                        gs.paired
+                            .as_ref()
+                            .map_or("-".to_string(), |paired| paired.pairs.to_string()),
+                    );
+                }
+                row_data.extend(stats.columns.iter().map(|column| {
+                    column
+                        .value(gs)
+                        .map_or("-".to_string(), |v| format!("{:.3}", v))
+                }));
+                if capability {
+                    let cap = gs.capability;
+                    let format_index =
+                        |index: Option<f64>| index.map_or("-".to_string(), |v| format!("{v:.2}"));
+                    row_data.extend([
+                        format_index(cap.and_then(|c| c.cp)),
+                        format_index(cap.and_then(|c| c.cpk)),
+                        format_index(cap.and_then(|c| c.pp)),
+                        format_index(cap.and_then(|c| c.ppk)),
+                        cap.map_or("-".to_string(), |c| format!("{:.1}", c.out_of_spec)),
+                    ]);
+                }
+                row_data.extend([
+                    format_p(gs.normality.shapiro_wilk.map(|test| test.p_value)),
+                    format_p(gs.normality.anderson_darling.map(|test| test.p_value)),
+                    gs.effect_size_label(2),
+                    format_ci(gs.mean_diff_ci),
+                    gs.sigma_ratio
+                        .map(|ratio| format!("{:.2}", ratio))
+                        .unwrap_or("-".to_string()),
+                    format_p(gs.variance_p),
+                ]);
+                if bootstrap.is_some() {
+                    row_data.push(format_ci(gs.bootstrap.and_then(|b| b.mean_diff)));
+                    row_data.push(format_ci(gs.bootstrap.and_then(|b| b.median_diff)));
+                }
+                row_data.push(format_p(gs.tost_p));
+                row_data.push(format_p(gs.p_value));
+                if adjusted {
+                    row_data.push(format_p(gs.p_adjusted));
+                }
 
                 // Cell center Y position for this row
                 let cell_center_y = start_y + row_idx * row_height + row_height / 2;
 
                 for (i, value) in row_data.iter().enumerate() {
                     let cell_center_x = start_x + i as i32 * col_width + col_width / 2;
-                    // P-value and group name use text_color, others use black
-                    let color = if i == 0 || i == 8 { text_color } else { BLACK };
+                    // The deciding p-value and group name use text_color, others use black
+                    let color = if i == 0 || i == num_cols - 1 {
+                        text_color
+                    } else {
+                        BLACK
+                    };
 
                     let style = TextStyle::from(("sans-serif", font_size).into_font())
                         .color(&color)
@@ -761,43 +832,25 @@ impl ChartRenderer {
             }
         }
 
-        Ok(())
-    }
-
-    /// Export all charts to tem folder (PNG format)
-    pub fn export_all_charts(
-        chart_data: &HashMap<String, ChartData>,
-        data_type_order: &[String],
-        base_path: &Path,
-    ) -> Result<usize, Box<dyn std::error::Error>> {
-        // Create tem folder
-        let tem_path = base_path.join("tem");
-        fs::create_dir_all(&tem_path)?;
-
-        let width = 1400u32;
-        let height = 1000u32;
-
-        let mut count = 0;
-        for data_type in data_type_order {
-            if let Some(data) = chart_data.get(data_type) {
-                // Sanitize filename
-                let safe_name: String = data_type
-                    .chars()
-                    .map(|c| {
-                        if c.is_alphanumeric() || c == '_' || c == '-' {
-                            c
-                        } else {
-                            '_'
-                        }
-                    })
-                    .collect();
-
-                let file_path = tem_path.join(format!("{}.png", safe_name));
-                Self::render_chart_card_png(data, &file_path, width, height)?;
-                count += 1;
-            }
+        // Test the p-values come from, unmatched pair IDs, the outlier rule, the spec
+        // limits and the equivalence margin, under the table
+        let captions = [
+            stats.test_label(),
+            stats.paired_label(),
+            stats.outlier_label(),
+            stats.spec_label(),
+            stats.equivalence_label(),
+        ];
+        for (line, label) in captions.into_iter().flatten().enumerate() {
+            let style =
+                TextStyle::from(("sans-serif", 18).into_font()).color(&RGBColor(100, 100, 100));
+            area.draw(&Text::new(
+                label,
+                (start_x, start_y + table_height + 10 + 24 * line as i32),
+                style,
+            ))?;
         }
 
-        Ok(count)
+        Ok(())
     }
 }
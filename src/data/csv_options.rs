@@ -0,0 +1,242 @@
+//! CSV Parsing Options Module
+//! Delimiter, decimal separator, quoting, null tokens and encoding for CSV import,
+//! with auto-detection from a sample of the file.
+
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// Bytes read from the start of a file for auto-detection.
+const SAMPLE_BYTES: u64 = 64 * 1024;
+
+/// Lines of the sample inspected for auto-detection.
+const SAMPLE_LINES: usize = 50;
+
+/// Text encoding of a CSV file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TextEncoding {
+    #[default]
+    Utf8,
+    Latin1,
+}
+
+impl TextEncoding {
+    pub const ALL: [TextEncoding; 2] = [TextEncoding::Utf8, TextEncoding::Latin1];
+
+    /// Human readable name for the options dialog.
+    pub fn name(&self) -> &'static str {
+        match self {
+            TextEncoding::Utf8 => "UTF-8",
+            TextEncoding::Latin1 => "Latin-1 (ISO-8859-1)",
+        }
+    }
+
+    /// Decode raw bytes to text.
+    pub fn decode(&self, bytes: &[u8]) -> String {
+        match self {
+            TextEncoding::Utf8 => String::from_utf8_lossy(bytes).into_owned(),
+            // Latin-1 code points map one-to-one onto the first 256 Unicode scalars
+            TextEncoding::Latin1 => bytes.iter().map(|&b| b as char).collect(),
+        }
+    }
+}
+
+/// How a CSV file is parsed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CsvOptions {
+    pub delimiter: u8,
+    /// Numbers use `,` as the decimal separator (e.g. `3,14`).
+    pub decimal_comma: bool,
+    /// Quote character, or `None` to disable quoting.
+    pub quote_char: Option<u8>,
+    /// Cell values read as missing.
+    pub null_values: Vec<String>,
+    pub encoding: TextEncoding,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            decimal_comma: false,
+            quote_char: Some(b'"'),
+            null_values: vec!["NA".to_string(), "N/A".to_string(), "#N/A".to_string()],
+            encoding: TextEncoding::Utf8,
+        }
+    }
+}
+
+impl CsvOptions {
+    /// Delimiters offered in the options dialog and tried by auto-detection.
+    pub const DELIMITERS: [(u8, &'static str); 4] = [
+        (b',', "Comma (,)"),
+        (b';', "Semicolon (;)"),
+        (b'\t', "Tab"),
+        (b'|', "Pipe (|)"),
+    ];
+
+    /// Human readable name of the delimiter.
+    pub fn delimiter_name(&self) -> String {
+        Self::DELIMITERS
+            .iter()
+            .find(|(d, _)| *d == self.delimiter)
+            .map(|(_, name)| name.to_string())
+            .unwrap_or_else(|| format!("'{}'", self.delimiter as char))
+    }
+
+    /// Check the options for combinations the reader cannot handle.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.decimal_comma && self.delimiter == b',' {
+            return Err("Decimal comma needs a delimiter other than comma".to_string());
+        }
+        if self.quote_char == Some(self.delimiter) {
+            return Err("Quote character must differ from the delimiter".to_string());
+        }
+        Ok(())
+    }
+
+    /// Guess the options of a CSV file from a sample of its first lines.
+    pub fn detect(path: &Path) -> std::io::Result<Self> {
+        let mut bytes = Vec::new();
+        File::open(path)?
+            .take(SAMPLE_BYTES)
+            .read_to_end(&mut bytes)?;
+        let truncated = bytes.len() as u64 == SAMPLE_BYTES;
+
+        Ok(Self::detect_from_sample(&bytes, truncated))
+    }
+
+    /// Read the first lines of a file as text for previewing in the options dialog.
+    pub fn sample_lines(path: &Path, encoding: TextEncoding, count: usize) -> Vec<String> {
+        let mut bytes = Vec::new();
+        let read =
+            File::open(path).and_then(|file| file.take(SAMPLE_BYTES).read_to_end(&mut bytes));
+        if read.is_err() {
+            return Vec::new();
+        }
+
+        encoding
+            .decode(&bytes)
+            .lines()
+            .take(count)
+            .map(|line| line.to_string())
+            .collect()
+    }
+
+    /// Guess the options from raw sample bytes.
+    /// `truncated` marks a sample that may end mid-line.
+    fn detect_from_sample(bytes: &[u8], truncated: bool) -> Self {
+        let encoding = match std::str::from_utf8(bytes) {
+            Ok(_) => TextEncoding::Utf8,
+            // An incomplete character at the very end is just a cut-off sample
+            Err(e) if e.error_len().is_none() => TextEncoding::Utf8,
+            Err(_) => TextEncoding::Latin1,
+        };
+
+        let text = encoding.decode(bytes);
+        let mut lines: Vec<&str> = text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .take(SAMPLE_LINES + 1)
+            .collect();
+        if truncated || lines.len() > SAMPLE_LINES {
+            lines.pop();
+        }
+
+        let quote_char = Some(b'"');
+        let delimiter = Self::detect_delimiter(&lines, quote_char);
+        let decimal_comma =
+            delimiter != b',' && Self::detect_decimal_comma(&lines, delimiter, quote_char);
+
+        Self {
+            delimiter,
+            decimal_comma,
+            quote_char,
+            encoding,
+            ..Default::default()
+        }
+    }
+
+    /// Pick the candidate delimiter that splits every line into the same number of fields,
+    /// preferring the one producing the most fields.
+    fn detect_delimiter(lines: &[&str], quote_char: Option<u8>) -> u8 {
+        let Some(header) = lines.first() else {
+            return b',';
+        };
+
+        let mut best: Option<(bool, usize, u8)> = None;
+        for (delimiter, _) in Self::DELIMITERS {
+            let header_count = split_fields(header, delimiter, quote_char).len();
+            if header_count < 2 {
+                continue;
+            }
+            let consistent = lines
+                .iter()
+                .all(|line| split_fields(line, delimiter, quote_char).len() == header_count);
+
+            let candidate = (consistent, header_count, delimiter);
+            if best.is_none_or(|(c, n, _)| (consistent, header_count) > (c, n)) {
+                best = Some(candidate);
+            }
+        }
+
+        best.map(|(_, _, delimiter)| delimiter).unwrap_or(b',')
+    }
+
+    /// Decide whether numeric fields use a decimal comma rather than a decimal point.
+    fn detect_decimal_comma(lines: &[&str], delimiter: u8, quote_char: Option<u8>) -> bool {
+        let mut comma_numbers = 0;
+        let mut point_numbers = 0;
+
+        for line in lines.iter().skip(1) {
+            for field in split_fields(line, delimiter, quote_char) {
+                match decimal_separator(&field) {
+                    Some(',') => comma_numbers += 1,
+                    Some('.') => point_numbers += 1,
+                    _ => {}
+                }
+            }
+        }
+
+        comma_numbers > point_numbers
+    }
+}
+
+/// Split a line into fields, ignoring delimiters inside quotes.
+fn split_fields(line: &str, delimiter: u8, quote_char: Option<u8>) -> Vec<String> {
+    let delimiter = delimiter as char;
+    let quote = quote_char.map(|q| q as char);
+
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+
+    for c in line.chars() {
+        if Some(c) == quote {
+            in_quotes = !in_quotes;
+        } else if c == delimiter && !in_quotes {
+            fields.push(std::mem::take(&mut current));
+        } else {
+            current.push(c);
+        }
+    }
+    fields.push(current);
+
+    fields
+}
+
+/// Return the decimal separator of a field shaped like `-12,5` or `3.14`.
+fn decimal_separator(field: &str) -> Option<char> {
+    let field = field.trim();
+    let digits = field.strip_prefix('-').unwrap_or(field);
+    let sep_pos = digits.find([',', '.'])?;
+    let (int_part, frac_part) = (&digits[..sep_pos], &digits[sep_pos + 1..]);
+
+    let all_digits = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+    if all_digits(int_part) && all_digits(frac_part) {
+        digits[sep_pos..].chars().next()
+    } else {
+        None
+    }
+}
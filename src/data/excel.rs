@@ -0,0 +1,215 @@
+//! Excel Workbook Reader Module
+//! Reads worksheets from Excel workbooks into Polars DataFrames using calamine.
+
+use calamine::{open_workbook_auto, Data, Range, Reader};
+use polars::prelude::*;
+use std::collections::HashSet;
+use std::path::Path;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ExcelError {
+    #[error("Failed to read workbook: {0}")]
+    WorkbookError(#[from] calamine::Error),
+    #[error("Workbook has no sheets")]
+    NoSheets,
+    #[error("Header row {0} is beyond the end of the sheet")]
+    HeaderOutOfRange(usize),
+    #[error("Polars error: {0}")]
+    PolarsError(#[from] PolarsError),
+}
+
+/// Inferred type of a worksheet column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CellKind {
+    Empty,
+    Int,
+    Float,
+    Bool,
+    DateTime,
+    Text,
+}
+
+impl CellKind {
+    fn of(cell: &Data) -> Self {
+        match cell {
+            Data::Empty | Data::Error(_) => CellKind::Empty,
+            Data::Int(_) => CellKind::Int,
+            Data::Float(_) => CellKind::Float,
+            Data::Bool(_) => CellKind::Bool,
+            Data::DateTime(_) | Data::DateTimeIso(_) => CellKind::DateTime,
+            Data::String(s) if s.trim().is_empty() => CellKind::Empty,
+            Data::String(_) | Data::DurationIso(_) => CellKind::Text,
+        }
+    }
+
+    /// Combine the kinds of two cells in the same column.
+    fn merge(self, other: Self) -> Self {
+        match (self, other) {
+            (a, CellKind::Empty) => a,
+            (CellKind::Empty, b) => b,
+            (a, b) if a == b => a,
+            (CellKind::Int, CellKind::Float) | (CellKind::Float, CellKind::Int) => CellKind::Float,
+            _ => CellKind::Text,
+        }
+    }
+}
+
+/// Reads Excel worksheets into DataFrames.
+pub struct ExcelReader;
+
+impl ExcelReader {
+    /// List the worksheet names of a workbook, in workbook order.
+    pub fn list_sheets(path: &Path) -> Result<Vec<String>, ExcelError> {
+        let workbook = open_workbook_auto(path)?;
+        let sheets = workbook.sheet_names();
+        if sheets.is_empty() {
+            return Err(ExcelError::NoSheets);
+        }
+        Ok(sheets)
+    }
+
+    /// Read a worksheet into a DataFrame.
+    ///
+    /// `sheet` defaults to the first sheet. `header_row` is the 1-based sheet row
+    /// holding the column names; rows above it are skipped. A header row above the
+    /// sheet's first non-empty row falls back to that row.
+    pub fn read_sheet(
+        path: &Path,
+        sheet: Option<&str>,
+        header_row: usize,
+    ) -> Result<DataFrame, ExcelError> {
+        let mut workbook = open_workbook_auto(path)?;
+        let sheet_name = match sheet {
+            Some(name) => name.to_string(),
+            None => workbook
+                .sheet_names()
+                .first()
+                .cloned()
+                .ok_or(ExcelError::NoSheets)?,
+        };
+        let range = workbook.worksheet_range(&sheet_name)?;
+
+        Self::range_to_dataframe(&range, header_row)
+    }
+
+    /// Convert a cell range to a DataFrame, inferring one dtype per column.
+    fn range_to_dataframe(range: &Range<Data>, header_row: usize) -> Result<DataFrame, ExcelError> {
+        // The range starts at the first used cell, not at sheet row 1
+        let first_row = range.start().map_or(0, |(row, _)| row as usize);
+        let header_idx = (header_row.max(1) - 1).saturating_sub(first_row);
+        let mut rows = range.rows().skip(header_idx);
+        let header = rows
+            .next()
+            .ok_or(ExcelError::HeaderOutOfRange(header_row))?;
+        let data_rows: Vec<&[Data]> = rows.collect();
+
+        let names = Self::column_names(header);
+
+        let columns = names
+            .into_iter()
+            .enumerate()
+            .map(|(i, name)| {
+                let cells: Vec<&Data> = data_rows
+                    .iter()
+                    .map(|row| row.get(i).unwrap_or(&Data::Empty))
+                    .collect();
+                Self::build_column(name, &cells)
+            })
+            .collect();
+
+        Ok(DataFrame::new(columns)?)
+    }
+
+    /// Build unique, non-empty column names from the header row.
+    fn column_names(header: &[Data]) -> Vec<String> {
+        let mut seen: HashSet<String> = HashSet::new();
+
+        header
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| {
+                let base = match cell {
+                    Data::Empty => format!("column_{}", i + 1),
+                    other => {
+                        let text = other.to_string().trim().to_string();
+                        if text.is_empty() {
+                            format!("column_{}", i + 1)
+                        } else {
+                            text
+                        }
+                    }
+                };
+
+                // Disambiguate duplicate headers with a numeric suffix
+                let mut name = base.clone();
+                let mut suffix = 2;
+                while !seen.insert(name.clone()) {
+                    name = format!("{}_{}", base, suffix);
+                    suffix += 1;
+                }
+                name
+            })
+            .collect()
+    }
+
+    /// Build a typed column from the cells below a header.
+    fn build_column(name: String, cells: &[&Data]) -> Column {
+        let kind = cells
+            .iter()
+            .fold(CellKind::Empty, |acc, cell| acc.merge(CellKind::of(cell)));
+
+        match kind {
+            CellKind::Int => {
+                let values: Vec<Option<i64>> = cells
+                    .iter()
+                    .map(|cell| match cell {
+                        Data::Int(v) => Some(*v),
+                        _ => None,
+                    })
+                    .collect();
+                Column::new(name.into(), values)
+            }
+            CellKind::Float => {
+                let values: Vec<Option<f64>> = cells
+                    .iter()
+                    .map(|cell| match cell {
+                        Data::Int(v) => Some(*v as f64),
+                        Data::Float(v) => Some(*v),
+                        _ => None,
+                    })
+                    .collect();
+                Column::new(name.into(), values)
+            }
+            CellKind::Bool => {
+                let values: Vec<Option<bool>> = cells
+                    .iter()
+                    .map(|cell| match cell {
+                        Data::Bool(v) => Some(*v),
+                        _ => None,
+                    })
+                    .collect();
+                Column::new(name.into(), values)
+            }
+            CellKind::DateTime | CellKind::Text | CellKind::Empty => {
+                let values: Vec<Option<String>> = cells
+                    .iter()
+                    .map(|cell| Self::cell_to_string(cell))
+                    .collect();
+                Column::new(name.into(), values)
+            }
+        }
+    }
+
+    /// Render a cell as text, formatting Excel dates as ISO 8601.
+    fn cell_to_string(cell: &Data) -> Option<String> {
+        match cell {
+            Data::Empty | Data::Error(_) => None,
+            Data::DateTime(dt) => dt
+                .as_datetime()
+                .map(|d| d.format("%Y-%m-%d %H:%M:%S").to_string()),
+            Data::String(s) if s.trim().is_empty() => None,
+            other => Some(other.to_string()),
+        }
+    }
+}
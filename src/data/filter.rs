@@ -0,0 +1,595 @@
+//! Row Filter Module
+//! Conditions built in the control panel, or typed as a text expression such as
+//! `site == "FAB2" and temperature > 25`, compiled to a Polars predicate that
+//! selects the rows to analyse.
+
+use polars::prelude::*;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum FilterError {
+    #[error("Polars error: {0}")]
+    PolarsError(#[from] PolarsError),
+    #[error("Unknown column '{0}'")]
+    UnknownColumn(String),
+    #[error("'{value}' is not a valid value for column '{column}'")]
+    InvalidValue { column: String, value: String },
+    #[error("Syntax error at character {position}: {message}")]
+    Syntax { position: usize, message: String },
+}
+
+/// How the filter is entered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FilterMode {
+    /// Column / operator / value rows
+    #[default]
+    Builder,
+    /// Free-text expression
+    Expression,
+}
+
+/// How a condition joins the conditions before it. AND binds tighter than OR.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Connector {
+    #[default]
+    And,
+    Or,
+}
+
+impl Connector {
+    pub const ALL: [Connector; 2] = [Connector::And, Connector::Or];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Connector::And => "AND",
+            Connector::Or => "OR",
+        }
+    }
+}
+
+/// Comparison of a column against a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FilterOperator {
+    #[default]
+    Eq,
+    NotEq,
+    Gt,
+    GtEq,
+    Lt,
+    LtEq,
+    /// Substring match on the column's text
+    Contains,
+    IsNull,
+    IsNotNull,
+}
+
+impl FilterOperator {
+    pub const ALL: [FilterOperator; 9] = [
+        FilterOperator::Eq,
+        FilterOperator::NotEq,
+        FilterOperator::Gt,
+        FilterOperator::GtEq,
+        FilterOperator::Lt,
+        FilterOperator::LtEq,
+        FilterOperator::Contains,
+        FilterOperator::IsNull,
+        FilterOperator::IsNotNull,
+    ];
+
+    /// Symbol shown in the filter builder.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            FilterOperator::Eq => "==",
+            FilterOperator::NotEq => "!=",
+            FilterOperator::Gt => ">",
+            FilterOperator::GtEq => ">=",
+            FilterOperator::Lt => "<",
+            FilterOperator::LtEq => "<=",
+            FilterOperator::Contains => "contains",
+            FilterOperator::IsNull => "is null",
+            FilterOperator::IsNotNull => "is not null",
+        }
+    }
+
+    /// Whether the operator compares against a value.
+    pub fn takes_value(&self) -> bool {
+        !matches!(self, FilterOperator::IsNull | FilterOperator::IsNotNull)
+    }
+}
+
+/// One row of the filter builder.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FilterCondition {
+    /// Ignored for the first condition
+    pub connector: Connector,
+    pub column: String,
+    pub operator: FilterOperator,
+    pub value: String,
+}
+
+impl FilterCondition {
+    fn to_expr(&self, schema: &Schema) -> Result<Expr, FilterError> {
+        let dtype = schema
+            .get(self.column.as_str())
+            .ok_or_else(|| FilterError::UnknownColumn(self.column.clone()))?;
+        let column = col(self.column.as_str());
+        let value = || literal_for(&self.column, dtype, self.value.trim());
+
+        Ok(match self.operator {
+            FilterOperator::Eq => column.eq(value()?),
+            FilterOperator::NotEq => column.neq(value()?),
+            FilterOperator::Gt => column.gt(value()?),
+            FilterOperator::GtEq => column.gt_eq(value()?),
+            FilterOperator::Lt => column.lt(value()?),
+            FilterOperator::LtEq => column.lt_eq(value()?),
+            FilterOperator::Contains => column
+                .cast(DataType::String)
+                .str()
+                .count_matches(lit(self.value.clone()), true)
+                .gt(lit(0)),
+            FilterOperator::IsNull => column.is_null(),
+            FilterOperator::IsNotNull => column.is_not_null(),
+        })
+    }
+}
+
+/// Rows to keep for analysis.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RowFilter {
+    pub mode: FilterMode,
+    pub conditions: Vec<FilterCondition>,
+    pub expression: String,
+}
+
+impl RowFilter {
+    /// Whether the filter keeps every row.
+    pub fn is_empty(&self) -> bool {
+        match self.mode {
+            FilterMode::Builder => self.conditions.iter().all(|c| c.column.is_empty()),
+            FilterMode::Expression => self.expression.trim().is_empty(),
+        }
+    }
+
+    /// Compile the filter to a predicate over data with the given schema, or `None`
+    /// when it keeps every row. Builder rows without a column are skipped.
+    pub fn to_expr(&self, schema: &Schema) -> Result<Option<Expr>, FilterError> {
+        if self.is_empty() {
+            return Ok(None);
+        }
+
+        match self.mode {
+            FilterMode::Builder => {
+                // OR of AND-chains, so AND binds tighter as in the expression syntax
+                let mut chains: Vec<Expr> = Vec::new();
+                let mut current: Option<Expr> = None;
+                for condition in self.conditions.iter().filter(|c| !c.column.is_empty()) {
+                    let expr = condition.to_expr(schema)?;
+                    current = Some(match (current, condition.connector) {
+                        (None, _) => expr,
+                        (Some(chain), Connector::And) => chain.and(expr),
+                        (Some(chain), Connector::Or) => {
+                            chains.push(chain);
+                            expr
+                        }
+                    });
+                }
+                chains.extend(current);
+                Ok(chains.into_iter().reduce(Expr::or))
+            }
+            FilterMode::Expression => Parser::new(&self.expression, schema)?.parse().map(Some),
+        }
+    }
+
+    /// Keep only the rows matching the filter.
+    pub fn apply(&self, lazy: LazyFrame) -> Result<LazyFrame, FilterError> {
+        if self.is_empty() {
+            return Ok(lazy);
+        }
+        let schema = lazy.clone().collect_schema()?;
+        Ok(match self.to_expr(&schema)? {
+            Some(predicate) => lazy.filter(predicate),
+            None => lazy,
+        })
+    }
+}
+
+/// Literal of the column's dtype parsed from user text.
+fn literal_for(column: &str, dtype: &DataType, text: &str) -> Result<Expr, FilterError> {
+    let invalid = || FilterError::InvalidValue {
+        column: column.to_string(),
+        value: text.to_string(),
+    };
+
+    if dtype.is_primitive_numeric() {
+        text.parse::<f64>().map(lit).map_err(|_| invalid())
+    } else if *dtype == DataType::Boolean {
+        match text.to_ascii_lowercase().as_str() {
+            "true" => Ok(lit(true)),
+            "false" => Ok(lit(false)),
+            _ => Err(invalid()),
+        }
+    } else if dtype.is_temporal() {
+        // Check the text parses now rather than failing when the query runs
+        if Series::new(PlSmallStr::EMPTY, [text])
+            .strict_cast(dtype)
+            .is_err()
+        {
+            return Err(invalid());
+        }
+        Ok(lit(text.to_string()).strict_cast(dtype.clone()))
+    } else {
+        Ok(lit(text.to_string()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// Bare word: a keyword or a column name
+    Word(String),
+    /// Column name quoted with backticks
+    Column(String),
+    Str(String),
+    Number(f64),
+    Op(&'static str),
+    LParen,
+    RParen,
+}
+
+/// Operators, longest first so that `<=` is not read as `<`.
+const OPERATORS: [&str; 14] = [
+    "==", "!=", "<=", ">=", "&&", "||", "=", "<", ">", "!", "+", "-", "*", "/",
+];
+
+/// Split an expression into tokens with their (1-based) character positions.
+fn tokenize(text: &str) -> Result<Vec<(usize, Token)>, FilterError> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        let token = if c == '"' || c == '\'' || c == '`' {
+            let end = chars[i + 1..]
+                .iter()
+                .position(|&d| d == c)
+                .map(|offset| i + 1 + offset)
+                .ok_or(FilterError::Syntax {
+                    position: start + 1,
+                    message: format!("unterminated {}", c),
+                })?;
+            let content: String = chars[i + 1..end].iter().collect();
+            i = end + 1;
+            if c == '`' {
+                Token::Column(content)
+            } else {
+                Token::Str(content)
+            }
+        } else if c.is_ascii_digit()
+            || (c == '.' && chars.get(i + 1).is_some_and(char::is_ascii_digit))
+        {
+            while i < chars.len()
+                && (chars[i].is_ascii_alphanumeric()
+                    || chars[i] == '.'
+                    || ((chars[i] == '+' || chars[i] == '-') && matches!(chars[i - 1], 'e' | 'E')))
+            {
+                i += 1;
+            }
+            let number: String = chars[start..i].iter().collect();
+            Token::Number(number.parse().map_err(|_| FilterError::Syntax {
+                position: start + 1,
+                message: format!("invalid number '{}'", number),
+            })?)
+        } else if c.is_alphanumeric() || c == '_' {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            Token::Word(chars[start..i].iter().collect())
+        } else if c == '(' {
+            i += 1;
+            Token::LParen
+        } else if c == ')' {
+            i += 1;
+            Token::RParen
+        } else {
+            let rest: String = chars[i..chars.len().min(i + 2)].iter().collect();
+            let op =
+                OPERATORS
+                    .iter()
+                    .find(|op| rest.starts_with(**op))
+                    .ok_or(FilterError::Syntax {
+                        position: start + 1,
+                        message: format!("unexpected '{}'", c),
+                    })?;
+            i += op.len();
+            Token::Op(op)
+        };
+        tokens.push((start + 1, token));
+    }
+
+    Ok(tokens)
+}
+
+/// Parsed operand; columns and strings are kept apart until a comparison so that
+/// string literals can be cast to the column's dtype.
+enum Operand {
+    Column(String),
+    Str(String),
+    Expr(Expr),
+}
+
+impl Operand {
+    fn into_expr(self) -> Expr {
+        match self {
+            Operand::Column(name) => col(name.as_str()),
+            Operand::Str(text) => lit(text),
+            Operand::Expr(expr) => expr,
+        }
+    }
+}
+
+/// Recursive descent parser for filter expressions:
+///
+/// ```text
+/// or         := and (("or" | "||") and)*
+/// and        := not (("and" | "&&") not)*
+/// not        := ("not" | "!") not | comparison
+/// comparison := sum (("==" | "=" | "!=" | "<" | "<=" | ">" | ">=" | "contains") sum
+///               | "is" ["not"] "null")?
+/// sum        := product (("+" | "-") product)*
+/// product    := unary (("*" | "/") unary)*
+/// unary      := "-" unary | primary
+/// primary    := number | string | column | "true" | "false" | "null" | "(" or ")"
+/// ```
+struct Parser<'a> {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    /// Position reported for errors at the end of the input
+    end: usize,
+    schema: &'a Schema,
+}
+
+impl<'a> Parser<'a> {
+    fn new(text: &str, schema: &'a Schema) -> Result<Self, FilterError> {
+        Ok(Self {
+            tokens: tokenize(text)?,
+            pos: 0,
+            end: text.chars().count() + 1,
+            schema,
+        })
+    }
+
+    fn parse(mut self) -> Result<Expr, FilterError> {
+        let expr = self.parse_or()?.into_expr();
+        match self.tokens.get(self.pos) {
+            None => Ok(expr),
+            Some((position, token)) => Err(FilterError::Syntax {
+                position: *position,
+                message: format!("unexpected {}", describe(token)),
+            }),
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(_, token)| token)
+    }
+
+    fn error(&self, message: &str) -> FilterError {
+        FilterError::Syntax {
+            position: self
+                .tokens
+                .get(self.pos)
+                .map_or(self.end, |(position, _)| *position),
+            message: message.to_string(),
+        }
+    }
+
+    /// Consume the next token if it is one of the given keywords or operators.
+    fn accept(&mut self, words: &[&str]) -> bool {
+        let matched = match self.peek() {
+            Some(Token::Word(word)) => words.iter().any(|w| word.eq_ignore_ascii_case(w)),
+            Some(Token::Op(op)) => words.contains(op),
+            _ => false,
+        };
+        if matched {
+            self.pos += 1;
+        }
+        matched
+    }
+
+    fn parse_or(&mut self) -> Result<Operand, FilterError> {
+        let mut left = self.parse_and()?;
+        while self.accept(&["or", "||"]) {
+            let right = self.parse_and()?;
+            left = Operand::Expr(left.into_expr().or(right.into_expr()));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Operand, FilterError> {
+        let mut left = self.parse_not()?;
+        while self.accept(&["and", "&&"]) {
+            let right = self.parse_not()?;
+            left = Operand::Expr(left.into_expr().and(right.into_expr()));
+        }
+        Ok(left)
+    }
+
+    fn parse_not(&mut self) -> Result<Operand, FilterError> {
+        if self.accept(&["not", "!"]) {
+            let operand = self.parse_not()?;
+            return Ok(Operand::Expr(operand.into_expr().not()));
+        }
+        self.parse_comparison()
+    }
+
+    fn parse_comparison(&mut self) -> Result<Operand, FilterError> {
+        let left = self.parse_sum()?;
+
+        if self.accept(&["is"]) {
+            let negated = self.accept(&["not"]);
+            if !self.accept(&["null"]) {
+                return Err(self.error("expected 'null'"));
+            }
+            let expr = left.into_expr();
+            return Ok(Operand::Expr(if negated {
+                expr.is_not_null()
+            } else {
+                expr.is_null()
+            }));
+        }
+
+        if self.accept(&["contains"]) {
+            let Operand::Str(pattern) = self.parse_sum()? else {
+                return Err(self.error("expected a string after 'contains'"));
+            };
+            return Ok(Operand::Expr(
+                left.into_expr()
+                    .cast(DataType::String)
+                    .str()
+                    .count_matches(lit(pattern), true)
+                    .gt(lit(0)),
+            ));
+        }
+
+        let op = match self.peek() {
+            Some(Token::Op(op @ ("==" | "=" | "!=" | "<" | "<=" | ">" | ">="))) => *op,
+            _ => return Ok(left),
+        };
+        self.pos += 1;
+        let right = self.parse_sum()?;
+
+        // A string compared with a column is read as a value of the column's dtype
+        let left_column = self.column_dtype(&left);
+        let right_column = self.column_dtype(&right);
+        let left = Self::coerce(left, right_column)?;
+        let right = Self::coerce(right, left_column)?;
+
+        Ok(Operand::Expr(match op {
+            "==" | "=" => left.eq(right),
+            "!=" => left.neq(right),
+            "<" => left.lt(right),
+            "<=" => left.lt_eq(right),
+            ">" => left.gt(right),
+            _ => left.gt_eq(right),
+        }))
+    }
+
+    fn parse_sum(&mut self) -> Result<Operand, FilterError> {
+        let mut left = self.parse_product()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Op(op @ ("+" | "-"))) => *op,
+                _ => return Ok(left),
+            };
+            self.pos += 1;
+            let right = self.parse_product()?.into_expr();
+            let left_expr = left.into_expr();
+            left = Operand::Expr(if op == "+" {
+                left_expr + right
+            } else {
+                left_expr - right
+            });
+        }
+    }
+
+    fn parse_product(&mut self) -> Result<Operand, FilterError> {
+        let mut left = self.parse_unary()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Op(op @ ("*" | "/"))) => *op,
+                _ => return Ok(left),
+            };
+            self.pos += 1;
+            let right = self.parse_unary()?.into_expr();
+            let left_expr = left.into_expr();
+            left = Operand::Expr(if op == "*" {
+                left_expr * right
+            } else {
+                left_expr / right
+            });
+        }
+    }
+
+    fn parse_unary(&mut self) -> Result<Operand, FilterError> {
+        if self.accept(&["-"]) {
+            let operand = self.parse_unary()?.into_expr();
+            return Ok(Operand::Expr(lit(0.0) - operand));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Operand, FilterError> {
+        let Some((_, token)) = self.tokens.get(self.pos).cloned() else {
+            return Err(self.error("unexpected end of expression"));
+        };
+
+        let operand = match token {
+            Token::Number(value) => Operand::Expr(lit(value)),
+            Token::Str(text) => Operand::Str(text),
+            Token::Column(name) => self.column(name)?,
+            Token::Word(word) => match word.to_ascii_lowercase().as_str() {
+                "true" => Operand::Expr(lit(true)),
+                "false" => Operand::Expr(lit(false)),
+                "null" => Operand::Expr(lit(NULL)),
+                _ => self.column(word)?,
+            },
+            Token::LParen => {
+                self.pos += 1;
+                let inner = self.parse_or()?;
+                if self.peek() != Some(&Token::RParen) {
+                    return Err(self.error("expected ')'"));
+                }
+                inner
+            }
+            Token::RParen | Token::Op(_) => {
+                return Err(self.error(&format!("unexpected {}", describe(&token))));
+            }
+        };
+        self.pos += 1;
+        Ok(operand)
+    }
+
+    fn column(&self, name: String) -> Result<Operand, FilterError> {
+        if self.schema.contains(name.as_str()) {
+            Ok(Operand::Column(name))
+        } else {
+            Err(FilterError::UnknownColumn(name))
+        }
+    }
+
+    fn column_dtype(&self, operand: &Operand) -> Option<(String, DataType)> {
+        match operand {
+            Operand::Column(name) => self
+                .schema
+                .get(name.as_str())
+                .map(|dtype| (name.clone(), dtype.clone())),
+            _ => None,
+        }
+    }
+
+    fn coerce(operand: Operand, other: Option<(String, DataType)>) -> Result<Expr, FilterError> {
+        match (operand, other) {
+            (Operand::Str(text), Some((column, dtype))) => literal_for(&column, &dtype, &text),
+            (operand, _) => Ok(operand.into_expr()),
+        }
+    }
+}
+
+/// Token as named in syntax errors.
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Word(word) => format!("'{}'", word),
+        Token::Column(name) => format!("`{}`", name),
+        Token::Str(text) => format!("\"{}\"", text),
+        Token::Number(value) => value.to_string(),
+        Token::Op(op) => format!("'{}'", op),
+        Token::LParen => "'('".to_string(),
+        Token::RParen => "')'".to_string(),
+    }
+}
@@ -1,22 +1,195 @@
-//! CSV Data Loader Module
-//! Handles CSV file loading and column extraction using Polars.
+//! Data Loader Module
+//! Handles CSV, Parquet, Arrow IPC and Excel file loading and column extraction using Polars.
 
+use super::csv_options::{CsvOptions, TextEncoding};
+use super::excel::{ExcelError, ExcelReader};
+use super::schema::{ColumnRole, ColumnSchema};
 use polars::prelude::*;
-use std::path::PathBuf;
+use std::fs::File;
+use std::io::{Cursor, Read};
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum LoaderError {
-    #[error("Failed to load CSV: {0}")]
-    CsvError(#[from] PolarsError),
+    #[error("Failed to load file: {0}")]
+    ReadError(#[from] PolarsError),
+    #[error("{0}")]
+    ExcelError(#[from] ExcelError),
+    #[error("Invalid file pattern: {0}")]
+    PatternError(#[from] glob::PatternError),
+    #[error("No files match '{0}'")]
+    NoMatches(String),
+    #[error("Failed to read file: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Invalid CSV options: {0}")]
+    InvalidCsvOptions(String),
+    #[error("{0} files are not supported; re-save them as Arrow IPC (Feather v2)")]
+    Unsupported(&'static str),
     #[error("No data loaded")]
     NoData,
 }
 
-/// Handles CSV file loading with Polars for high performance.
+/// Supported input file formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileFormat {
+    Csv,
+    Parquet,
+    Ipc,
+    /// Arrow IPC streaming format, which has no footer to scan from
+    IpcStream,
+    /// Legacy Feather v1, recognised only to report it as unsupported
+    FeatherV1,
+    Excel,
+}
+
+impl FileFormat {
+    /// File extensions offered in the file dialog.
+    pub const CSV_EXTENSIONS: &'static [&'static str] = &["csv", "txt"];
+    pub const PARQUET_EXTENSIONS: &'static [&'static str] = &["parquet", "pq"];
+    pub const IPC_EXTENSIONS: &'static [&'static str] = &["arrow", "ipc", "feather"];
+    pub const IPC_STREAM_EXTENSIONS: &'static [&'static str] = &["arrows"];
+    pub const EXCEL_EXTENSIONS: &'static [&'static str] = &["xlsx", "xlsm"];
+
+    /// All extensions the loader understands.
+    pub fn all_extensions() -> Vec<&'static str> {
+        Self::CSV_EXTENSIONS
+            .iter()
+            .chain(Self::PARQUET_EXTENSIONS)
+            .chain(Self::IPC_EXTENSIONS)
+            .chain(Self::IPC_STREAM_EXTENSIONS)
+            .chain(Self::EXCEL_EXTENSIONS)
+            .copied()
+            .collect()
+    }
+
+    /// Detect the format of a file.
+    /// A CSV extension is trusted as is, since a first header cell such as "PAR1" can
+    /// look like magic bytes. Otherwise magic bytes take precedence and the extension
+    /// is used when the header is inconclusive.
+    pub fn detect(path: &Path) -> Self {
+        let by_extension = Self::from_extension(path);
+        if by_extension == Some(FileFormat::Csv) {
+            return FileFormat::Csv;
+        }
+
+        let mut header = [0u8; 8];
+        let header_len = File::open(path)
+            .and_then(|mut file| file.read(&mut header))
+            .unwrap_or(0);
+
+        Self::from_magic(&header[..header_len])
+            .or(by_extension)
+            .unwrap_or(FileFormat::Csv)
+    }
+
+    /// Identify binary formats from their leading magic bytes.
+    fn from_magic(header: &[u8]) -> Option<Self> {
+        if header.starts_with(b"PAR1") {
+            Some(FileFormat::Parquet)
+        } else if header.starts_with(b"ARROW1") {
+            Some(FileFormat::Ipc)
+        } else if header.starts_with(&[0xFF; 4]) {
+            // Continuation marker opening each message of an IPC stream
+            Some(FileFormat::IpcStream)
+        } else if header.starts_with(b"FEA1") {
+            Some(FileFormat::FeatherV1)
+        } else if header.starts_with(b"PK\x03\x04") {
+            // Zip container - the only zipped format we read is an Excel workbook
+            Some(FileFormat::Excel)
+        } else {
+            None
+        }
+    }
+
+    /// Identify the format from the file extension (case-insensitive).
+    fn from_extension(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_string_lossy().to_lowercase();
+        if Self::PARQUET_EXTENSIONS.contains(&ext.as_str()) {
+            Some(FileFormat::Parquet)
+        } else if Self::IPC_EXTENSIONS.contains(&ext.as_str()) {
+            Some(FileFormat::Ipc)
+        } else if Self::IPC_STREAM_EXTENSIONS.contains(&ext.as_str()) {
+            // Streams written before the continuation marker have no magic bytes
+            Some(FileFormat::IpcStream)
+        } else if Self::EXCEL_EXTENSIONS.contains(&ext.as_str()) {
+            Some(FileFormat::Excel)
+        } else if Self::CSV_EXTENSIONS.contains(&ext.as_str()) {
+            Some(FileFormat::Csv)
+        } else {
+            None
+        }
+    }
+
+    /// Human readable name for status messages.
+    pub fn name(&self) -> &'static str {
+        match self {
+            FileFormat::Csv => "CSV",
+            FileFormat::Parquet => "Parquet",
+            FileFormat::Ipc => "Arrow IPC",
+            FileFormat::IpcStream => "Arrow IPC stream",
+            FileFormat::FeatherV1 => "Feather v1",
+            FileFormat::Excel => "Excel",
+        }
+    }
+}
+
+/// Options controlling how a data file is read.
+#[derive(Debug, Clone)]
+pub struct LoadOptions {
+    /// Worksheet to read from an Excel workbook (first sheet when `None`).
+    pub sheet: Option<String>,
+    /// 1-based worksheet row holding the column names.
+    pub header_row: usize,
+    /// Parsing options for CSV files.
+    pub csv: CsvOptions,
+}
+
+impl Default for LoadOptions {
+    fn default() -> Self {
+        Self {
+            sheet: None,
+            header_row: 1,
+            csv: CsvOptions::default(),
+        }
+    }
+}
+
+/// Name of the synthetic column recording which file each row came from.
+pub const SOURCE_FILE_COLUMN: &str = "__source_file";
+
+/// Name of the hidden column numbering the rows of a streaming scan. It is attached
+/// directly on each file scan, where the streaming engine can still produce it.
+pub const ROW_ID_COLUMN: &str = "__row_id";
+
+/// Several data files concatenated into one DataFrame.
+pub struct MultiFileLoad {
+    pub df: DataFrame,
+    /// Schema differences found while aligning the files.
+    pub warnings: Vec<String>,
+}
+
+/// Lazy scan of several data files, stacked like `MultiFileLoad`.
+pub struct MultiFileScan {
+    pub lazy: LazyFrame,
+    /// Schema differences found while aligning the files.
+    pub warnings: Vec<String>,
+}
+
+/// Data scanned from disk for streaming analysis instead of being held in memory.
+struct StreamingSource {
+    scan: LazyFrame,
+    row_count: usize,
+}
+
+/// Handles data file loading with Polars for high performance.
 pub struct DataLoader {
+    /// Loaded data; only the first rows in streaming mode
     df: Option<DataFrame>,
-    file_path: Option<PathBuf>,
+    /// DataFrame as read from the file, before column roles are applied
+    source_df: Option<DataFrame>,
+    schema: Vec<ColumnSchema>,
+    streaming: Option<StreamingSource>,
 }
 
 impl Default for DataLoader {
@@ -29,74 +202,257 @@ impl DataLoader {
     pub fn new() -> Self {
         Self {
             df: None,
-            file_path: None,
+            source_df: None,
+            schema: Vec::new(),
+            streaming: None,
         }
     }
 
-    /// Load a CSV file using Polars.
-    pub fn load_csv(&mut self, file_path: &str) -> Result<&DataFrame, LoaderError> {
-        self.file_path = Some(PathBuf::from(file_path));
+    /// Build a lazy scan of a data file, dispatching on the detected format.
+    pub fn scan_file(path: &Path, options: &LoadOptions) -> Result<LazyFrame, LoaderError> {
+        let lazy = match FileFormat::detect(path) {
+            FileFormat::Csv => return Self::scan_csv(path, &options.csv),
+            FileFormat::Parquet => LazyFrame::scan_parquet(path, ScanArgsParquet::default()),
+            FileFormat::Ipc => LazyFrame::scan_ipc(path, ScanArgsIpc::default()),
+            // Streams can only be read front to back; read them and wrap the result
+            FileFormat::IpcStream => IpcStreamReader::new(File::open(path)?)
+                .finish()
+                .map(DataFrame::lazy),
+            FileFormat::FeatherV1 => return Err(LoaderError::Unsupported("Feather v1")),
+            // Workbooks cannot be scanned lazily; read the sheet and wrap it
+            FileFormat::Excel => {
+                Ok(
+                    ExcelReader::read_sheet(path, options.sheet.as_deref(), options.header_row)?
+                        .lazy(),
+                )
+            }
+        }?;
+        Ok(lazy)
+    }
+
+    /// Build a lazy scan of a CSV file with the given parsing options.
+    fn scan_csv(path: &Path, csv: &CsvOptions) -> Result<LazyFrame, LoaderError> {
+        csv.validate().map_err(LoaderError::InvalidCsvOptions)?;
+
+        let null_values = (!csv.null_values.is_empty()).then(|| {
+            NullValues::AllColumns(csv.null_values.iter().map(|s| s.as_str().into()).collect())
+        });
 
+        match csv.encoding {
+            TextEncoding::Utf8 => Ok(LazyCsvReader::new(path)
+                .with_infer_schema_length(Some(10000))
+                .with_ignore_errors(true)
+                .with_separator(csv.delimiter)
+                .with_decimal_comma(csv.decimal_comma)
+                .with_quote_char(csv.quote_char)
+                .with_null_values(null_values)
+                .finish()?),
+            // Polars only reads UTF-8, so transcode the file in memory first
+            TextEncoding::Latin1 => {
+                let bytes = std::fs::read(path)?;
+                let text = csv.encoding.decode(&bytes);
+                let df = CsvReadOptions::default()
+                    .with_infer_schema_length(Some(10000))
+                    .with_ignore_errors(true)
+                    .map_parse_options(|parse| {
+                        parse
+                            .with_separator(csv.delimiter)
+                            .with_decimal_comma(csv.decimal_comma)
+                            .with_quote_char(csv.quote_char)
+                            .with_null_values(null_values.clone())
+                    })
+                    .into_reader_with_file_handle(Cursor::new(text.into_bytes()))
+                    .finish()?;
+                Ok(df.lazy())
+            }
+        }
+    }
+
+    /// Read a data file fully into memory (used by the background loader).
+    pub fn read_file(path: &Path, options: &LoadOptions) -> Result<DataFrame, LoaderError> {
         // Use lazy evaluation for memory efficiency, then collect
-        let df = LazyCsvReader::new(file_path)
-            .with_infer_schema_length(Some(10000))
-            .with_ignore_errors(true)
-            .finish()?
-            .collect()?;
+        Ok(Self::scan_file(path, options)?.collect()?)
+    }
 
-        self.df = Some(df);
-        self.df.as_ref().ok_or(LoaderError::NoData)
+    /// Expand a glob pattern into the matching files, sorted by path.
+    pub fn expand_glob(pattern: &str) -> Result<Vec<PathBuf>, LoaderError> {
+        let mut paths: Vec<PathBuf> = glob::glob(pattern)?
+            .filter_map(Result::ok)
+            .filter(|path| path.is_file())
+            .collect();
+        if paths.is_empty() {
+            return Err(LoaderError::NoMatches(pattern.to_string()));
+        }
+        paths.sort();
+        Ok(paths)
     }
 
-    /// Get list of column names from loaded DataFrame.
-    pub fn get_columns(&self) -> Vec<String> {
-        self.df
-            .as_ref()
-            .map(|df| {
-                df.get_column_names()
-                    .iter()
-                    .map(|s| s.to_string())
-                    .collect()
-            })
-            .unwrap_or_default()
+    /// Read several data files and stack them vertically.
+    ///
+    /// Columns are aligned by name (missing columns become nulls, differing dtypes are
+    /// cast to a common supertype) and a `__source_file` column records each row's file.
+    pub fn read_files(
+        paths: &[PathBuf],
+        options: &LoadOptions,
+    ) -> Result<MultiFileLoad, LoaderError> {
+        let scan = Self::scan_files(paths, options)?;
+        Ok(MultiFileLoad {
+            df: scan.lazy.collect()?,
+            warnings: scan.warnings,
+        })
     }
 
-    /// Get list of numeric column names.
-    pub fn get_numeric_columns(&self) -> Vec<String> {
-        let Some(df) = &self.df else {
-            return Vec::new();
-        };
+    /// Build a lazy scan stacking several data files, aligned as in `read_files`.
+    pub fn scan_files(
+        paths: &[PathBuf],
+        options: &LoadOptions,
+    ) -> Result<MultiFileScan, LoaderError> {
+        Self::stack_scans(paths, options, false)
+    }
+
+    /// Build a lazy scan of one or more files for streaming analysis, with a
+    /// `__row_id` column unique across files (file index in the upper 32 bits).
+    pub fn scan_for_streaming(
+        paths: &[PathBuf],
+        options: &LoadOptions,
+    ) -> Result<MultiFileScan, LoaderError> {
+        match paths {
+            [path] => Ok(MultiFileScan {
+                lazy: Self::scan_file(path, options)?
+                    .with_row_index(ROW_ID_COLUMN, None)
+                    .with_column(col(ROW_ID_COLUMN).cast(DataType::UInt64)),
+                warnings: Vec::new(),
+            }),
+            _ => Self::stack_scans(paths, options, true),
+        }
+    }
+
+    /// Align and concatenate file scans, optionally numbering the rows of each file.
+    fn stack_scans(
+        paths: &[PathBuf],
+        options: &LoadOptions,
+        row_ids: bool,
+    ) -> Result<MultiFileScan, LoaderError> {
+        let mut scans = Vec::with_capacity(paths.len());
+        for path in paths {
+            let file_name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.display().to_string());
+            let mut lazy = Self::scan_file(path, options)?;
+            // Resolve the schema on a copy; a cached schema keeps the row index
+            // from being pushed into the scan, which the streaming engine needs
+            let schema = lazy.clone().collect_schema()?;
+            if row_ids {
+                lazy = lazy.with_row_index(ROW_ID_COLUMN, None);
+            }
+            scans.push((file_name, lazy, schema));
+        }
+
+        // Union of all columns, in order of first appearance
+        let mut all_columns: Vec<(String, DataType)> = Vec::new();
+        for (_, _, schema) in &scans {
+            for (name, dtype) in schema.iter() {
+                if !all_columns.iter().any(|(seen, _)| seen == name.as_str()) {
+                    all_columns.push((name.to_string(), dtype.clone()));
+                }
+            }
+        }
 
-        df.get_columns()
+        let schemas: Vec<(String, SchemaRef)> = scans
             .iter()
-            .filter(|col| {
-                matches!(
-                    col.dtype(),
-                    DataType::Float32
-                        | DataType::Float64
-                        | DataType::Int8
-                        | DataType::Int16
-                        | DataType::Int32
-                        | DataType::Int64
-                        | DataType::UInt8
-                        | DataType::UInt16
-                        | DataType::UInt32
-                        | DataType::UInt64
-                )
+            .map(|(file_name, _, schema)| (file_name.clone(), schema.clone()))
+            .collect();
+        let warnings = Self::schema_warnings(&schemas, &all_columns);
+
+        let lazy_frames: Vec<LazyFrame> = scans
+            .into_iter()
+            .enumerate()
+            .map(|(file_index, (file_name, lazy, schema))| {
+                let row_id = (col(ROW_ID_COLUMN).cast(DataType::UInt64)
+                    + lit((file_index as u64) << 32))
+                .alias(ROW_ID_COLUMN);
+                let exprs: Vec<Expr> = all_columns
+                    .iter()
+                    .map(|(name, _)| {
+                        if schema.contains(name) {
+                            col(name.as_str())
+                        } else {
+                            lit(NULL).alias(name.as_str())
+                        }
+                    })
+                    .chain(std::iter::once(lit(file_name).alias(SOURCE_FILE_COLUMN)))
+                    .chain(row_ids.then_some(row_id))
+                    .collect();
+                lazy.select(exprs)
             })
-            .map(|col| col.name().to_string())
-            .collect()
+            .collect();
+
+        let lazy = concat(
+            lazy_frames,
+            UnionArgs {
+                to_supertypes: true,
+                ..Default::default()
+            },
+        )?;
+
+        Ok(MultiFileScan { lazy, warnings })
+    }
+
+    /// Describe columns that are missing from some files or change dtype between files.
+    fn schema_warnings(
+        schemas: &[(String, SchemaRef)],
+        all_columns: &[(String, DataType)],
+    ) -> Vec<String> {
+        let mut warnings = Vec::new();
+
+        for (name, first_dtype) in all_columns {
+            let missing: Vec<&str> = schemas
+                .iter()
+                .filter(|(_, schema)| !schema.contains(name))
+                .map(|(file_name, _)| file_name.as_str())
+                .collect();
+            if !missing.is_empty() {
+                warnings.push(format!("'{}' missing from {}", name, missing.join(", ")));
+            }
+
+            let mismatched: Vec<String> = schemas
+                .iter()
+                .filter_map(|(file_name, schema)| {
+                    let dtype = schema.get(name)?;
+                    (dtype != first_dtype).then(|| format!("{} ({})", file_name, dtype))
+                })
+                .collect();
+            if !mismatched.is_empty() {
+                warnings.push(format!(
+                    "'{}' is {} but differs in {}",
+                    name,
+                    first_dtype,
+                    mismatched.join(", ")
+                ));
+            }
+        }
+
+        warnings
     }
 
     /// Get unique values from a column.
     pub fn get_unique_values(&self, column: &str) -> Vec<String> {
-        let Some(df) = &self.df else {
-            return Vec::new();
+        let unique = if let Some(lazy) = self.get_lazy_frame() {
+            lazy.select([col(column)])
+                .unique(None, UniqueKeepStrategy::Any)
+                .with_streaming(true)
+                .collect()
+                .ok()
+                .and_then(|df| df.column(column).ok().cloned())
+        } else {
+            self.df
+                .as_ref()
+                .and_then(|df| df.column(column).ok())
+                .and_then(|col| col.unique().ok())
         };
 
-        df.column(column)
-            .ok()
-            .and_then(|col| col.unique().ok())
+        unique
             .map(|unique| {
                 let series = unique.as_materialized_series();
                 (0..series.len())
@@ -113,9 +469,12 @@ impl DataLoader {
             .unwrap_or_default()
     }
 
-    /// Get the number of rows in the DataFrame.
+    /// Get the number of rows in the DataFrame (of the full scan in streaming mode).
     pub fn get_row_count(&self) -> usize {
-        self.df.as_ref().map(|df| df.height()).unwrap_or(0)
+        match &self.streaming {
+            Some(source) => source.row_count,
+            None => self.df.as_ref().map(|df| df.height()).unwrap_or(0),
+        }
     }
 
     /// Get a reference to the loaded DataFrame.
@@ -123,13 +482,58 @@ impl DataLoader {
         self.df.as_ref()
     }
 
-    /// Get file path.
-    pub fn get_file_path(&self) -> Option<&PathBuf> {
-        self.file_path.as_ref()
-    }
-
     /// Set DataFrame directly (used for async loading)
     pub fn set_dataframe(&mut self, df: DataFrame) {
+        self.schema = ColumnSchema::infer_all(&df);
+        self.source_df = Some(df.clone());
         self.df = Some(df);
+        self.streaming = None;
+    }
+
+    /// Analyse a lazy scan with the streaming engine instead of loading it.
+    /// `preview` holds the first rows, used for display and schema inference.
+    pub fn set_streaming_source(&mut self, scan: LazyFrame, preview: DataFrame, row_count: usize) {
+        self.set_dataframe(preview);
+        self.streaming = Some(StreamingSource { scan, row_count });
+    }
+
+    /// Whether the data is scanned from disk rather than held in memory.
+    pub fn is_streaming(&self) -> bool {
+        self.streaming.is_some()
+    }
+
+    /// Lazy scan with the current column roles applied, plus the `__row_id` column
+    /// (streaming mode only).
+    pub fn get_lazy_frame(&self) -> Option<LazyFrame> {
+        let source = self.streaming.as_ref()?;
+        let exprs: Vec<Expr> = self
+            .schema
+            .iter()
+            .filter(|column| column.role != ColumnRole::Ignore)
+            .map(ColumnSchema::cast_expr)
+            .chain(std::iter::once(col(ROW_ID_COLUMN)))
+            .collect();
+        Some(source.scan.clone().select(exprs))
+    }
+
+    /// Get the column roles of the loaded data.
+    pub fn get_schema(&self) -> &[ColumnSchema] {
+        &self.schema
+    }
+
+    /// Re-cast the loaded data according to new column roles.
+    /// Casts always start from the data as read, so roles can be changed back losslessly.
+    pub fn apply_schema(&mut self, schema: Vec<ColumnSchema>) -> Result<&DataFrame, LoaderError> {
+        let source = self.source_df.as_ref().ok_or(LoaderError::NoData)?;
+
+        let columns = schema
+            .iter()
+            .filter(|column| column.role != ColumnRole::Ignore)
+            .map(|column| column.cast(source.column(&column.name)?))
+            .collect::<PolarsResult<Vec<Column>>>()?;
+
+        self.df = Some(DataFrame::new(columns)?);
+        self.schema = schema;
+        self.df.as_ref().ok_or(LoaderError::NoData)
     }
 }
@@ -1,7 +1,19 @@
-//! Data module - CSV loading and processing
+//! Data module - file loading and processing
 
+mod csv_options;
+mod excel;
+mod filter;
 mod loader;
 mod processor;
+mod quality;
+mod schema;
+mod table;
 
-pub use loader::DataLoader;
-pub use processor::{DataProcessor, DataMode};
+pub use csv_options::{CsvOptions, TextEncoding};
+pub use excel::ExcelReader;
+pub use filter::{Connector, FilterCondition, FilterMode, FilterOperator, RowFilter};
+pub use loader::{DataLoader, FileFormat, LoadOptions, ROW_ID_COLUMN, SOURCE_FILE_COLUMN};
+pub use processor::{DataMode, DataProcessor, PairedGroups, PairedValues};
+pub use quality::{DataQualityReport, DropCounts};
+pub use schema::{ColumnRole, ColumnSchema};
+pub use table::{DataTypeTable, TableError, TableRows};
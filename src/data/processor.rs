@@ -1,9 +1,15 @@
 //! Data Processor Module
 //! Handles data cleaning and transformation (stack operation).
 
+use super::loader::ROW_ID_COLUMN;
+use super::quality::DropCounts;
 use polars::prelude::*;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use thiserror::Error;
 
+/// Name of the pair ID column carried through the long format in paired mode.
+pub const PAIR_ID_COLUMN: &str = "pair_id";
+
 #[derive(Error, Debug)]
 pub enum ProcessorError {
     #[error("Polars error: {0}")]
@@ -15,68 +21,94 @@ pub enum ProcessorError {
 }
 
 /// Data processing mode
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum DataMode {
     /// Single column mode: data already in long format
+    #[default]
     Single,
     /// Multi column mode: need to unpivot/melt
     Multi,
 }
 
-impl Default for DataMode {
-    fn default() -> Self {
-        DataMode::Single
-    }
+/// Dropped values keyed by (data_type, group).
+pub type ExcludedCounts = HashMap<(String, String), DropCounts>;
+
+/// Values of a group matched with the control's by pair ID.
+#[derive(Debug, Clone, Default)]
+pub struct PairedValues {
+    /// Group minus control value of each matched ID, in ID order
+    pub differences: Vec<f64>,
+    /// IDs found in only one of the group and the control, sorted
+    pub unmatched: Vec<String>,
+}
+
+/// Paired values keyed by data type and then group; the control has no entry.
+pub type PairedGroups = HashMap<String, HashMap<String, PairedValues>>;
+
+/// Long-format data ready for analysis, plus what was left out getting there.
+pub struct PreparedData {
+    /// Columns: ["group", "data_type", "value"], with categorical group and data type,
+    /// plus `pair_id` in paired mode
+    pub df: DataFrame,
+    pub excluded: ExcludedCounts,
+    /// Rows dropped because their group or data type was missing.
+    pub unassigned: usize,
 }
 
 /// Handles data cleaning and transformation operations.
 pub struct DataProcessor;
 
 impl DataProcessor {
-    /// Transform multi-column data to long format (stack operation).
+    /// Transform multi-column data to long format (stack operation) by unpivoting the
+    /// data columns.
     ///
-    /// Output columns: [group, "data_type", "value"]
+    /// Output columns: ["group", "data_type", "value", "raw_null"], plus `__row_id`
+    /// when `keep_row_id` is set and `pair_id` when a pair column is given.
     pub fn stack_to_long(
-        df: &DataFrame,
+        lazy: LazyFrame,
         group_col: &str,
         data_cols: &[String],
-    ) -> Result<DataFrame, ProcessorError> {
-        // Build the result manually by iterating through columns
-        let mut groups: Vec<String> = Vec::new();
-        let mut data_types: Vec<String> = Vec::new();
-        let mut values: Vec<f64> = Vec::new();
-
-        let group_series = df.column(group_col)?;
-
-        for data_col in data_cols {
-            if let Ok(value_series) = df.column(data_col) {
-                let value_f64 = value_series.cast(&DataType::Float64)?;
-                let value_ca = value_f64.f64()?;
-
-                for i in 0..df.height() {
-                    if let (Ok(g), Some(v)) = (group_series.get(i), value_ca.get(i)) {
-                        if !v.is_nan() && !g.is_null() {
-                            groups.push(g.to_string().trim_matches('"').to_string());
-                            data_types.push(data_col.clone());
-                            values.push(v);
-                        }
-                    }
-                }
-            }
-        }
+        keep_row_id: bool,
+        pair_col: Option<&str>,
+    ) -> LazyFrame {
+        // Pack each value with its null flag so both survive the unpivot together
+        let packed = data_cols.iter().map(|data_col| {
+            as_struct(vec![
+                col(data_col.as_str())
+                    .cast(DataType::Float64)
+                    .alias("value"),
+                col(data_col.as_str()).is_null().alias("raw_null"),
+            ])
+            .alias(data_col.as_str())
+        });
+        let index: Vec<&str> = std::iter::once("group")
+            .chain(keep_row_id.then_some(ROW_ID_COLUMN))
+            .chain(pair_col.map(|_| PAIR_ID_COLUMN))
+            .collect();
+        let columns: Vec<Expr> =
+            std::iter::once(col(group_col).cast(DataType::String).alias("group"))
+                .chain(keep_row_id.then(|| col(ROW_ID_COLUMN)))
+                .chain(pair_col.map(Self::pair_id))
+                .chain(packed)
+                .collect();
 
-        let df = DataFrame::new(vec![
-            Column::new("group".into(), groups),
-            Column::new("data_type".into(), data_types),
-            Column::new("value".into(), values),
-        ])?;
-
-        Ok(df)
+        lazy.select(columns)
+            .unpivot(UnpivotArgsDSL {
+                on: data_cols
+                    .iter()
+                    .map(|data_col| Selector::from(data_col.as_str()))
+                    .collect(),
+                index: index.into_iter().map(Selector::from).collect(),
+                variable_name: Some("data_type".into()),
+                value_name: Some("packed".into()),
+            })
+            .unnest(["packed"])
     }
 
     /// Prepare data based on mode (single or multi-column).
     ///
-    /// Output format: ["group", "data_type", "value"]
+    /// Output format: ["group", "data_type", "value"], plus `pair_id` when a pair
+    /// column is given.
     pub fn prepare_data(
         df: &DataFrame,
         mode: DataMode,
@@ -84,43 +116,88 @@ impl DataProcessor {
         data_type_col: Option<&str>,
         value_col: Option<&str>,
         data_cols: Option<&[String]>,
-    ) -> Result<DataFrame, ProcessorError> {
+        pair_col: Option<&str>,
+    ) -> Result<PreparedData, ProcessorError> {
+        let long = Self::prepare_lazy(
+            df.clone().lazy(),
+            mode,
+            group_col,
+            data_type_col,
+            value_col,
+            data_cols,
+            pair_col,
+        )?
+        .collect()?;
+
+        let totals = long
+            .clone()
+            .lazy()
+            .group_by([col("data_type"), col("group")])
+            .agg(Self::drop_count_aggs())
+            .collect()?;
+        let (excluded, unassigned) = Self::read_drop_counts(&totals)?;
+
+        let categorical = DataType::Categorical(None, CategoricalOrdering::Physical);
+        let mut columns = vec![
+            col("group").cast(categorical.clone()),
+            col("data_type").cast(categorical),
+            col("value"),
+        ];
+        if pair_col.is_some() {
+            columns.push(col(PAIR_ID_COLUMN));
+        }
+        let df = long
+            .lazy()
+            .filter(
+                col("group")
+                    .is_not_null()
+                    .and(col("data_type").is_not_null())
+                    .and(Self::usable_value()),
+            )
+            .select(columns)
+            .collect()?;
+
+        Ok(PreparedData {
+            df,
+            excluded,
+            unassigned,
+        })
+    }
+
+    /// Build the long format lazily, without collecting it.
+    ///
+    /// Output columns: ["group", "data_type", "value", "raw_null"]. Nothing is dropped
+    /// yet; `raw_null` marks values that were null before the cast to float, so missing
+    /// values can be told apart from unparseable ones. A `__row_id` column of a
+    /// streaming scan is carried through, and the pair column as `pair_id`.
+    pub fn prepare_lazy(
+        mut lazy: LazyFrame,
+        mode: DataMode,
+        group_col: &str,
+        data_type_col: Option<&str>,
+        value_col: Option<&str>,
+        data_cols: Option<&[String]>,
+        pair_col: Option<&str>,
+    ) -> Result<LazyFrame, ProcessorError> {
+        let keep_row_id = lazy.collect_schema()?.contains(ROW_ID_COLUMN);
+
         match mode {
             DataMode::Single => {
                 let data_type_col =
                     data_type_col.ok_or(ProcessorError::MissingSingleModeColumns)?;
                 let value_col = value_col.ok_or(ProcessorError::MissingSingleModeColumns)?;
 
-                // Build result manually
-                let group_series = df.column(group_col)?;
-                let dtype_series = df.column(data_type_col)?;
-                let value_series = df.column(value_col)?;
-                let value_f64 = value_series.cast(&DataType::Float64)?;
-                let value_ca = value_f64.f64()?;
-
-                let mut groups: Vec<String> = Vec::new();
-                let mut data_types: Vec<String> = Vec::new();
-                let mut values: Vec<f64> = Vec::new();
-
-                for i in 0..df.height() {
-                    if let (Ok(g), Ok(dt), Some(v)) =
-                        (group_series.get(i), dtype_series.get(i), value_ca.get(i))
-                    {
-                        if !v.is_nan() && !g.is_null() && !dt.is_null() {
-                            groups.push(g.to_string().trim_matches('"').to_string());
-                            data_types.push(dt.to_string().trim_matches('"').to_string());
-                            values.push(v);
-                        }
-                    }
+                let mut columns = vec![
+                    col(group_col).cast(DataType::String).alias("group"),
+                    col(data_type_col).cast(DataType::String).alias("data_type"),
+                    col(value_col).cast(DataType::Float64).alias("value"),
+                    col(value_col).is_null().alias("raw_null"),
+                ];
+                if keep_row_id {
+                    columns.push(col(ROW_ID_COLUMN));
                 }
-
-                let result = DataFrame::new(vec![
-                    Column::new("group".into(), groups),
-                    Column::new("data_type".into(), data_types),
-                    Column::new("value".into(), values),
-                ])?;
-
-                Ok(result)
+                columns.extend(pair_col.map(Self::pair_id));
+                Ok(lazy.select(columns))
             }
             DataMode::Multi => {
                 let data_cols = data_cols.ok_or(ProcessorError::MissingMultiModeColumns)?;
@@ -128,67 +205,180 @@ impl DataProcessor {
                     return Err(ProcessorError::MissingMultiModeColumns);
                 }
 
-                Self::stack_to_long(df, group_col, data_cols)
+                Ok(Self::stack_to_long(
+                    lazy,
+                    group_col,
+                    data_cols,
+                    keep_row_id,
+                    pair_col,
+                ))
             }
         }
     }
 
-    /// Get unique data types from processed DataFrame.
-    pub fn get_data_types(df: &DataFrame) -> Vec<String> {
-        df.column("data_type")
-            .ok()
-            .and_then(|col| col.unique().ok())
-            .map(|unique| {
-                let series = unique.as_materialized_series();
-                let mut types: Vec<String> = (0..series.len())
-                    .filter_map(|i| {
-                        let val = series.get(i).ok()?;
-                        if val.is_null() {
-                            None
-                        } else {
-                            Some(val.to_string().trim_matches('"').to_string())
-                        }
-                    })
-                    .collect();
-                types.sort();
-                types
-            })
-            .unwrap_or_default()
+    /// The pair column as the `pair_id` text column.
+    fn pair_id(pair_col: &str) -> Expr {
+        col(pair_col).cast(DataType::String).alias(PAIR_ID_COLUMN)
     }
 
-    /// Get unique groups from processed DataFrame.
-    pub fn get_groups(df: &DataFrame) -> Vec<String> {
-        df.column("group")
-            .ok()
-            .and_then(|col| col.unique().ok())
-            .map(|unique| {
-                let series = unique.as_materialized_series();
-                let mut groups: Vec<String> = (0..series.len())
-                    .filter_map(|i| {
-                        let val = series.get(i).ok()?;
-                        if val.is_null() {
-                            None
-                        } else {
-                            Some(val.to_string().trim_matches('"').to_string())
-                        }
-                    })
-                    .collect();
-                groups.sort();
-                groups
+    /// Drop rows of prepared data by their position within their data type and
+    /// group, keyed by data type and then group. Positions count the group's rows
+    /// in row order, as `StatsCalculator::group_values` gathers them.
+    pub fn drop_rows(
+        df: &DataFrame,
+        positions: &HashMap<String, HashMap<String, Vec<usize>>>,
+    ) -> PolarsResult<DataFrame> {
+        let dropped: HashSet<(&str, &str, usize)> = positions
+            .iter()
+            .flat_map(|(data_type, groups)| {
+                groups.iter().flat_map(move |(group, positions)| {
+                    positions
+                        .iter()
+                        .map(move |&i| (data_type.as_str(), group.as_str(), i))
+                })
+            })
+            .collect();
+        if dropped.is_empty() {
+            return Ok(df.clone());
+        }
+
+        let data_types = df.column("data_type")?.cast(&DataType::String)?;
+        let groups = df.column("group")?.cast(&DataType::String)?;
+        let mut seen: HashMap<(&str, &str), usize> = HashMap::new();
+        let keep: BooleanChunked = data_types
+            .str()?
+            .into_iter()
+            .zip(groups.str()?)
+            .map(|(data_type, group)| match (data_type, group) {
+                (Some(data_type), Some(group)) => {
+                    let position = seen.entry((data_type, group)).or_default();
+                    *position += 1;
+                    !dropped.contains(&(data_type, group, *position - 1))
+                }
+                _ => true,
             })
-            .unwrap_or_default()
+            .collect();
+        df.filter(&keep)
     }
 
-    /// Filter DataFrame for a specific data type.
-    pub fn filter_by_data_type(
-        df: &DataFrame,
-        data_type: &str,
-    ) -> Result<DataFrame, ProcessorError> {
-        let filtered = df
+    /// Match the values of every group with the control's by pair ID, per data
+    /// type, from prepared data with a `pair_id` column. Repeated values of an ID
+    /// within a group are averaged; rows without an ID are left out.
+    pub fn pair_by_id(df: &DataFrame, control_group: &str) -> PolarsResult<PairedGroups> {
+        let by_id = df
             .clone()
             .lazy()
-            .filter(col("data_type").eq(lit(data_type)))
+            .filter(col(PAIR_ID_COLUMN).is_not_null())
+            .group_by([col("data_type"), col("group"), col(PAIR_ID_COLUMN)])
+            .agg([col("value").mean()])
             .collect()?;
-        Ok(filtered)
+
+        let data_types = by_id.column("data_type")?.cast(&DataType::String)?;
+        let groups = by_id.column("group")?.cast(&DataType::String)?;
+        let ids = by_id.column(PAIR_ID_COLUMN)?.str()?.clone();
+        let values = by_id.column("value")?.f64()?.clone();
+
+        // Value of each ID per (data_type, group), in ID order
+        let mut by_group: HashMap<(String, String), BTreeMap<String, f64>> = HashMap::new();
+        for (i, (data_type, group)) in data_types.str()?.into_iter().zip(groups.str()?).enumerate()
+        {
+            let (Some(data_type), Some(group), Some(id), Some(value)) =
+                (data_type, group, ids.get(i), values.get(i))
+            else {
+                continue;
+            };
+            by_group
+                .entry((data_type.to_string(), group.to_string()))
+                .or_default()
+                .insert(id.to_string(), value);
+        }
+
+        let empty = BTreeMap::new();
+        let mut paired = PairedGroups::new();
+        for ((data_type, group), group_values) in &by_group {
+            if group == control_group {
+                continue;
+            }
+            let control = by_group
+                .get(&(data_type.clone(), control_group.to_string()))
+                .unwrap_or(&empty);
+
+            let differences = group_values
+                .iter()
+                .filter_map(|(id, value)| Some(value - control.get(id)?))
+                .collect();
+            let unmatched = group_values
+                .keys()
+                .filter(|id| !control.contains_key(*id))
+                .chain(control.keys().filter(|id| !group_values.contains_key(*id)))
+                .cloned()
+                .collect::<BTreeSet<String>>()
+                .into_iter()
+                .collect();
+
+            paired.entry(data_type.clone()).or_default().insert(
+                group.clone(),
+                PairedValues {
+                    differences,
+                    unmatched,
+                },
+            );
+        }
+
+        Ok(paired)
+    }
+
+    /// Whether a long-format value can be analysed (present and not NaN).
+    pub fn usable_value() -> Expr {
+        col("value").is_not_null().and(col("value").is_nan().not())
+    }
+
+    /// Aggregations over long-format data grouped by (data_type, group) counting the
+    /// rows and why values are dropped; read back with `read_drop_counts`.
+    pub fn drop_count_aggs() -> Vec<Expr> {
+        let flag_sum = |flag: Expr| flag.cast(DataType::UInt64).sum();
+        vec![
+            len().alias("rows"),
+            flag_sum(col("raw_null")).alias("null"),
+            flag_sum(col("value").is_null().and(col("raw_null").not())).alias("unparseable"),
+            flag_sum(col("value").is_nan()).alias("nan"),
+        ]
+    }
+
+    /// Dropped values per (data_type, group) and the number of unassigned rows,
+    /// from a frame aggregated with `drop_count_aggs`.
+    pub fn read_drop_counts(totals: &DataFrame) -> PolarsResult<(ExcludedCounts, usize)> {
+        let data_types = totals.column("data_type")?.cast(&DataType::String)?;
+        let groups = totals.column("group")?.cast(&DataType::String)?;
+        let count_of = |name: &str| -> PolarsResult<UInt64Chunked> {
+            Ok(totals.column(name)?.cast(&DataType::UInt64)?.u64()?.clone())
+        };
+        let (rows, nulls, unparseable, nans) = (
+            count_of("rows")?,
+            count_of("null")?,
+            count_of("unparseable")?,
+            count_of("nan")?,
+        );
+
+        let mut excluded = HashMap::new();
+        let mut unassigned = 0;
+        for (i, (data_type, group)) in data_types.str()?.into_iter().zip(groups.str()?).enumerate()
+        {
+            let (Some(data_type), Some(group)) = (data_type, group) else {
+                unassigned += rows.get(i).unwrap_or(0) as usize;
+                continue;
+            };
+
+            let counts = DropCounts {
+                unparseable: unparseable.get(i).unwrap_or(0) as usize,
+                null: nulls.get(i).unwrap_or(0) as usize,
+                nan: nans.get(i).unwrap_or(0) as usize,
+            };
+            if counts.total() > 0 {
+                excluded.insert((data_type.to_string(), group.to_string()), counts);
+            }
+        }
+
+        Ok((excluded, unassigned))
     }
 }
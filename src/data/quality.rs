@@ -0,0 +1,257 @@
+//! Data Quality Module
+//! Counts missing, NaN and unparseable values so dropped measurements are reported
+//! instead of disappearing silently.
+
+use super::csv_options::{CsvOptions, TextEncoding};
+use super::loader::FileFormat;
+use polars::prelude::*;
+use std::path::PathBuf;
+
+/// Why values were left out, by reason.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DropCounts {
+    /// Present in the file but not readable as a number.
+    pub unparseable: usize,
+    /// Empty or matching a null token.
+    pub null: usize,
+    /// Parsed as NaN.
+    pub nan: usize,
+}
+
+impl DropCounts {
+    pub fn total(&self) -> usize {
+        self.unparseable + self.null + self.nan
+    }
+
+    /// Add another set of counts to this one.
+    pub fn add(&mut self, other: &DropCounts) {
+        self.unparseable += other.unparseable;
+        self.null += other.null;
+        self.nan += other.nan;
+    }
+
+    /// Short description such as "3 null, 1 unparseable".
+    pub fn describe(&self) -> String {
+        let parts: Vec<String> = [
+            (self.null, "null"),
+            (self.nan, "NaN"),
+            (self.unparseable, "unparseable"),
+        ]
+        .iter()
+        .filter(|(count, _)| *count > 0)
+        .map(|(count, label)| format!("{} {}", count, label))
+        .collect();
+
+        if parts.is_empty() {
+            "none".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+/// Missing and invalid values found in one column after loading.
+#[derive(Debug, Clone)]
+pub struct ColumnQuality {
+    pub column: String,
+    pub counts: DropCounts,
+}
+
+/// Per-column summary of missing and invalid values in a loaded DataFrame.
+#[derive(Debug, Clone, Default)]
+pub struct DataQualityReport {
+    pub row_count: usize,
+    /// Only columns with at least one problem value.
+    pub columns: Vec<ColumnQuality>,
+}
+
+impl DataQualityReport {
+    /// Inspect a loaded DataFrame.
+    ///
+    /// `raw_nulls` holds per-column null counts of the same file read as plain text;
+    /// nulls beyond those were cells the lenient parser could not read.
+    pub fn from_dataframe(df: &DataFrame, raw_nulls: Option<&[(String, usize)]>) -> Self {
+        let columns = df
+            .get_columns()
+            .iter()
+            .filter_map(|column| {
+                let nan = match column.dtype() {
+                    DataType::Float32 | DataType::Float64 => column
+                        .as_materialized_series()
+                        .is_nan()
+                        .map(|mask| mask.sum().unwrap_or(0) as usize)
+                        .unwrap_or(0),
+                    _ => 0,
+                };
+                Self::column_quality(column.name(), column.null_count(), nan, raw_nulls)
+            })
+            .collect();
+
+        Self {
+            row_count: df.height(),
+            columns,
+        }
+    }
+
+    /// Inspect scanned data in a single streaming pass, without loading it.
+    pub fn from_lazy(lazy: LazyFrame, raw_nulls: Option<&[(String, usize)]>) -> PolarsResult<Self> {
+        let mut lazy = lazy;
+        let schema = lazy.collect_schema()?;
+
+        // One aggregate per column and check; indexed names avoid clashing with data columns
+        let mut exprs = vec![len().alias("rows")];
+        for (i, (name, dtype)) in schema.iter().enumerate() {
+            exprs.push(col(name.as_str()).null_count().alias(format!("null_{}", i)));
+            if matches!(dtype, DataType::Float32 | DataType::Float64) {
+                exprs.push(
+                    col(name.as_str())
+                        .is_nan()
+                        .cast(DataType::UInt64)
+                        .sum()
+                        .alias(format!("nan_{}", i)),
+                );
+            }
+        }
+        let counts = lazy.select(exprs).with_streaming(true).collect()?;
+        let count = |name: &str| {
+            counts
+                .column(name)
+                .map(|column| Self::first_value(&column.clone().into_frame()))
+                .unwrap_or(0)
+        };
+
+        let columns = schema
+            .iter_names()
+            .enumerate()
+            .filter_map(|(i, name)| {
+                Self::column_quality(
+                    name,
+                    count(&format!("null_{}", i)),
+                    count(&format!("nan_{}", i)),
+                    raw_nulls,
+                )
+            })
+            .collect();
+
+        Ok(Self {
+            row_count: count("rows"),
+            columns,
+        })
+    }
+
+    /// Split a column's nulls into missing and unparseable values using the raw
+    /// null counts, and keep the column only if it has any problem values.
+    fn column_quality(
+        name: &str,
+        null_count: usize,
+        nan: usize,
+        raw_nulls: Option<&[(String, usize)]>,
+    ) -> Option<ColumnQuality> {
+        let raw_null = raw_nulls
+            .and_then(|raw| raw.iter().find(|(raw_name, _)| raw_name == name))
+            .map(|(_, count)| (*count).min(null_count))
+            .unwrap_or(null_count);
+
+        let counts = DropCounts {
+            unparseable: null_count - raw_null,
+            null: raw_null,
+            nan,
+        };
+        (counts.total() > 0).then(|| ColumnQuality {
+            column: name.to_string(),
+            counts,
+        })
+    }
+
+    /// Per-column null counts of CSV files read without type inference, i.e. the
+    /// values that are genuinely missing rather than unreadable.
+    ///
+    /// Columns absent from a file count all of that file's rows as missing, matching
+    /// how multi-file loads fill them. Returns `None` unless every file is a UTF-8 CSV.
+    pub fn raw_null_counts(
+        paths: &[PathBuf],
+        csv: &CsvOptions,
+    ) -> PolarsResult<Option<Vec<(String, usize)>>> {
+        // Typed formats need no second pass; transcoded files are not scanned lazily
+        if csv.encoding != TextEncoding::Utf8
+            || paths
+                .iter()
+                .any(|path| FileFormat::detect(path) != FileFormat::Csv)
+        {
+            return Ok(None);
+        }
+
+        let null_values = (!csv.null_values.is_empty()).then(|| {
+            NullValues::AllColumns(csv.null_values.iter().map(|s| s.as_str().into()).collect())
+        });
+
+        let mut per_file: Vec<(usize, Vec<(String, usize)>)> = Vec::with_capacity(paths.len());
+        for path in paths {
+            let lazy = LazyCsvReader::new(path)
+                .with_infer_schema_length(Some(0))
+                .with_separator(csv.delimiter)
+                .with_quote_char(csv.quote_char)
+                .with_null_values(null_values.clone())
+                .finish()?;
+            let rows = Self::first_value(
+                &lazy
+                    .clone()
+                    .select([len()])
+                    .with_streaming(true)
+                    .collect()?,
+            );
+            let nulls = lazy
+                .select([all().null_count()])
+                .with_streaming(true)
+                .collect()?;
+            let counts = nulls
+                .get_columns()
+                .iter()
+                .map(|column| {
+                    let count = Self::first_value(&column.clone().into_frame());
+                    (column.name().to_string(), count)
+                })
+                .collect();
+            per_file.push((rows, counts));
+        }
+
+        // Sum over files, treating a missing column as entirely null
+        let mut totals: Vec<(String, usize)> = Vec::new();
+        for (_, counts) in &per_file {
+            for (name, _) in counts {
+                if !totals.iter().any(|(seen, _)| seen == name) {
+                    totals.push((name.clone(), 0));
+                }
+            }
+        }
+        for (name, total) in &mut totals {
+            for (rows, counts) in &per_file {
+                *total += counts
+                    .iter()
+                    .find(|(column, _)| column == name)
+                    .map(|(_, count)| *count)
+                    .unwrap_or(*rows);
+            }
+        }
+
+        Ok(Some(totals))
+    }
+
+    /// Read the single value of a one-row, one-column count frame.
+    fn first_value(df: &DataFrame) -> usize {
+        df.get_columns()
+            .first()
+            .and_then(|column| column.cast(&DataType::UInt64).ok())
+            .and_then(|column| column.u64().ok().and_then(|ca| ca.get(0)))
+            .unwrap_or(0) as usize
+    }
+
+    /// Total problem values across all columns.
+    pub fn total(&self) -> DropCounts {
+        let mut total = DropCounts::default();
+        for column in &self.columns {
+            total.add(&column.counts);
+        }
+        total
+    }
+}
@@ -0,0 +1,118 @@
+//! Column Schema Module
+//! Analysis roles for each column, inferred from the loaded dtypes and
+//! overridable by the user before analysis.
+
+use polars::prelude::*;
+
+/// How a column takes part in the analysis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnRole {
+    /// Measurement values, cast to Float64
+    Numeric,
+    /// Labels such as groups or data types, cast to String
+    Categorical,
+    /// Timestamps, parsed to Datetime
+    Datetime,
+    /// Dropped from the DataFrame
+    Ignore,
+}
+
+impl ColumnRole {
+    pub const ALL: [ColumnRole; 4] = [
+        ColumnRole::Numeric,
+        ColumnRole::Categorical,
+        ColumnRole::Datetime,
+        ColumnRole::Ignore,
+    ];
+
+    /// Human readable name for the schema editor.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ColumnRole::Numeric => "Numeric",
+            ColumnRole::Categorical => "Categorical",
+            ColumnRole::Datetime => "Datetime",
+            ColumnRole::Ignore => "Ignore",
+        }
+    }
+
+    /// Default role for a column of the given dtype.
+    pub fn infer(dtype: &DataType) -> Self {
+        if dtype.is_primitive_numeric() {
+            ColumnRole::Numeric
+        } else if dtype.is_temporal() {
+            ColumnRole::Datetime
+        } else {
+            ColumnRole::Categorical
+        }
+    }
+}
+
+/// A column of the loaded file and the role it plays in the analysis.
+#[derive(Debug, Clone)]
+pub struct ColumnSchema {
+    pub name: String,
+    /// Dtype as read from the file.
+    pub source_dtype: DataType,
+    pub role: ColumnRole,
+}
+
+impl ColumnSchema {
+    /// Infer the schema of a freshly loaded DataFrame.
+    pub fn infer_all(df: &DataFrame) -> Vec<ColumnSchema> {
+        df.get_columns()
+            .iter()
+            .map(|column| ColumnSchema {
+                name: column.name().to_string(),
+                source_dtype: column.dtype().clone(),
+                role: ColumnRole::infer(column.dtype()),
+            })
+            .collect()
+    }
+
+    /// Cast a source column to the dtype its role calls for.
+    /// Values that do not fit the new type become null.
+    pub fn cast(&self, column: &Column) -> PolarsResult<Column> {
+        match self.role {
+            ColumnRole::Numeric if column.dtype().is_primitive_numeric() => Ok(column.clone()),
+            ColumnRole::Numeric => column.cast(&DataType::Float64),
+            ColumnRole::Categorical => column.cast(&DataType::String),
+            ColumnRole::Datetime if column.dtype().is_temporal() => Ok(column.clone()),
+            ColumnRole::Datetime => column
+                .cast(&DataType::String)?
+                .str()?
+                .as_datetime(
+                    None,
+                    TimeUnit::Microseconds,
+                    false,
+                    false,
+                    None,
+                    &StringChunked::from_iter([Some("raise")]),
+                )
+                .map(|ca| ca.into_series().into_column()),
+            ColumnRole::Ignore => Ok(column.clone()),
+        }
+    }
+
+    /// Dtype of the column after `cast`.
+    fn target_dtype(&self) -> DataType {
+        match self.role {
+            ColumnRole::Numeric if self.source_dtype.is_primitive_numeric() => {
+                self.source_dtype.clone()
+            }
+            ColumnRole::Numeric => DataType::Float64,
+            ColumnRole::Categorical => DataType::String,
+            ColumnRole::Datetime if self.source_dtype.is_temporal() => self.source_dtype.clone(),
+            ColumnRole::Datetime => DataType::Datetime(TimeUnit::Microseconds, None),
+            ColumnRole::Ignore => self.source_dtype.clone(),
+        }
+    }
+
+    /// Lazy equivalent of `cast`, for data that is scanned rather than held in memory.
+    pub fn cast_expr(&self) -> Expr {
+        let schema = self.clone();
+        col(self.name.as_str()).map(
+            move |column| schema.cast(&column).map(Some),
+            GetOutput::from_type(self.target_dtype()),
+        )
+    }
+}
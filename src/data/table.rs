@@ -0,0 +1,87 @@
+//! Data Type Table Module
+//! Reads small per data type tables (equivalence margins, spec limits) from any
+//! file the loader understands. The table has a `data_type` column and one numeric
+//! column per value; column names match case-insensitively.
+
+use super::csv_options::CsvOptions;
+use super::loader::{DataLoader, LoadOptions, LoaderError};
+use polars::prelude::*;
+use std::collections::HashMap;
+use std::path::Path;
+use thiserror::Error;
+
+/// Name of the key column.
+pub const DATA_TYPE_COLUMN: &str = "data_type";
+
+/// Requested column values of each data type.
+pub type TableRows = HashMap<String, Vec<Option<f64>>>;
+
+#[derive(Error, Debug)]
+pub enum TableError {
+    #[error("{0}")]
+    Load(#[from] LoaderError),
+    #[error("Failed to read table: {0}")]
+    Polars(#[from] PolarsError),
+    #[error("Table has no '{0}' column")]
+    MissingKey(&'static str),
+    #[error("Table needs at least one of the columns: {0}")]
+    MissingColumns(String),
+}
+
+/// Values of the requested columns for each data type.
+pub struct DataTypeTable;
+
+impl DataTypeTable {
+    /// Read `columns` for every data type in the table at `path`, parsing CSV
+    /// files with `csv`. Each row holds the values in the order of `columns`,
+    /// `None` for empty cells and absent columns; at least one requested column
+    /// must exist.
+    pub fn read(path: &Path, columns: &[&str], csv: &CsvOptions) -> Result<TableRows, TableError> {
+        let options = LoadOptions {
+            csv: csv.clone(),
+            ..LoadOptions::default()
+        };
+        let df = DataLoader::read_file(path, &options)?;
+        let find = |name: &str| {
+            df.get_column_names()
+                .into_iter()
+                .find(|column| column.trim().eq_ignore_ascii_case(name))
+                .cloned()
+        };
+
+        let key = find(DATA_TYPE_COLUMN).ok_or(TableError::MissingKey(DATA_TYPE_COLUMN))?;
+        let value_columns: Vec<Option<PlSmallStr>> = columns.iter().map(|c| find(c)).collect();
+        if value_columns.iter().all(Option::is_none) {
+            return Err(TableError::MissingColumns(columns.join(", ")));
+        }
+
+        let keys = df.column(&key)?.cast(&DataType::String)?;
+        let keys = keys.str()?;
+        let values = value_columns
+            .iter()
+            .map(|column| {
+                column
+                    .as_ref()
+                    .map(|name| df.column(name)?.cast(&DataType::Float64))
+                    .transpose()
+            })
+            .collect::<PolarsResult<Vec<Option<Column>>>>()?;
+        let values = values
+            .iter()
+            .map(|column| column.as_ref().map(|c| c.f64()).transpose())
+            .collect::<PolarsResult<Vec<_>>>()?;
+
+        let mut table = HashMap::new();
+        for i in 0..df.height() {
+            let Some(data_type) = keys.get(i).map(str::trim).filter(|k| !k.is_empty()) else {
+                continue;
+            };
+            let row = values
+                .iter()
+                .map(|column| column.and_then(|c| c.get(i)).filter(|v| v.is_finite()))
+                .collect();
+            table.insert(data_type.to_string(), row);
+        }
+        Ok(table)
+    }
+}
@@ -2,120 +2,360 @@
 //! Main window with control panel and chart viewer.
 
 use crate::charts::ChartData;
-use crate::data::{DataLoader, DataMode, DataProcessor};
-use crate::gui::{ChartViewer, ControlPanel, ControlPanelAction};
-use crate::stats::StatsCalculator;
+use crate::data::{
+    CsvOptions, DataLoader, DataMode, DataProcessor, DataQualityReport, DataTypeTable, DropCounts,
+    ExcelReader, FileFormat, LoadOptions, PairedGroups, TableError, TableRows, ROW_ID_COLUMN,
+    SOURCE_FILE_COLUMN,
+};
+use crate::gui::{
+    ChartViewer, ControlPanel, ControlPanelAction, CsvDialogAction, CsvOptionsDialog, DataPreview,
+    FilterStatus, SchemaEditor, SchemaEditorAction,
+};
+use crate::stats::{EquivalenceMargins, GroupedValues, SpecTable, StatsCalculator, StreamingStats};
 use egui::SidePanel;
 use polars::prelude::*;
-use rayon::prelude::*;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc::{channel, Receiver, Sender};
 use std::thread;
 
+/// Rows collected for the data preview in streaming mode
+const STREAMING_PREVIEW_ROWS: IdxSize = 10_000;
+
+/// Storage key of the CSV import options remembered per file
+const CSV_OPTIONS_KEY: &str = "csv_options_by_file";
+
 /// Calculation result from background thread
 enum CalcResult {
     Progress(f32, String),
-    Complete(HashMap<String, ChartData>),
+    Complete {
+        chart_data: HashMap<String, ChartData>,
+        exclusions: Vec<(String, DropCounts)>,
+        unassigned: usize,
+    },
     Error(String),
 }
 
-/// CSV loading result from background thread
+/// File loading result from background thread
 enum LoadResult {
     Progress(String),
     Complete {
         df: DataFrame,
         columns: Vec<String>,
         row_count: usize,
+        file_count: usize,
+        warnings: Vec<String>,
+        quality: DataQualityReport,
+        /// Lazy scan of the files in streaming mode; `df` is then only a preview
+        scan: Option<Box<LazyFrame>>,
     },
     Error(String),
 }
 
+/// Tabs of the central panel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum CentralTab {
+    #[default]
+    Charts,
+    Data,
+    Schema,
+}
+
 /// Main application window.
 pub struct ChartifyApp {
     loader: DataLoader,
     control_panel: ControlPanel,
     chart_viewer: ChartViewer,
+    data_preview: DataPreview,
+    schema_editor: SchemaEditor,
+    central_tab: CentralTab,
 
     // Async calculation
     calc_rx: Option<Receiver<CalcResult>>,
     is_calculating: bool,
+    // Calculate again once the running calculation completes
+    recalculate_pending: bool,
 
-    // Async CSV loading
+    // Async file loading
     load_rx: Option<Receiver<LoadResult>>,
     is_loading: bool,
 
+    // Async count of the rows kept by the row filter: (matching, total)
+    filter_rx: Option<Receiver<Result<(usize, usize), String>>>,
+
+    // CSV import options, remembered per file across sessions
+    csv_dialog: CsvOptionsDialog,
+    csv_options_by_file: HashMap<PathBuf, CsvOptions>,
+
     // Last exported PPT path
     last_ppt_path: Option<std::path::PathBuf>,
 }
 
 impl ChartifyApp {
-    pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
+    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        let csv_options_by_file = cc
+            .storage
+            .and_then(|storage| eframe::get_value(storage, CSV_OPTIONS_KEY))
+            .unwrap_or_default();
+
         Self {
             loader: DataLoader::new(),
             control_panel: ControlPanel::new(),
             chart_viewer: ChartViewer::new(),
+            data_preview: DataPreview::new(),
+            schema_editor: SchemaEditor::new(),
+            central_tab: CentralTab::default(),
             calc_rx: None,
             is_calculating: false,
+            recalculate_pending: false,
             load_rx: None,
             is_loading: false,
+            filter_rx: None,
+            csv_dialog: CsvOptionsDialog::new(),
+            csv_options_by_file,
             last_ppt_path: None,
         }
     }
 
-    /// Handle CSV file selection - now async!
+    /// Handle data file selection - now async!
     fn handle_browse_csv(&mut self) {
         if self.is_loading {
             return; // Already loading
         }
 
-        if let Some(path) = rfd::FileDialog::new()
-            .add_filter("CSV Files", &["csv"])
-            .pick_file()
+        if let Some(paths) = rfd::FileDialog::new()
+            .add_filter("Data Files", &FileFormat::all_extensions())
+            .add_filter("CSV Files", FileFormat::CSV_EXTENSIONS)
+            .add_filter("Parquet Files", FileFormat::PARQUET_EXTENSIONS)
+            .add_filter("Arrow IPC Files", FileFormat::IPC_EXTENSIONS)
+            .add_filter("Arrow IPC Streams", FileFormat::IPC_STREAM_EXTENSIONS)
+            .add_filter("Excel Workbooks", FileFormat::EXCEL_EXTENSIONS)
+            .pick_files()
         {
-            // Clear previous charts
-            self.chart_viewer.clear();
-            self.control_panel.settings.csv_path = Some(path.clone());
-            self.control_panel.set_progress(0.0, "Loading CSV file...");
-            self.is_loading = true;
+            self.set_source_files(paths);
+        }
+    }
 
-            let (tx, rx) = channel();
-            self.load_rx = Some(rx);
+    /// Handle loading every file matching the glob pattern
+    fn handle_load_pattern(&mut self) {
+        if self.is_loading {
+            return; // Already loading
+        }
 
-            let path_str = path.to_string_lossy().to_string();
+        let pattern = self.control_panel.settings.file_pattern.trim().to_string();
+        match DataLoader::expand_glob(&pattern) {
+            Ok(paths) => self.set_source_files(paths),
+            Err(e) => {
+                self.control_panel
+                    .set_progress(0.0, &format!("Error: {}", e));
+            }
+        }
+    }
 
-            // Load CSV in background thread
-            thread::spawn(move || {
-                let _ = tx.send(LoadResult::Progress("Reading CSV file...".to_string()));
+    /// Use new source files and start loading them
+    fn set_source_files(&mut self, paths: Vec<PathBuf>) {
+        let Some(first) = paths.first() else {
+            return;
+        };
+        let format = FileFormat::detect(first);
 
-                let result = LazyCsvReader::new(&path_str)
-                    .with_infer_schema_length(Some(10000))
-                    .with_ignore_errors(true)
-                    .finish()
-                    .and_then(|lazy| lazy.collect());
+        // CSV files are loaded once their parsing options are confirmed
+        if format == FileFormat::Csv {
+            self.open_csv_dialog(paths);
+            return;
+        }
 
-                match result {
-                    Ok(df) => {
-                        let columns: Vec<String> = df
-                            .get_column_names()
-                            .iter()
-                            .map(|s| s.to_string())
-                            .collect();
-                        let row_count = df.height();
-                        let _ = tx.send(LoadResult::Complete {
-                            df,
-                            columns,
-                            row_count,
-                        });
-                    }
-                    Err(e) => {
-                        let _ = tx.send(LoadResult::Error(e.to_string()));
-                    }
+        // Workbooks need a sheet choice; default to the first sheet
+        let sheets = if format == FileFormat::Excel {
+            match ExcelReader::list_sheets(first) {
+                Ok(sheets) => sheets,
+                Err(e) => {
+                    self.control_panel
+                        .set_progress(0.0, &format!("Error: {}", e));
+                    return;
                 }
-            });
+            }
+        } else {
+            Vec::new()
+        };
+        self.control_panel.update_sheets(sheets);
+        self.control_panel.csv_source = false;
+        self.control_panel.settings.csv_paths = paths;
+        self.start_loading();
+    }
+
+    /// Show the CSV options dialog, starting from remembered or auto-detected options
+    fn open_csv_dialog(&mut self, paths: Vec<PathBuf>) {
+        let Some(first) = paths.first() else {
+            return;
+        };
+
+        let options = self.csv_options_for(first);
+        self.csv_dialog.open_for(paths, options);
+    }
+
+    /// CSV options of a file: those last confirmed for it, else auto-detected
+    fn csv_options_for(&self, path: &Path) -> CsvOptions {
+        match self.csv_options_by_file.get(&storage_key(path)) {
+            Some(options) => options.clone(),
+            None => CsvOptions::detect(path).unwrap_or_default(),
+        }
+    }
+
+    /// Pick a per data type table file and read `columns` from it, parsing CSV
+    /// files with their remembered or detected options; `None` when the dialog is
+    /// cancelled
+    fn pick_data_type_table(&self, columns: &[&str]) -> Option<Result<TableRows, TableError>> {
+        let path = rfd::FileDialog::new()
+            .add_filter("Data Files", &FileFormat::all_extensions())
+            .pick_file()?;
+        Some(DataTypeTable::read(
+            &path,
+            columns,
+            &self.csv_options_for(&path),
+        ))
+    }
+
+    /// Load the CSV files confirmed in the options dialog
+    fn handle_csv_dialog_load(&mut self) {
+        let paths = std::mem::take(&mut self.csv_dialog.paths);
+        let options = self.csv_dialog.options.clone();
+        for path in &paths {
+            self.csv_options_by_file
+                .insert(storage_key(path), options.clone());
+        }
+
+        self.control_panel.update_sheets(Vec::new());
+        self.control_panel.csv_source = true;
+        self.control_panel.settings.csv_options = options;
+        self.control_panel.settings.csv_paths = paths;
+        self.start_loading();
+    }
+
+    /// Load the selected files in a background thread
+    fn start_loading(&mut self) {
+        if self.is_loading {
+            return; // Already loading
+        }
+
+        let paths = self.control_panel.settings.csv_paths.clone();
+        if paths.is_empty() {
+            return;
+        }
+        let options = self.control_panel.load_options();
+        let streaming = self.control_panel.settings.streaming;
+
+        // Clear previous charts
+        self.chart_viewer.clear();
+        self.data_preview.clear();
+        self.schema_editor.set_schema(&[]);
+        self.control_panel.load_warnings.clear();
+        self.control_panel.set_progress(0.0, "Loading file...");
+        self.is_loading = true;
+
+        let (tx, rx) = channel();
+        self.load_rx = Some(rx);
+
+        // Load files in background thread
+        thread::spawn(move || {
+            if streaming {
+                Self::run_streaming_load(tx, paths, options);
+                return;
+            }
+
+            let result = if let [path] = paths.as_slice() {
+                let format = FileFormat::detect(path);
+                let _ = tx.send(LoadResult::Progress(format!(
+                    "Reading {} file...",
+                    format.name()
+                )));
+
+                DataLoader::read_file(path, &options).map(|df| (df, Vec::new()))
+            } else {
+                let _ = tx.send(LoadResult::Progress(format!(
+                    "Reading {} files...",
+                    paths.len()
+                )));
+
+                DataLoader::read_files(&paths, &options).map(|load| (load.df, load.warnings))
+            };
+
+            match result {
+                Ok((df, warnings)) => {
+                    let _ = tx.send(LoadResult::Progress("Checking data quality...".to_string()));
+                    let raw_nulls = DataQualityReport::raw_null_counts(&paths, &options.csv)
+                        .ok()
+                        .flatten();
+                    let quality = DataQualityReport::from_dataframe(&df, raw_nulls.as_deref());
+
+                    let columns: Vec<String> = df
+                        .get_column_names()
+                        .iter()
+                        .map(|s| s.to_string())
+                        .collect();
+                    let row_count = df.height();
+                    let _ = tx.send(LoadResult::Complete {
+                        df,
+                        columns,
+                        row_count,
+                        file_count: paths.len(),
+                        warnings,
+                        quality,
+                        scan: None,
+                    });
+                }
+                Err(e) => {
+                    let _ = tx.send(LoadResult::Error(e.to_string()));
+                }
+            }
+        });
+    }
+
+    /// Scan files for streaming analysis, collecting only a preview (called from background thread)
+    fn run_streaming_load(tx: Sender<LoadResult>, paths: Vec<PathBuf>, options: LoadOptions) {
+        let _ = tx.send(LoadResult::Progress(format!(
+            "Scanning {} file(s)...",
+            paths.len()
+        )));
+
+        let result = DataLoader::scan_for_streaming(&paths, &options).and_then(|scan| {
+            let (lazy, warnings) = (scan.lazy, scan.warnings);
+            // The row id is kept for sampling but hidden from the preview and schema
+            let visible = lazy.clone().drop([ROW_ID_COLUMN]);
+            let preview = visible.clone().limit(STREAMING_PREVIEW_ROWS).collect()?;
+
+            let _ = tx.send(LoadResult::Progress("Checking data quality...".to_string()));
+            let raw_nulls = DataQualityReport::raw_null_counts(&paths, &options.csv)
+                .ok()
+                .flatten();
+            let quality = DataQualityReport::from_lazy(visible, raw_nulls.as_deref())?;
+            Ok((lazy, preview, warnings, quality))
+        });
+
+        match result {
+            Ok((lazy, preview, warnings, quality)) => {
+                let columns: Vec<String> = preview
+                    .get_column_names()
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect();
+                let _ = tx.send(LoadResult::Complete {
+                    df: preview,
+                    columns,
+                    row_count: quality.row_count,
+                    file_count: paths.len(),
+                    warnings,
+                    quality,
+                    scan: Some(Box::new(lazy)),
+                });
+            }
+            Err(e) => {
+                let _ = tx.send(LoadResult::Error(e.to_string()));
+            }
         }
     }
 
-    /// Check for CSV loading results
+    /// Check for file loading results
     fn check_load_results(&mut self) {
         let rx = self.load_rx.take();
         if let Some(rx) = rx {
@@ -130,13 +370,50 @@ impl ChartifyApp {
                         df,
                         columns,
                         row_count,
+                        file_count,
+                        warnings,
+                        quality,
+                        scan,
                     } => {
-                        self.loader.set_dataframe(df);
-                        self.control_panel.update_columns(columns.clone());
-                        self.control_panel.set_progress(
-                            0.0,
-                            &format!("Loaded {} rows, {} columns", row_count, columns.len()),
+                        self.data_preview.set_dataframe(&df);
+                        self.central_tab = CentralTab::Data;
+                        let streaming = scan.is_some();
+                        match scan {
+                            Some(scan) => {
+                                self.data_preview.set_total_rows(row_count);
+                                self.loader.set_streaming_source(*scan, df, row_count);
+                            }
+                            None => self.loader.set_dataframe(df),
+                        }
+                        self.schema_editor.set_schema(self.loader.get_schema());
+                        self.control_panel.update_columns(self.loader.get_schema());
+                        let mut status = format!(
+                            "{} {} rows, {} columns",
+                            if streaming { "Scanned" } else { "Loaded" },
+                            row_count,
+                            columns.len()
                         );
+                        if file_count > 1 {
+                            status.push_str(&format!(" from {} files", file_count));
+                        }
+                        if streaming {
+                            status.push_str(" (streaming)");
+                        }
+                        self.control_panel.set_progress(0.0, &status);
+                        self.control_panel.load_warnings = warnings;
+                        self.control_panel.load_quality = Some(quality);
+                        self.control_panel.analysis_exclusions.clear();
+                        self.control_panel.unassigned_rows = 0;
+
+                        // Group stacked files by their source unless a valid group is chosen
+                        if file_count > 1
+                            && !columns.contains(&self.control_panel.settings.group_col)
+                        {
+                            self.control_panel.settings.group_col = SOURCE_FILE_COLUMN.to_string();
+                            self.control_panel.settings.control_group.clear();
+                            self.handle_group_column_changed();
+                        }
+                        self.start_filter_count();
                         self.is_loading = false;
                         should_keep_receiver = false;
                     }
@@ -155,6 +432,32 @@ impl ChartifyApp {
         }
     }
 
+    /// Re-cast the loaded data with the roles chosen in the schema editor
+    fn handle_apply_schema(&mut self) {
+        match self.loader.apply_schema(self.schema_editor.schema()) {
+            Ok(df) => {
+                self.data_preview.set_dataframe(df);
+                if self.loader.is_streaming() {
+                    self.data_preview
+                        .set_total_rows(self.loader.get_row_count());
+                }
+                let schema = self.loader.get_schema();
+                self.schema_editor.set_schema(schema);
+                self.control_panel.update_columns(schema);
+                self.chart_viewer.clear();
+                self.control_panel.set_progress(0.0, "Column types applied");
+
+                // Refresh groups in case the group column changed type
+                self.handle_group_column_changed();
+                self.start_filter_count();
+            }
+            Err(e) => {
+                self.control_panel
+                    .set_progress(0.0, &format!("Error: {}", e));
+            }
+        }
+    }
+
     /// Handle group column change - update available groups
     fn handle_group_column_changed(&mut self) {
         let group_col = &self.control_panel.settings.group_col;
@@ -164,16 +467,157 @@ impl ChartifyApp {
         }
     }
 
+    /// Re-derive the current results from the settings that need no recalculation
+    fn handle_rules_changed(&mut self) {
+        let settings = &self.control_panel.settings;
+        let mut chart_data = std::mem::take(&mut self.chart_viewer.chart_data);
+        for data in chart_data.values_mut() {
+            data.stats.omnibus_method = settings.omnibus;
+            data.stats.effect_size_method = settings.effect_size;
+            data.stats.select_variance_test(settings.variance_test);
+            data.stats.apply_confidence_level(settings.ci_level);
+            data.stats.drop_stale_critical_value(settings.alpha);
+            data.stats
+                .apply_mismatch_rules(settings.alpha, &settings.mismatch_rules);
+            data.stats
+                .apply_equivalence(settings.alpha, &settings.equivalence);
+            let values = &data.data_by_group;
+            data.stats.select_columns(&settings.stat_columns, |group| {
+                values.get(group).map(Vec::as_slice)
+            });
+            data.stats
+                .apply_spec_limits(&settings.spec_limits, |group| {
+                    values.get(group).map(Vec::as_slice)
+                });
+        }
+        self.chart_viewer.set_chart_data(chart_data);
+    }
+
+    /// Calculate the current results again with the new settings, after the running
+    /// calculation when there is one
+    fn handle_recalculate(&mut self) {
+        if self.is_calculating {
+            self.recalculate_pending = true;
+        } else if !self.chart_viewer.chart_data.is_empty() {
+            self.start_calculation();
+        }
+    }
+
+    /// Read per data type equivalence margins from a table file
+    fn handle_import_margins(&mut self) {
+        let Some(table) = self.pick_data_type_table(&EquivalenceMargins::TABLE_COLUMNS) else {
+            return;
+        };
+
+        match table {
+            Ok(rows) => {
+                let count = self.control_panel.settings.equivalence.import(rows);
+                self.control_panel
+                    .set_progress(100.0, &format!("Imported margins for {} data types", count));
+                self.handle_rules_changed();
+            }
+            Err(e) => {
+                self.control_panel
+                    .set_progress(0.0, &format!("Margin import error: {}", e));
+            }
+        }
+    }
+
+    /// Read per data type spec limits from a table file
+    fn handle_import_specs(&mut self) {
+        let Some(table) = self.pick_data_type_table(&SpecTable::TABLE_COLUMNS) else {
+            return;
+        };
+
+        match table {
+            Ok(rows) => {
+                let count = self.control_panel.settings.spec_limits.import(rows);
+                self.control_panel.set_progress(
+                    100.0,
+                    &format!("Imported spec limits for {} data types", count),
+                );
+                self.handle_rules_changed();
+            }
+            Err(e) => {
+                self.control_panel
+                    .set_progress(0.0, &format!("Spec import error: {}", e));
+            }
+        }
+    }
+
+    /// Count the rows kept by the row filter in a background thread
+    fn start_filter_count(&mut self) {
+        self.filter_rx = None;
+        let filter = &self.control_panel.settings.row_filter;
+        let source = self
+            .loader
+            .get_lazy_frame()
+            .or_else(|| self.loader.get_dataframe().map(|df| df.clone().lazy()));
+        let Some(source) = source.filter(|_| !filter.is_empty()) else {
+            self.control_panel.filter_status = FilterStatus::Inactive;
+            return;
+        };
+
+        let filtered = match filter.apply(source) {
+            Ok(filtered) => filtered,
+            Err(e) => {
+                self.control_panel.filter_status = FilterStatus::Invalid(e.to_string());
+                return;
+            }
+        };
+        let total = self.loader.get_row_count();
+
+        let (tx, rx) = channel();
+        self.filter_rx = Some(rx);
+        self.control_panel.filter_status = FilterStatus::Counting;
+
+        thread::spawn(move || {
+            let rows = filtered
+                .select([len()])
+                .with_streaming(true)
+                .collect()
+                .and_then(|df| {
+                    let count = df.column("len")?.cast(&DataType::UInt64)?;
+                    Ok(count.u64()?.get(0).unwrap_or(0) as usize)
+                });
+            let _ = tx.send(rows.map(|rows| (rows, total)).map_err(|e| e.to_string()));
+        });
+    }
+
+    /// Check for the row filter count
+    fn check_filter_count(&mut self) {
+        let Some(rx) = &self.filter_rx else {
+            return;
+        };
+        match rx.try_recv() {
+            Ok(result) => {
+                self.control_panel.filter_status = match result {
+                    Ok((rows, total)) => FilterStatus::Matched { rows, total },
+                    Err(error) => FilterStatus::Invalid(error),
+                };
+                self.filter_rx = None;
+            }
+            Err(std::sync::mpsc::TryRecvError::Empty) => {}
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => self.filter_rx = None,
+        }
+    }
+
     /// Start calculation in background thread
     fn start_calculation(&mut self) {
         let settings = self.control_panel.settings.clone();
         let data_cols = self.control_panel.get_selected_data_cols();
 
-        // Get DataFrame clone
-        let Some(df) = self.loader.get_dataframe().cloned() else {
+        // Streaming mode scans the files again; otherwise work on a DataFrame clone
+        let lazy = self.loader.get_lazy_frame();
+        let df = if lazy.is_some() {
+            None
+        } else {
+            self.loader.get_dataframe().cloned()
+        };
+        if lazy.is_none() && df.is_none() {
             self.control_panel.set_progress(0.0, "No data loaded");
             return;
-        };
+        }
 
         let (tx, rx) = channel();
         self.calc_rx = Some(rx);
@@ -182,7 +626,11 @@ impl ChartifyApp {
 
         // Run calculation in background thread
         thread::spawn(move || {
-            Self::run_calculation(tx, df, settings, data_cols);
+            if let Some(lazy) = lazy {
+                Self::run_streaming_calculation(tx, lazy, settings, data_cols);
+            } else if let Some(df) = df {
+                Self::run_calculation(tx, df, settings, data_cols);
+            }
         });
     }
 
@@ -195,7 +643,21 @@ impl ChartifyApp {
     ) {
         let _ = tx.send(CalcResult::Progress(10.0, "Processing data...".to_string()));
 
+        // Keep only the rows selected by the row filter
+        let df = match settings
+            .row_filter
+            .apply(df.lazy())
+            .and_then(|lazy| Ok(lazy.collect()?))
+        {
+            Ok(df) => df,
+            Err(e) => {
+                let _ = tx.send(CalcResult::Error(e.to_string()));
+                return;
+            }
+        };
+
         // Process data
+        let pair_col = settings.pair_col();
         let processed_df = match settings.mode {
             DataMode::Single => DataProcessor::prepare_data(
                 &df,
@@ -204,6 +666,7 @@ impl ChartifyApp {
                 Some(&settings.data_type_col),
                 Some(&settings.value_col),
                 None,
+                pair_col,
             ),
             DataMode::Multi => DataProcessor::prepare_data(
                 &df,
@@ -212,11 +675,12 @@ impl ChartifyApp {
                 None,
                 None,
                 Some(&data_cols),
+                pair_col,
             ),
         };
 
-        let processed_df = match processed_df {
-            Ok(df) => df,
+        let prepared = match processed_df {
+            Ok(prepared) => prepared,
             Err(e) => {
                 let _ = tx.send(CalcResult::Error(e.to_string()));
                 return;
@@ -228,50 +692,280 @@ impl ChartifyApp {
             "Calculating statistics...".to_string(),
         ));
 
+        // Gather each group's values once, for both the statistics and the plots
+        let mut grouped = match StatsCalculator::group_values(&prepared.df) {
+            Ok(grouped) => grouped,
+            Err(e) => {
+                let _ = tx.send(CalcResult::Error(e.to_string()));
+                return;
+            }
+        };
+
+        // Find each group's outliers, taking them out of the values when excluded
+        let (mut outliers, positions) = settings.outliers.split(&mut grouped, settings.alpha);
+
+        // Match each group's values with the control's in paired mode
+        let mut paired = if pair_col.is_some() {
+            let pairing = if settings.outliers.excludes() {
+                DataProcessor::drop_rows(&prepared.df, &positions)
+            } else {
+                Ok(prepared.df.clone())
+            };
+            match pairing.and_then(|df| DataProcessor::pair_by_id(&df, &settings.control_group)) {
+                Ok(paired) => paired,
+                Err(e) => {
+                    let _ = tx.send(CalcResult::Error(e.to_string()));
+                    return;
+                }
+            }
+        } else {
+            PairedGroups::new()
+        };
+
         // Calculate statistics in parallel
-        let stats =
-            StatsCalculator::compute_all_stats_parallel(&processed_df, &settings.control_group);
+        let mut stats = StatsCalculator::compute_all_stats_parallel(
+            &grouped,
+            &settings.control_group,
+            settings.test,
+        );
+        if pair_col.is_some() {
+            StatsCalculator::apply_pairing(&mut stats, &paired, settings.test);
+        }
+        StatsCalculator::select_effect_size(&mut stats, settings.effect_size);
+        StatsCalculator::select_variance_test(&mut stats, settings.variance_test);
+        StatsCalculator::apply_confidence_level(&mut stats, settings.ci_level);
+        StatsCalculator::select_columns(&mut stats, &settings.stat_columns, |dt, g| {
+            grouped.get(dt)?.get(g).map(Vec::as_slice)
+        });
+        if settings.normality_fallback {
+            StatsCalculator::apply_normality_fallback(&mut stats, settings.alpha, |dt, g| {
+                grouped.get(dt)?.get(g).map(Vec::as_slice)
+            });
+        }
+        if settings.resampler.is_needed(settings.test) {
+            StatsCalculator::resample(
+                &mut stats,
+                &grouped,
+                &settings.resampler,
+                resampling_progress(&tx, 30.0, 50.0),
+            );
+        }
+        StatsCalculator::apply_correction(&mut stats, settings.correction);
+        StatsCalculator::attach_critical_values(&mut stats, settings.alpha);
+        StatsCalculator::select_omnibus(&mut stats, settings.omnibus);
+        StatsCalculator::apply_mismatch_rules(&mut stats, settings.alpha, &settings.mismatch_rules);
+        StatsCalculator::apply_equivalence(&mut stats, settings.alpha, &settings.equivalence);
+        StatsCalculator::apply_spec_limits(&mut stats, &settings.spec_limits, |dt, g| {
+            grouped.get(dt)?.get(g).map(Vec::as_slice)
+        });
+        StatsCalculator::attach_excluded_counts(&mut stats, &prepared.excluded);
+        StatsCalculator::attach_outliers(&mut stats, &settings.outliers, settings.alpha, &outliers);
 
         let _ = tx.send(CalcResult::Progress(
             50.0,
             "Generating charts...".to_string(),
         ));
 
-        // Generate chart data in parallel
+        // Hand each group's values over to its chart; this ensures the quantile plot
+        // data matches the statistics table
         let data_types: Vec<String> = stats.keys().cloned().collect();
+        let chart_data: HashMap<String, ChartData> = stats
+            .into_iter()
+            .map(|(data_type, stat)| {
+                let mut groups = grouped.remove(&data_type).unwrap_or_default();
+                let differences = paired
+                    .remove(&data_type)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(group, values)| (group, values.differences))
+                    .collect();
+                let outliers = outliers.remove(&data_type).unwrap_or_default();
+                let data_by_group = stat
+                    .get_ordered_groups()
+                    .into_iter()
+                    .map(|group| {
+                        let values = groups.remove(&group).unwrap_or_default();
+                        (group, values)
+                    })
+                    .collect();
 
-        let chart_data: HashMap<String, ChartData> = data_types
-            .par_iter()
-            .map(|data_type| {
-                let stat = stats.get(data_type).unwrap();
-                let mut data_by_group = HashMap::new();
-
-                for group in stat.get_ordered_groups() {
-                    // Use the new function that filters by BOTH data_type AND group
-                    // This ensures quantile plot data matches the statistics table
-                    let values = StatsCalculator::get_values_for_data_type_and_group(
-                        &processed_df,
+                (
+                    data_type.clone(),
+                    ChartData {
                         data_type,
-                        &group,
-                    )
+                        data_by_group,
+                        differences,
+                        outliers,
+                        stats: stat,
+                    },
+                )
+            })
+            .collect();
+
+        let exclusions = exclusions_by_data_type(&data_types, &prepared.excluded);
+
+        let _ = tx.send(CalcResult::Complete {
+            chart_data,
+            exclusions,
+            unassigned: prepared.unassigned,
+        });
+    }
+
+    /// Run calculation with the streaming engine over scanned files (called from background thread)
+    fn run_streaming_calculation(
+        tx: Sender<CalcResult>,
+        lazy: LazyFrame,
+        settings: crate::gui::control_panel::UserSettings,
+        data_cols: Vec<String>,
+    ) {
+        let _ = tx.send(CalcResult::Progress(10.0, "Building query...".to_string()));
+
+        if settings.pair_col().is_some() {
+            let _ = tx.send(CalcResult::Error(
+                "Paired mode needs the data in memory; turn off streaming mode".to_string(),
+            ));
+            return;
+        }
+        if settings.outliers.excludes() {
+            let _ = tx.send(CalcResult::Error(
+                "Excluding outliers needs the data in memory; turn off streaming mode".to_string(),
+            ));
+            return;
+        }
+
+        let lazy = match settings.row_filter.apply(lazy) {
+            Ok(lazy) => lazy,
+            Err(e) => {
+                let _ = tx.send(CalcResult::Error(e.to_string()));
+                return;
+            }
+        };
+
+        let long = match settings.mode {
+            DataMode::Single => DataProcessor::prepare_lazy(
+                lazy,
+                DataMode::Single,
+                &settings.group_col,
+                Some(&settings.data_type_col),
+                Some(&settings.value_col),
+                None,
+                None,
+            ),
+            DataMode::Multi => DataProcessor::prepare_lazy(
+                lazy,
+                DataMode::Multi,
+                &settings.group_col,
+                None,
+                None,
+                Some(&data_cols),
+                None,
+            ),
+        };
+        let long = match long {
+            Ok(long) => long,
+            Err(e) => {
+                let _ = tx.send(CalcResult::Error(e.to_string()));
+                return;
+            }
+        };
+
+        let _ = tx.send(CalcResult::Progress(
+            30.0,
+            "Streaming statistics...".to_string(),
+        ));
+
+        let result = match StreamingStats::compute(long, &settings.control_group, settings.test) {
+            Ok(result) => result,
+            Err(e) => {
+                let _ = tx.send(CalcResult::Error(e.to_string()));
+                return;
+            }
+        };
+        let mut stats = result.stats;
+        StatsCalculator::select_effect_size(&mut stats, settings.effect_size);
+        StatsCalculator::select_variance_test(&mut stats, settings.variance_test);
+        StatsCalculator::apply_confidence_level(&mut stats, settings.ci_level);
+        let sample = |data_type: &str, group: &str| {
+            result
+                .samples
+                .get(&(data_type.to_string(), group.to_string()))
+                .map(Vec::as_slice)
+        };
+        StatsCalculator::select_columns(&mut stats, &settings.stat_columns, sample);
+        if settings.normality_fallback {
+            StatsCalculator::apply_normality_fallback(&mut stats, settings.alpha, sample);
+        }
+        if settings.resampler.is_needed(settings.test) {
+            // Resample each group's sample
+            settings
+                .resampler
+                .run(&mut stats, sample, resampling_progress(&tx, 30.0, 90.0));
+        }
+        StatsCalculator::apply_correction(&mut stats, settings.correction);
+        StatsCalculator::attach_critical_values(&mut stats, settings.alpha);
+        StatsCalculator::select_omnibus(&mut stats, settings.omnibus);
+        StatsCalculator::apply_mismatch_rules(&mut stats, settings.alpha, &settings.mismatch_rules);
+        StatsCalculator::apply_equivalence(&mut stats, settings.alpha, &settings.equivalence);
+        StatsCalculator::apply_spec_limits(&mut stats, &settings.spec_limits, sample);
+        StatsCalculator::attach_excluded_counts(&mut stats, &result.excluded);
+
+        // Outliers are only marked, and only within each group's sample
+        let mut outliers = GroupedValues::new();
+        if settings.outliers.is_active() {
+            for ((data_type, group), values) in &result.samples {
+                let found = settings.outliers.rule.detect(values, settings.alpha);
+                outliers.entry(data_type.clone()).or_default().insert(
+                    group.clone(),
+                    found.into_iter().map(|i| values[i]).collect(),
+                );
+            }
+        }
+        StatsCalculator::attach_outliers(&mut stats, &settings.outliers, settings.alpha, &outliers);
+
+        let _ = tx.send(CalcResult::Progress(
+            90.0,
+            "Generating charts...".to_string(),
+        ));
+
+        // Plots show each group's sample rather than every value
+        let data_types: Vec<String> = stats.keys().cloned().collect();
+        let chart_data: HashMap<String, ChartData> = stats
+            .into_iter()
+            .map(|(data_type, stat)| {
+                let data_by_group = stat
+                    .get_ordered_groups()
                     .into_iter()
-                    .filter(|v| !v.is_nan())
+                    .map(|group| {
+                        let values = result
+                            .samples
+                            .get(&(data_type.clone(), group.clone()))
+                            .cloned()
+                            .unwrap_or_default();
+                        (group, values)
+                    })
                     .collect();
-                    data_by_group.insert(group, values);
-                }
+                let outliers = outliers.remove(&data_type).unwrap_or_default();
 
                 (
                     data_type.clone(),
                     ChartData {
-                        data_type: data_type.clone(),
+                        data_type,
                         data_by_group,
-                        stats: stat.clone(),
+                        differences: HashMap::new(),
+                        outliers,
+                        stats: stat,
                     },
                 )
             })
             .collect();
 
-        let _ = tx.send(CalcResult::Complete(chart_data));
+        let exclusions = exclusions_by_data_type(&data_types, &result.excluded);
+
+        let _ = tx.send(CalcResult::Complete {
+            chart_data,
+            exclusions,
+            unassigned: result.unassigned,
+        });
     }
 
     /// Check for calculation results
@@ -286,9 +980,19 @@ impl ChartifyApp {
                     CalcResult::Progress(progress, status) => {
                         self.control_panel.set_progress(progress, &status);
                     }
-                    CalcResult::Complete(chart_data) => {
+                    CalcResult::Complete {
+                        chart_data,
+                        exclusions,
+                        unassigned,
+                    } => {
                         let count = chart_data.len();
+                        self.control_panel.analysis_exclusions = exclusions;
+                        self.control_panel.unassigned_rows = unassigned;
+                        let mut data_types: Vec<String> = chart_data.keys().cloned().collect();
+                        data_types.sort();
+                        self.control_panel.data_types = data_types;
                         self.chart_viewer.set_chart_data(chart_data);
+                        self.central_tab = CentralTab::Charts;
                         self.control_panel
                             .set_progress(100.0, &format!("Complete! {} charts ready", count));
                         self.is_calculating = false;
@@ -308,6 +1012,11 @@ impl ChartifyApp {
                 self.calc_rx = Some(rx);
             }
         }
+
+        if self.recalculate_pending && !self.is_calculating {
+            self.recalculate_pending = false;
+            self.start_calculation();
+        }
     }
 
     /// Handle PPT export - render charts to memory and create PPT directly
@@ -408,13 +1117,18 @@ impl ChartifyApp {
 }
 
 impl eframe::App for ChartifyApp {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, CSV_OPTIONS_KEY, &self.csv_options_by_file);
+    }
+
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         // Check for background results
         self.check_load_results();
         self.check_calculation_results();
+        self.check_filter_count();
 
-        // Request repaint while loading or calculating
-        if self.is_loading || self.is_calculating {
+        // Request repaint while loading, calculating or counting filtered rows
+        if self.is_loading || self.is_calculating || self.filter_rx.is_some() {
             ctx.request_repaint();
         }
 
@@ -428,9 +1142,24 @@ impl eframe::App for ChartifyApp {
 
                     match action {
                         ControlPanelAction::BrowseCsv => self.handle_browse_csv(),
+                        ControlPanelAction::LoadPattern => self.handle_load_pattern(),
+                        ControlPanelAction::LoadSheet | ControlPanelAction::Reload => {
+                            self.start_loading()
+                        }
+                        ControlPanelAction::CsvOptions => {
+                            if !self.is_loading {
+                                let paths = self.control_panel.settings.csv_paths.clone();
+                                self.open_csv_dialog(paths);
+                            }
+                        }
                         ControlPanelAction::GroupColumnChanged => {
                             self.handle_group_column_changed()
                         }
+                        ControlPanelAction::FilterChanged => self.start_filter_count(),
+                        ControlPanelAction::RulesChanged => self.handle_rules_changed(),
+                        ControlPanelAction::Recalculate => self.handle_recalculate(),
+                        ControlPanelAction::ImportMargins => self.handle_import_margins(),
+                        ControlPanelAction::ImportSpecs => self.handle_import_specs(),
                         ControlPanelAction::Calculate => {
                             if !self.is_calculating {
                                 self.start_calculation();
@@ -452,9 +1181,74 @@ impl eframe::App for ChartifyApp {
                 });
             });
 
-        // Central panel - Chart Viewer
+        // CSV import options dialog
+        match self.csv_dialog.show(ctx) {
+            CsvDialogAction::Load => self.handle_csv_dialog_load(),
+            CsvDialogAction::Cancel | CsvDialogAction::None => {}
+        }
+
+        // Central panel - Chart Viewer and Data Preview tabs
         egui::CentralPanel::default().show(ctx, |ui| {
-            self.chart_viewer.show(ctx, ui);
+            ui.horizontal(|ui| {
+                ui.selectable_value(&mut self.central_tab, CentralTab::Charts, "📈 Charts");
+                ui.selectable_value(&mut self.central_tab, CentralTab::Data, "📋 Data");
+                ui.selectable_value(&mut self.central_tab, CentralTab::Schema, "🧬 Schema");
+            });
+            ui.separator();
+
+            match self.central_tab {
+                CentralTab::Charts => self.chart_viewer.show(ctx, ui),
+                CentralTab::Data => self.data_preview.show(ui),
+                CentralTab::Schema => {
+                    if self.schema_editor.show(ui) == SchemaEditorAction::Apply {
+                        self.handle_apply_schema();
+                    }
+                }
+            }
         });
     }
 }
+
+/// Dropped values summed per data type, for data types with any drops.
+fn exclusions_by_data_type(
+    data_types: &[String],
+    excluded: &HashMap<(String, String), DropCounts>,
+) -> Vec<(String, DropCounts)> {
+    let mut exclusions: Vec<(String, DropCounts)> = data_types
+        .iter()
+        .map(|data_type| {
+            let mut total = DropCounts::default();
+            for ((dt, _), counts) in excluded {
+                if dt == data_type {
+                    total.add(counts);
+                }
+            }
+            (data_type.clone(), total)
+        })
+        .filter(|(_, counts)| counts.total() > 0)
+        .collect();
+    exclusions.sort_by(|a, b| a.0.cmp(&b.0));
+    exclusions
+}
+
+/// Progress callback of the resampling engine, mapping (done, total) comparisons
+/// onto the [start, end] range of the progress bar.
+fn resampling_progress(
+    tx: &Sender<CalcResult>,
+    start: f32,
+    end: f32,
+) -> impl Fn(usize, usize) + Sync + '_ {
+    move |done, total| {
+        let progress = start + (end - start) * done as f32 / total.max(1) as f32;
+        let _ = tx.send(CalcResult::Progress(
+            progress,
+            format!("Resampling {done}/{total} comparisons..."),
+        ));
+    }
+}
+
+/// Key a file is remembered by: its canonical path, so the same file opened
+/// through another relative path or link is still recognised.
+fn storage_key(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
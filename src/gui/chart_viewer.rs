@@ -3,6 +3,7 @@
 //! Supports responsive multi-column layout based on available width.
 
 use crate::charts::{ChartData, ChartPlotter};
+use crate::stats::Outcome;
 use egui::{Color32, RichText, ScrollArea};
 use std::collections::HashMap;
 
@@ -10,23 +11,40 @@ use std::collections::HashMap;
 const CHART_SPACING: f32 = 15.0;
 const CARD_HEIGHT: f32 = 450.0; // Height for each card
 const CHART_WIDTH: f32 = 780.0; // Fixed width for each chart card
+const COMPACT_TITLE_WIDTH: f32 = 240.0; // Title column of a compact row
+const COMPACT_ROW_HEIGHT: f32 = 90.0; // Error bar plot height of a compact row
+
+/// How the charts are laid out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChartView {
+    /// Boxplot, QQ plot and stats table per data type
+    #[default]
+    Cards,
+    /// One row per data type with the mean difference error bars
+    Compact,
+}
+
+impl ChartView {
+    pub const ALL: [ChartView; 2] = [ChartView::Cards, ChartView::Compact];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ChartView::Cards => "🗂 Cards",
+            ChartView::Compact => "☰ Compact",
+        }
+    }
+}
 
 /// Scrollable chart display area with responsive multi-column layout.
 /// Automatically arranges charts into columns based on available width.
+#[derive(Default)]
 pub struct ChartViewer {
     /// Chart data for all data types
     pub chart_data: HashMap<String, ChartData>,
-    /// Order of data types (mismatch first, then match)
+    /// Order of data types (different first, then inconclusive, then equivalent)
     pub data_type_order: Vec<String>,
-}
-
-impl Default for ChartViewer {
-    fn default() -> Self {
-        Self {
-            chart_data: HashMap::new(),
-            data_type_order: Vec::new(),
-        }
-    }
+    /// Current layout
+    pub view: ChartView,
 }
 
 impl ChartViewer {
@@ -35,33 +53,34 @@ impl ChartViewer {
     }
 
     /// Clear all charts
-    #[allow(dead_code)]
     pub fn clear(&mut self) {
         self.chart_data.clear();
         self.data_type_order.clear();
     }
 
-    /// Set chart data with proper ordering (mismatch first)
+    /// Set chart data with proper ordering (different first, then inconclusive,
+    /// then equivalent)
     pub fn set_chart_data(&mut self, chart_data: HashMap<String, ChartData>) {
-        let mut mismatch: Vec<String> = Vec::new();
-        let mut match_items: Vec<String> = Vec::new();
-
-        for (data_type, data) in &chart_data {
-            if data.stats.has_significant_results() {
-                mismatch.push(data_type.clone());
-            } else {
-                match_items.push(data_type.clone());
-            }
-        }
-
-        mismatch.sort();
-        match_items.sort();
+        let mut order: Vec<(u8, String)> = chart_data
+            .iter()
+            .map(|(data_type, data)| (data.stats.outcome().rank(), data_type.clone()))
+            .collect();
+        order.sort();
 
-        self.data_type_order = mismatch;
-        self.data_type_order.extend(match_items);
+        self.data_type_order = order.into_iter().map(|(_, data_type)| data_type).collect();
         self.chart_data = chart_data;
     }
 
+    /// Border color and title icon of an outcome
+    fn outcome_style(outcome: Outcome) -> (Color32, &'static str) {
+        let icon = match outcome {
+            Outcome::Different => "⚠",
+            Outcome::Inconclusive => "?",
+            Outcome::Equivalent => "✓",
+        };
+        (ChartPlotter::outcome_color(outcome), icon)
+    }
+
     /// Draw the chart viewer with responsive multi-column layout
     /// Charts have fixed width and automatically wrap to multiple columns
     pub fn show(&mut self, _ctx: &egui::Context, ui: &mut egui::Ui) {
@@ -72,6 +91,18 @@ impl ChartViewer {
             return;
         }
 
+        ui.horizontal(|ui| {
+            for view in ChartView::ALL {
+                ui.selectable_value(&mut self.view, view, view.name());
+            }
+        });
+        ui.add_space(5.0);
+
+        if self.view == ChartView::Compact {
+            self.show_compact(ui);
+            return;
+        }
+
         // Calculate how many columns fit in available width
         let avail_width = ui.available_width();
         let card_total_width = CHART_WIDTH + CHART_SPACING;
@@ -79,7 +110,7 @@ impl ChartViewer {
 
         // Calculate number of rows needed
         let total_items = self.data_type_order.len();
-        let total_rows = (total_items + num_columns - 1) / num_columns; // Ceiling division
+        let total_rows = total_items.div_ceil(num_columns);
         let row_height = CARD_HEIGHT + CHART_SPACING;
 
         // Clone data for use in closure
@@ -96,8 +127,7 @@ impl ChartViewer {
                             if idx < total_items {
                                 if let Some(dt) = order.get(idx) {
                                     if let Some(data) = chart_data.get(dt) {
-                                        let is_sig = data.stats.has_significant_results();
-                                        Self::draw_chart_card_fixed_width(ui, data, is_sig);
+                                        Self::draw_chart_card_fixed_width(ui, data);
                                     }
                                 }
                                 ui.add_space(CHART_SPACING);
@@ -109,13 +139,69 @@ impl ChartViewer {
             });
     }
 
+    /// Draw one row per data type: title and omnibus result beside the mean
+    /// difference error bars of every group
+    fn show_compact(&self, ui: &mut egui::Ui) {
+        let max_groups = self
+            .chart_data
+            .values()
+            .map(|data| data.stats.group_stats.len().saturating_sub(1))
+            .max()
+            .unwrap_or(1);
+        let plot_height = COMPACT_ROW_HEIGHT.max(30.0 + 22.0 * max_groups as f32);
+        let row_height = plot_height + 24.0 + CHART_SPACING / 2.0;
+
+        ScrollArea::vertical()
+            .auto_shrink([false, false])
+            .show_rows(
+                ui,
+                row_height,
+                self.data_type_order.len(),
+                |ui, row_range| {
+                    for dt in &self.data_type_order[row_range] {
+                        if let Some(data) = self.chart_data.get(dt) {
+                            Self::draw_compact_row(ui, data, plot_height);
+                        }
+                        ui.add_space(CHART_SPACING / 2.0);
+                    }
+                },
+            );
+    }
+
+    /// Draw a compact row with the data type's error bars
+    fn draw_compact_row(ui: &mut egui::Ui, chart_data: &ChartData, plot_height: f32) {
+        let (border_color, icon) = Self::outcome_style(chart_data.stats.outcome());
+
+        egui::Frame::none()
+            .rounding(6.0)
+            .stroke(egui::Stroke::new(1.5, border_color))
+            .fill(ui.visuals().widgets.noninteractive.bg_fill)
+            .inner_margin(8.0)
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.vertical(|ui| {
+                        ui.set_width(COMPACT_TITLE_WIDTH);
+                        ui.label(
+                            RichText::new(format!("{} {}", icon, chart_data.data_type))
+                                .size(14.0)
+                                .strong()
+                                .color(border_color),
+                        );
+                        if let Some(omnibus) = chart_data.stats.omnibus() {
+                            ui.label(RichText::new(omnibus.label()).size(11.0).weak());
+                        }
+                    });
+                    ui.vertical(|ui| {
+                        ChartPlotter::draw_ci_chart(ui, chart_data, plot_height);
+                    });
+                });
+            });
+    }
+
     /// Draw a single chart card with fixed width
-    fn draw_chart_card_fixed_width(ui: &mut egui::Ui, chart_data: &ChartData, is_sig: bool) {
-        let border_color = if is_sig {
-            Color32::from_rgb(220, 53, 69) // Red for significant
-        } else {
-            Color32::from_rgb(40, 167, 69) // Green for match
-        };
+    fn draw_chart_card_fixed_width(ui: &mut egui::Ui, chart_data: &ChartData) {
+        let outcome = chart_data.stats.outcome();
+        let (border_color, icon) = Self::outcome_style(outcome);
 
         // Fixed card width
         let card_width = CHART_WIDTH - 20.0;
@@ -131,14 +217,19 @@ impl ChartViewer {
 
                 ui.vertical(|ui| {
                     // Title with icon - larger font
-                    let icon = if is_sig { "⚠" } else { "✓" };
                     ui.horizontal(|ui| {
                         ui.label(
                             RichText::new(format!("{} Analysis: {}", icon, chart_data.data_type))
                                 .size(18.0)
                                 .strong()
                                 .color(border_color),
-                        );
+                        )
+                        .on_hover_text(outcome.name());
+                        if let Some(omnibus) = chart_data.stats.omnibus() {
+                            ui.add_space(12.0);
+                            ui.label(RichText::new(omnibus.label()).size(13.0).weak())
+                                .on_hover_text("Omnibus test across all groups");
+                        }
                     });
 
                     ui.add_space(8.0);
@@ -172,7 +263,11 @@ impl ChartViewer {
                         // Boxplot - narrower by 15px
                         ui.vertical(|ui| {
                             ui.set_width(chart_width - 15.0);
-                            ui.label(RichText::new("Distribution by Group").size(14.0).strong());
+                            ui.label(
+                                RichText::new(chart_data.distribution_title())
+                                    .size(14.0)
+                                    .strong(),
+                            );
                             ChartPlotter::draw_boxplot_chart(ui, chart_data, true);
                         });
 
@@ -1,28 +1,149 @@
 //! Control Panel Widget
 //! Left side panel with all input controls and settings.
 
-use crate::data::DataMode;
+use crate::data::{
+    ColumnRole, ColumnSchema, Connector, CsvOptions, DataMode, DataQualityReport, DropCounts,
+    FilterCondition, FilterMode, FilterOperator, LoadOptions, RowFilter,
+};
+use crate::stats::{
+    CorrectionMethod, EffectSizeMethod, EquivalenceMargins, MarginUnit, MismatchRule,
+    MismatchRules, OmnibusMethod, OutlierFilter, OutlierRule, Resampler, SpecTable, StatColumn,
+    TestMethod, VarianceTest, DEFAULT_ALPHA, DEFAULT_CI_LEVEL,
+};
 use egui::{Color32, ComboBox, RichText, ScrollArea};
 use std::path::PathBuf;
 
 /// User settings for analysis
-#[derive(Default, Clone)]
+#[derive(Clone)]
 pub struct UserSettings {
-    pub csv_path: Option<PathBuf>,
+    pub csv_paths: Vec<PathBuf>,
+    pub file_pattern: String,
+    pub sheet_name: String,
+    pub header_row: usize,
+    pub csv_options: CsvOptions,
+    /// Scan files from disk with the streaming engine instead of loading them
+    pub streaming: bool,
     pub mode: DataMode,
     pub group_col: String,
     pub control_group: String,
     pub data_type_col: String,
     pub value_col: String,
-    #[allow(dead_code)]
-    pub data_cols: Vec<String>,
+    /// Column identifying the unit measured in every group (wafer, patient) for
+    /// paired tests; empty for independent groups
+    pub pair_id_col: String,
+    /// Test comparing each group with the control
+    pub test: TestMethod,
+    /// Switch t-tests and Dunnett to Mann–Whitney U, and paired t to Wilcoxon
+    /// signed-rank, when normality is rejected
+    pub normality_fallback: bool,
+    /// Outlier rule and whether outliers are left out of the statistics
+    pub outliers: OutlierFilter,
+    /// Omnibus test across all groups of a data type
+    pub omnibus: OmnibusMethod,
+    /// Multiple-testing correction across all comparisons
+    pub correction: CorrectionMethod,
+    /// Significance level
+    pub alpha: f64,
+    /// Confidence level of the mean difference and effect size intervals
+    pub ci_level: f64,
+    /// Effect size shown in the stats tables and used by the mismatch rules
+    pub effect_size: EffectSizeMethod,
+    /// Variance test shown in the stats tables and used by the mismatch rules
+    pub variance_test: VarianceTest,
+    /// Descriptive columns of the stats tables, in `StatColumn::ALL` order
+    pub stat_columns: Vec<StatColumn>,
+    /// Bootstrap and permutation settings
+    pub resampler: Resampler,
+    /// When a group counts as a mismatch
+    pub mismatch_rules: MismatchRules,
+    /// Equivalence margins of the TOST
+    pub equivalence: EquivalenceMargins,
+    /// Spec limits of the capability indices
+    pub spec_limits: SpecTable,
+    /// Rows kept for analysis
+    pub row_filter: RowFilter,
+}
+
+impl Default for UserSettings {
+    fn default() -> Self {
+        Self {
+            csv_paths: Vec::new(),
+            file_pattern: String::new(),
+            sheet_name: String::new(),
+            header_row: 0,
+            csv_options: CsvOptions::default(),
+            streaming: false,
+            mode: DataMode::default(),
+            group_col: String::new(),
+            control_group: String::new(),
+            data_type_col: String::new(),
+            value_col: String::new(),
+            pair_id_col: String::new(),
+            test: TestMethod::default(),
+            normality_fallback: false,
+            outliers: OutlierFilter::default(),
+            omnibus: OmnibusMethod::default(),
+            correction: CorrectionMethod::default(),
+            alpha: DEFAULT_ALPHA,
+            ci_level: DEFAULT_CI_LEVEL,
+            effect_size: EffectSizeMethod::default(),
+            variance_test: VarianceTest::default(),
+            stat_columns: StatColumn::DEFAULT.to_vec(),
+            resampler: Resampler::default(),
+            mismatch_rules: MismatchRules::default(),
+            equivalence: EquivalenceMargins::default(),
+            spec_limits: SpecTable::default(),
+            row_filter: RowFilter::default(),
+        }
+    }
+}
+
+impl UserSettings {
+    /// The pair ID column, in paired mode.
+    pub fn pair_col(&self) -> Option<&str> {
+        (!self.pair_id_col.is_empty()).then_some(self.pair_id_col.as_str())
+    }
+
+    /// Whether the results depend on α beyond what `RulesChanged` re-derives: the
+    /// Dunnett critical value, the normality fallback's choice of test and the
+    /// Grubbs and ESD outliers all need a recalculation.
+    pub fn alpha_needs_calculation(&self) -> bool {
+        self.test == TestMethod::Dunnett
+            || self.normality_fallback
+            || self.outliers.rule.uses_alpha()
+    }
+}
+
+/// Rows kept by the row filter, as last counted
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum FilterStatus {
+    /// No filter; every row is analysed
+    #[default]
+    Inactive,
+    Counting,
+    Matched {
+        rows: usize,
+        total: usize,
+    },
+    Invalid(String),
 }
 
 /// Left side control panel with file selection and processing controls.
 pub struct ControlPanel {
     pub settings: UserSettings,
     pub columns: Vec<String>,
+    /// Role of each entry in `columns`
+    pub column_roles: Vec<ColumnRole>,
     pub groups: Vec<String>,
+    pub sheets: Vec<String>,
+    pub load_warnings: Vec<String>,
+    pub csv_source: bool,
+    pub load_quality: Option<DataQualityReport>,
+    pub analysis_exclusions: Vec<(String, DropCounts)>,
+    pub unassigned_rows: usize,
+    pub filter_status: FilterStatus,
+    /// Data types of the last calculation, for per data type tolerances
+    pub data_types: Vec<String>,
     pub selected_data_cols: Vec<bool>,
     pub progress: f32,
     pub status: String,
@@ -34,7 +155,16 @@ impl Default for ControlPanel {
         Self {
             settings: UserSettings::default(),
             columns: Vec::new(),
+            column_roles: Vec::new(),
             groups: Vec::new(),
+            sheets: Vec::new(),
+            load_warnings: Vec::new(),
+            csv_source: false,
+            load_quality: None,
+            analysis_exclusions: Vec::new(),
+            unassigned_rows: 0,
+            filter_status: FilterStatus::default(),
+            data_types: Vec::new(),
             selected_data_cols: Vec::new(),
             progress: 0.0,
             status: "Ready".to_string(),
@@ -49,10 +179,33 @@ impl ControlPanel {
     }
 
     /// Update available columns after CSV load
-    pub fn update_columns(&mut self, columns: Vec<String>) {
-        self.columns = columns.clone();
-        self.selected_data_cols = vec![false; columns.len()];
-        self.calculate_enabled = !columns.is_empty();
+    pub fn update_columns(&mut self, schema: &[ColumnSchema]) {
+        let previously_selected = self.get_selected_data_cols();
+        let columns: Vec<&ColumnSchema> = schema
+            .iter()
+            .filter(|column| column.role != ColumnRole::Ignore)
+            .collect();
+
+        // Keep data column selections that are still numeric
+        self.selected_data_cols = columns
+            .iter()
+            .map(|column| {
+                column.role == ColumnRole::Numeric && previously_selected.contains(&column.name)
+            })
+            .collect();
+        self.columns = columns.iter().map(|column| column.name.clone()).collect();
+        self.column_roles = columns.iter().map(|column| column.role).collect();
+        self.calculate_enabled = !self.columns.is_empty();
+    }
+
+    /// Get names of the columns with one of the given roles
+    fn columns_with_roles(&self, roles: &[ColumnRole]) -> Vec<String> {
+        self.columns
+            .iter()
+            .zip(&self.column_roles)
+            .filter(|(_, role)| roles.contains(role))
+            .map(|(name, _)| name.clone())
+            .collect()
     }
 
     /// Update available groups
@@ -63,6 +216,22 @@ impl ControlPanel {
         }
     }
 
+    /// Update available worksheets after an Excel workbook is chosen
+    pub fn update_sheets(&mut self, sheets: Vec<String>) {
+        self.settings.sheet_name = sheets.first().cloned().unwrap_or_default();
+        self.settings.header_row = 1;
+        self.sheets = sheets;
+    }
+
+    /// Build file loading options from the current settings
+    pub fn load_options(&self) -> LoadOptions {
+        LoadOptions {
+            sheet: (!self.settings.sheet_name.is_empty()).then(|| self.settings.sheet_name.clone()),
+            header_row: self.settings.header_row.max(1),
+            csv: self.settings.csv_options.clone(),
+        }
+    }
+
     /// Get selected data columns for multi mode
     pub fn get_selected_data_cols(&self) -> Vec<String> {
         self.columns
@@ -105,16 +274,17 @@ impl ControlPanel {
             .inner_margin(8.0)
             .show(ui, |ui| {
                 ui.horizontal(|ui| {
-                    let path_text = self
-                        .settings
-                        .csv_path
-                        .as_ref()
-                        .and_then(|p| p.file_name())
-                        .map(|n| n.to_string_lossy().to_string())
-                        .unwrap_or_else(|| "No file selected".to_string());
+                    let path_text = match self.settings.csv_paths.as_slice() {
+                        [] => "No file selected".to_string(),
+                        [path] => path
+                            .file_name()
+                            .map(|n| n.to_string_lossy().to_string())
+                            .unwrap_or_default(),
+                        paths => format!("{} files", paths.len()),
+                    };
 
                     ui.label(RichText::new(&path_text).size(12.0).color(
-                        if self.settings.csv_path.is_some() {
+                        if !self.settings.csv_paths.is_empty() {
                             ui.visuals().text_color()
                         } else {
                             Color32::GRAY
@@ -125,8 +295,129 @@ impl ControlPanel {
                         if ui.button("📂 Browse").clicked() {
                             action = ControlPanelAction::BrowseCsv;
                         }
+                        if self.csv_source
+                            && ui.button("⚙").on_hover_text("CSV import options").clicked()
+                        {
+                            action = ControlPanelAction::CsvOptions;
+                        }
+                    });
+                });
+
+                // Glob pattern for loading many files at once
+                ui.add_space(5.0);
+                ui.horizontal(|ui| {
+                    ui.add_sized([80.0, 20.0], egui::Label::new("Pattern:"));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.settings.file_pattern)
+                            .desired_width(150.0)
+                            .hint_text("data/lot_*.csv"),
+                    )
+                    .on_hover_text("Load and concatenate every file matching a glob pattern");
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui
+                            .add_enabled(
+                                !self.settings.file_pattern.trim().is_empty(),
+                                egui::Button::new("Load"),
+                            )
+                            .clicked()
+                        {
+                            action = ControlPanelAction::LoadPattern;
+                        }
                     });
                 });
+
+                // Spec limits for the capability indices
+                ui.add_space(5.0);
+                ui.horizontal(|ui| {
+                    if ui
+                        .button("📥 Import specs")
+                        .on_hover_text(
+                            "Spec limits from a table with a data_type column and LSL,\n\
+                             target and USL columns (either limit may be empty)",
+                        )
+                        .clicked()
+                    {
+                        action = ControlPanelAction::ImportSpecs;
+                    }
+                    let specs = &mut self.settings.spec_limits;
+                    if !specs.limits.is_empty() {
+                        ui.label(
+                            RichText::new(format!("{} data types", specs.limits.len()))
+                                .size(11.0)
+                                .color(Color32::GRAY),
+                        );
+                        if ui
+                            .small_button("✖")
+                            .on_hover_text("Clear the spec limits")
+                            .clicked()
+                        {
+                            specs.limits.clear();
+                            action = ControlPanelAction::RulesChanged;
+                        }
+                    }
+                });
+
+                // Out-of-core analysis for files larger than memory
+                ui.add_space(5.0);
+                if ui
+                    .checkbox(&mut self.settings.streaming, "Streaming mode")
+                    .on_hover_text(
+                        "Analyse files from disk without loading them into memory.\n\
+                         Quantiles and plots use a sample of each group.",
+                    )
+                    .changed()
+                    && !self.settings.csv_paths.is_empty()
+                {
+                    action = ControlPanelAction::Reload;
+                }
+
+                // Worksheet picker (Excel workbooks only)
+                if !self.sheets.is_empty() {
+                    ui.add_space(5.0);
+                    ui.horizontal(|ui| {
+                        ui.add_sized([80.0, 20.0], egui::Label::new("Sheet:"));
+                        ComboBox::from_id_salt("sheet_name")
+                            .width(150.0)
+                            .selected_text(&self.settings.sheet_name)
+                            .show_ui(ui, |ui| {
+                                for sheet in &self.sheets {
+                                    if ui
+                                        .selectable_label(self.settings.sheet_name == *sheet, sheet)
+                                        .clicked()
+                                    {
+                                        self.settings.sheet_name = sheet.clone();
+                                        action = ControlPanelAction::LoadSheet;
+                                    }
+                                }
+                            });
+                    });
+                    ui.horizontal(|ui| {
+                        ui.add_sized([80.0, 20.0], egui::Label::new("Header Row:"));
+                        ui.add(
+                            egui::DragValue::new(&mut self.settings.header_row).range(1..=10000),
+                        )
+                        .on_hover_text("Sheet row holding the column names; blank rows above the data are skipped");
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            if ui.button("↻ Load Sheet").clicked() {
+                                action = ControlPanelAction::LoadSheet;
+                            }
+                        });
+                    });
+                }
+
+                // Schema differences between concatenated files
+                if !self.load_warnings.is_empty() {
+                    ui.add_space(5.0);
+                    ui.collapsing(
+                        RichText::new(format!("⚠ {} schema mismatches", self.load_warnings.len()))
+                            .color(Color32::from_rgb(230, 160, 60)),
+                        |ui| {
+                            for warning in &self.load_warnings {
+                                ui.label(RichText::new(warning).size(11.0));
+                            }
+                        },
+                    );
+                }
             });
 
         ui.add_space(15.0);
@@ -146,6 +437,369 @@ impl ControlPanel {
         ui.separator();
         ui.add_space(10.0);
 
+        // ===== Statistics Section =====
+        ui.label(RichText::new("📐 Statistics").size(14.0).strong());
+        ui.add_space(5.0);
+
+        // Paired data takes the paired tests, independent groups the others
+        let paired = self.settings.pair_col().is_some();
+        if self.settings.test.is_paired() != paired {
+            self.settings.test = if paired {
+                TestMethod::PairedT
+            } else {
+                TestMethod::default()
+            };
+        }
+        let tests: &[TestMethod] = if paired {
+            &TestMethod::PAIRED
+        } else {
+            &TestMethod::ALL
+        };
+
+        ui.horizontal(|ui| {
+            ui.add_sized([110.0, 20.0], egui::Label::new("Test:"));
+            ComboBox::from_id_salt("test_method")
+                .width(150.0)
+                .selected_text(self.settings.test.name())
+                .show_ui(ui, |ui| {
+                    for &method in tests {
+                        ui.selectable_value(&mut self.settings.test, method, method.name());
+                    }
+                })
+                .response
+                .on_hover_text(
+                    "Test comparing each group with the control.\n\
+                     Student's t assumes equal variances, Welch's t does not.\n\
+                     Mann–Whitney U and Kolmogorov–Smirnov suit skewed or discrete data;\n\
+                     in streaming mode they run on each group's sample.\n\
+                     Dunnett compares all groups with the control together, controlling\n\
+                     the family-wise error rate within each data type.\n\
+                     Permutation shuffles the group labels; it makes no distribution\n\
+                     assumption but takes longer.\n\
+                     With a Pair ID Column, paired t and Wilcoxon signed-rank compare\n\
+                     the differences of the values matched by ID.",
+                );
+        });
+
+        ui.add_space(5.0);
+        ui.checkbox(
+            &mut self.settings.normality_fallback,
+            "Rank test when normality is rejected",
+        )
+        .on_hover_text(
+            "Use Mann–Whitney U instead of the t-tests or Dunnett for data types where\n\
+             any group fails the normality test at α (Shapiro–Wilk up to 5000 values,\n\
+             Anderson–Darling above). Paired t falls back to Wilcoxon signed-rank when\n\
+             the differences of any group fail it. The test is chosen when the results\n\
+             are calculated, so changing α calculates them again.",
+        );
+
+        ui.add_space(5.0);
+        ui.horizontal(|ui| {
+            ui.add_sized([110.0, 20.0], egui::Label::new("Outliers:"));
+            ComboBox::from_id_salt("outlier_rule")
+                .width(150.0)
+                .selected_text(self.settings.outliers.rule.name())
+                .show_ui(ui, |ui| {
+                    for rule in OutlierRule::ALL {
+                        ui.selectable_value(&mut self.settings.outliers.rule, rule, rule.name());
+                    }
+                })
+                .response
+                .on_hover_text(
+                    "Rule finding outliers within each group, marked with crosses in the\n\
+                     boxplot and QQ plot. Tukey fences lie 1.5 × IQR beyond the quartiles;\n\
+                     the modified z-score uses the median and MAD. Grubbs and generalized\n\
+                     ESD test the most extreme values at α, assuming normal data; ESD looks\n\
+                     for up to 10% of the values and is not fooled by outliers masking\n\
+                     each other; changing α finds them again. In streaming mode outliers are\n\
+                     found in each group's sample.",
+                );
+        });
+
+        ui.add_space(5.0);
+        ui.add_enabled(
+            self.settings.outliers.is_active(),
+            egui::Checkbox::new(
+                &mut self.settings.outliers.exclude,
+                "Exclude outliers from the statistics",
+            ),
+        )
+        .on_hover_text(
+            "Leave outliers out of N and every statistic and test; the \"Outl.\" column\n\
+             counts them either way. Needs the data in memory.",
+        );
+
+        ui.add_space(5.0);
+        ui.horizontal(|ui| {
+            ui.add_sized([110.0, 20.0], egui::Label::new("Omnibus:"));
+            let before = self.settings.omnibus;
+            ComboBox::from_id_salt("omnibus_method")
+                .width(150.0)
+                .selected_text(self.settings.omnibus.name())
+                .show_ui(ui, |ui| {
+                    for method in OmnibusMethod::ALL {
+                        ui.selectable_value(&mut self.settings.omnibus, method, method.name());
+                    }
+                })
+                .response
+                .on_hover_text(
+                    "Test whether any group differs, across all groups of a data type\n\
+                     (three or more). Shown in the card titles and usable as a rule.",
+                );
+            if self.settings.omnibus != before {
+                action = ControlPanelAction::RulesChanged;
+            }
+        });
+
+        ui.add_space(5.0);
+        ui.horizontal(|ui| {
+            ui.add_sized([110.0, 20.0], egui::Label::new("Effect size:"));
+            let before = self.settings.effect_size;
+            ComboBox::from_id_salt("effect_size")
+                .width(150.0)
+                .selected_text(self.settings.effect_size.name())
+                .show_ui(ui, |ui| {
+                    for method in EffectSizeMethod::ALL {
+                        ui.selectable_value(&mut self.settings.effect_size, method, method.name());
+                    }
+                })
+                .response
+                .on_hover_text(
+                    "Cohen's d and Hedges' g use the pooled standard deviation,\n\
+                     Glass's Δ the control's; Cliff's δ compares ranks and suits\n\
+                     skewed or discrete data.",
+                );
+            if self.settings.effect_size != before {
+                action = ControlPanelAction::RulesChanged;
+            }
+        });
+
+        ui.add_space(5.0);
+        ui.horizontal(|ui| {
+            ui.add_sized([110.0, 20.0], egui::Label::new("Variance test:"));
+            let before = self.settings.variance_test;
+            ComboBox::from_id_salt("variance_test")
+                .width(150.0)
+                .selected_text(self.settings.variance_test.name())
+                .show_ui(ui, |ui| {
+                    for test in VarianceTest::ALL {
+                        ui.selectable_value(&mut self.settings.variance_test, test, test.name());
+                    }
+                })
+                .response
+                .on_hover_text(
+                    "Test of each group's spread against the control's, shown as \"Var. p\".\n\
+                     The F-test assumes normal groups; Levene and Brown–Forsythe\n\
+                     (deviations from the median) are robust to skew.",
+                );
+            if self.settings.variance_test != before {
+                action = ControlPanelAction::RulesChanged;
+            }
+        });
+
+        ui.add_space(5.0);
+        ui.horizontal(|ui| {
+            ui.add_sized([110.0, 20.0], egui::Label::new("Table columns:"));
+            let before = self.settings.stat_columns.clone();
+            ComboBox::from_id_salt("stat_columns")
+                .width(150.0)
+                .selected_text(format!("{} statistics", before.len()))
+                .close_behavior(egui::PopupCloseBehavior::CloseOnClickOutside)
+                .show_ui(ui, |ui| {
+                    let mut columns = Vec::new();
+                    for column in StatColumn::ALL {
+                        let mut shown = before.contains(&column);
+                        ui.checkbox(&mut shown, column.name())
+                            .on_hover_text(column.description());
+                        if shown {
+                            columns.push(column);
+                        }
+                    }
+                    self.settings.stat_columns = columns;
+                })
+                .response
+                .on_hover_text(
+                    "Descriptive statistics shown in the stats tables and the exported\n\
+                     cards. Trimmed and winsorized means, MAD and IQR σ resist outliers;\n\
+                     in streaming mode all but the mean and Std come from the sample.",
+                );
+            if self.settings.stat_columns != before {
+                action = ControlPanelAction::RulesChanged;
+            }
+        });
+
+        ui.add_space(5.0);
+        ui.horizontal(|ui| {
+            ui.add_sized([110.0, 20.0], egui::Label::new("Correction:"));
+            ComboBox::from_id_salt("correction")
+                .width(150.0)
+                .selected_text(self.settings.correction.name())
+                .show_ui(ui, |ui| {
+                    for method in CorrectionMethod::ALL {
+                        ui.selectable_value(&mut self.settings.correction, method, method.name());
+                    }
+                })
+                .response
+                .on_hover_text(
+                    "Adjust p-values across every data type and group compared in a run.\n\
+                     Bonferroni and Holm control the family-wise error rate,\n\
+                     Benjamini–Hochberg the false discovery rate.\n\
+                     Dunnett's p-values are already adjusted and are left as they are.",
+                );
+        });
+        if self.settings.test == TestMethod::Dunnett
+            && self.settings.correction != CorrectionMethod::None
+        {
+            ui.label(
+                RichText::new(
+                    "Dunnett's p-values are already adjusted and are not corrected again",
+                )
+                .size(11.0)
+                .color(Color32::from_rgb(230, 160, 60)),
+            );
+        }
+
+        ui.add_space(5.0);
+        ui.horizontal(|ui| {
+            ui.add_sized([110.0, 20.0], egui::Label::new("Alpha (α):"));
+            if ui
+                .add(
+                    egui::DragValue::new(&mut self.settings.alpha)
+                        .range(0.0001..=0.5)
+                        .speed(0.001)
+                        .max_decimals(4),
+                )
+                .on_hover_text(
+                    "Significance level for the (adjusted) p-value.\n\
+                     With Dunnett's test, the normality fallback or a Grubbs or ESD\n\
+                     outlier rule, changing it calculates the results again.",
+                )
+                .changed()
+            {
+                action = if self.settings.alpha_needs_calculation() {
+                    ControlPanelAction::Recalculate
+                } else {
+                    ControlPanelAction::RulesChanged
+                };
+            }
+        });
+
+        ui.add_space(5.0);
+        ui.horizontal(|ui| {
+            ui.add_sized([110.0, 20.0], egui::Label::new("CI level:"));
+            let mut percent = self.settings.ci_level * 100.0;
+            if ui
+                .add(
+                    egui::DragValue::new(&mut percent)
+                        .range(50.0..=99.9)
+                        .speed(0.1)
+                        .max_decimals(1)
+                        .suffix(" %"),
+                )
+                .on_hover_text("Confidence level of the mean difference and effect size intervals")
+                .changed()
+            {
+                self.settings.ci_level = percent / 100.0;
+                action = ControlPanelAction::RulesChanged;
+            }
+        });
+
+        ui.add_space(5.0);
+        ui.horizontal(|ui| {
+            ui.add_sized([110.0, 20.0], egui::Label::new("Iterations:"));
+            ui.add(
+                egui::DragValue::new(&mut self.settings.resampler.iterations)
+                    .range(100..=100_000)
+                    .speed(50.0),
+            )
+            .on_hover_text("Bootstrap resamples and permutations per comparison");
+        });
+
+        ui.add_space(5.0);
+        ui.horizontal(|ui| {
+            ui.add_sized([110.0, 20.0], egui::Label::new("Seed:"));
+            ui.add(egui::DragValue::new(&mut self.settings.resampler.seed))
+                .on_hover_text("The same seed reproduces the same intervals and p-values");
+        });
+
+        ui.add_space(5.0);
+        ui.add_enabled(
+            !paired,
+            egui::Checkbox::new(
+                &mut self.settings.resampler.bootstrap,
+                "Bootstrap CIs of the mean and median differences",
+            ),
+        )
+        .on_hover_text(
+            "Bias-corrected and accelerated (BCa) bootstrap intervals at the CI level,\n\
+             for small or skewed groups. In streaming mode they use each group's sample.",
+        )
+        .on_disabled_hover_text("The bootstrap resamples independent groups, not pairs");
+
+        ui.add_space(5.0);
+        if self.show_mismatch_rules(ui) {
+            action = ControlPanelAction::RulesChanged;
+        }
+
+        ui.add_space(5.0);
+        let margins = &mut self.settings.equivalence;
+        ui.horizontal(|ui| {
+            ui.add_sized([110.0, 20.0], egui::Label::new("Equiv. margin:"));
+            let before = margins.default;
+            ui.add(
+                egui::DragValue::new(&mut margins.default.value)
+                    .range(0.0..=f64::MAX)
+                    .speed(0.01),
+            )
+            .on_hover_text(
+                "Groups not flagged as mismatches are equivalent when the TOST shows\n\
+                 |M-C| below this margin at α, inconclusive otherwise",
+            );
+            ComboBox::from_id_salt("margin_unit")
+                .width(90.0)
+                .selected_text(margins.default.unit.name())
+                .show_ui(ui, |ui| {
+                    for unit in MarginUnit::ALL {
+                        ui.selectable_value(&mut margins.default.unit, unit, unit.name());
+                    }
+                });
+            if margins.default != before {
+                action = ControlPanelAction::RulesChanged;
+            }
+        });
+        ui.horizontal(|ui| {
+            if ui
+                .button("📥 Import margins")
+                .on_hover_text(
+                    "Per data type margins from a table with a data_type column and\n\
+                     a margin (absolute) or margin_sigma (× control σ) column",
+                )
+                .clicked()
+            {
+                action = ControlPanelAction::ImportMargins;
+            }
+            if !margins.margins.is_empty() {
+                ui.label(
+                    RichText::new(format!("{} data types", margins.margins.len()))
+                        .size(11.0)
+                        .color(Color32::GRAY),
+                );
+                if ui
+                    .small_button("✖")
+                    .on_hover_text("Use the default margin everywhere")
+                    .clicked()
+                {
+                    margins.margins.clear();
+                    action = ControlPanelAction::RulesChanged;
+                }
+            }
+        });
+
+        ui.add_space(15.0);
+        ui.separator();
+        ui.add_space(10.0);
+
         // ===== Column Configuration Section =====
         ui.label(RichText::new("🔧 Column Configuration").size(14.0).strong());
         ui.add_space(8.0);
@@ -153,6 +807,11 @@ impl ControlPanel {
         let label_width = 110.0;
         let combo_width = 150.0;
 
+        // Pickers only offer columns whose role fits
+        let label_columns =
+            self.columns_with_roles(&[ColumnRole::Categorical, ColumnRole::Datetime]);
+        let numeric_columns = self.columns_with_roles(&[ColumnRole::Numeric]);
+
         // Group column - aligned
         ui.horizontal(|ui| {
             ui.add_sized([label_width, 20.0], egui::Label::new("Group Column:"));
@@ -160,7 +819,7 @@ impl ControlPanel {
                 .width(combo_width)
                 .selected_text(&self.settings.group_col)
                 .show_ui(ui, |ui| {
-                    for col in &self.columns {
+                    for col in &label_columns {
                         if ui
                             .selectable_label(self.settings.group_col == *col, col)
                             .clicked()
@@ -192,6 +851,28 @@ impl ControlPanel {
                 });
         });
 
+        ui.add_space(5.0);
+
+        // Pair ID column - switches to paired tests
+        ui.horizontal(|ui| {
+            ui.add_sized([label_width, 20.0], egui::Label::new("Pair ID Column:"));
+            ComboBox::from_id_salt("pair_id_col")
+                .width(combo_width)
+                .selected_text(self.settings.pair_col().unwrap_or("(none)"))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.settings.pair_id_col, String::new(), "(none)");
+                    for col in &self.columns {
+                        ui.selectable_value(&mut self.settings.pair_id_col, col.clone(), col);
+                    }
+                })
+                .response
+                .on_hover_text(
+                    "Column identifying the unit measured under every condition\n\
+                     (wafer, patient). Groups are then matched with the control by ID\n\
+                     and compared with paired tests; not available in streaming mode.",
+                );
+        });
+
         ui.add_space(10.0);
 
         // Mode-specific columns
@@ -203,7 +884,7 @@ impl ControlPanel {
                         .width(combo_width)
                         .selected_text(&self.settings.data_type_col)
                         .show_ui(ui, |ui| {
-                            for col in &self.columns {
+                            for col in &label_columns {
                                 if ui
                                     .selectable_label(self.settings.data_type_col == *col, col)
                                     .clicked()
@@ -222,7 +903,7 @@ impl ControlPanel {
                         .width(combo_width)
                         .selected_text(&self.settings.value_col)
                         .show_ui(ui, |ui| {
-                            for col in &self.columns {
+                            for col in &numeric_columns {
                                 if ui
                                     .selectable_label(self.settings.value_col == *col, col)
                                     .clicked()
@@ -242,7 +923,9 @@ impl ControlPanel {
                     .show(ui, |ui| {
                         ScrollArea::vertical().max_height(120.0).show(ui, |ui| {
                             for (i, col) in self.columns.iter().enumerate() {
-                                if i < self.selected_data_cols.len() {
+                                if self.column_roles.get(i) == Some(&ColumnRole::Numeric)
+                                    && i < self.selected_data_cols.len()
+                                {
                                     ui.checkbox(&mut self.selected_data_cols[i], col);
                                 }
                             }
@@ -252,7 +935,11 @@ impl ControlPanel {
                 ui.add_space(5.0);
                 ui.horizontal(|ui| {
                     if ui.small_button("Select All").clicked() {
-                        self.selected_data_cols.iter_mut().for_each(|v| *v = true);
+                        for (selected, role) in
+                            self.selected_data_cols.iter_mut().zip(&self.column_roles)
+                        {
+                            *selected = *role == ColumnRole::Numeric;
+                        }
                     }
                     if ui.small_button("Clear All").clicked() {
                         self.selected_data_cols.iter_mut().for_each(|v| *v = false);
@@ -265,6 +952,18 @@ impl ControlPanel {
         ui.separator();
         ui.add_space(10.0);
 
+        // ===== Row Filter Section =====
+        ui.label(RichText::new("🔍 Row Filter").size(14.0).strong());
+        ui.add_space(5.0);
+
+        if self.show_row_filter(ui) {
+            action = ControlPanelAction::FilterChanged;
+        }
+
+        ui.add_space(15.0);
+        ui.separator();
+        ui.add_space(10.0);
+
         // ===== Action Buttons =====
         ui.vertical_centered(|ui| {
             ui.add_enabled_ui(self.calculate_enabled, |ui| {
@@ -325,9 +1024,354 @@ impl ControlPanel {
         };
         ui.label(RichText::new(&self.status).size(11.0).color(status_color));
 
+        // ===== Data Quality Section =====
+        if let Some(quality) = &self.load_quality {
+            ui.add_space(15.0);
+            ui.separator();
+            ui.add_space(10.0);
+            self.show_data_quality(ui, quality);
+        }
+
         action
     }
 
+    /// Draw the mismatch rule editor; returns true when the rules changed
+    fn show_mismatch_rules(&mut self, ui: &mut egui::Ui) -> bool {
+        let data_types = &self.data_types;
+        let effect_size = self.settings.effect_size;
+        let variance_test = self.settings.variance_test;
+        let rules = &mut self.settings.mismatch_rules;
+        let mut changed = false;
+
+        ui.label(RichText::new("Mismatch when any rule holds:").size(12.0));
+        let mut removed = None;
+        for (i, rule) in rules.rules.iter_mut().enumerate() {
+            egui::Frame::none()
+                .fill(ui.visuals().widgets.noninteractive.bg_fill)
+                .rounding(5.0)
+                .inner_margin(5.0)
+                .show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(RichText::new(format!("Rule {}", i + 1)).size(11.0).strong());
+                        ui.label(
+                            RichText::new("all checked must hold")
+                                .size(10.0)
+                                .color(Color32::GRAY),
+                        );
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            if ui.small_button("✖").on_hover_text("Remove rule").clicked() {
+                                removed = Some(i);
+                            }
+                        });
+                    });
+                    changed |= ui.checkbox(&mut rule.significant, "p ≤ α").changed();
+                    ui.horizontal(|ui| {
+                        let mut enabled = rule.min_effect_size.is_some();
+                        let label = format!("|{}| >", effect_size.name());
+                        if ui.checkbox(&mut enabled, label).changed() {
+                            rule.min_effect_size = enabled.then_some(0.5);
+                            changed = true;
+                        }
+                        if let Some(min) = &mut rule.min_effect_size {
+                            changed |= ui
+                                .add(egui::DragValue::new(min).range(0.0..=100.0).speed(0.05))
+                                .changed();
+                        }
+                    });
+                    changed |= ui
+                        .checkbox(&mut rule.mean_shift, "|M-C| > tolerance")
+                        .changed();
+                    changed |= ui
+                        .checkbox(&mut rule.omnibus, "omnibus p ≤ α")
+                        .on_hover_text(
+                            "The data type's omnibus test is significant \
+                             (the group's p-value with fewer than three groups)",
+                        )
+                        .changed();
+                    changed |= ui
+                        .checkbox(&mut rule.variance_shift, "variance p ≤ α")
+                        .on_hover_text(format!(
+                            "The {} test finds the group's spread differs from the control's",
+                            variance_test.name()
+                        ))
+                        .changed();
+                });
+            ui.add_space(3.0);
+        }
+        if let Some(i) = removed {
+            rules.rules.remove(i);
+            changed = true;
+        }
+        if rules.rules.is_empty() {
+            ui.label(
+                RichText::new("No rules: nothing is flagged")
+                    .size(11.0)
+                    .color(Color32::from_rgb(230, 160, 60)),
+            );
+        }
+        if ui.small_button("+ Add Rule").clicked() {
+            rules.rules.push(MismatchRule::default());
+            changed = true;
+        }
+
+        // Mean shift tolerances, per data type once a calculation has run
+        if rules.uses_mean_shift() {
+            ui.add_space(5.0);
+            ui.horizontal(|ui| {
+                ui.add_sized([110.0, 20.0], egui::Label::new("Tolerance:"));
+                changed |= ui
+                    .add(
+                        egui::DragValue::new(&mut rules.default_tolerance)
+                            .range(0.0..=f64::MAX)
+                            .speed(0.01),
+                    )
+                    .on_hover_text("Mean shift tolerance for data types without their own")
+                    .changed();
+            });
+            if !data_types.is_empty() {
+                ui.collapsing(RichText::new("Per data type tolerances").size(11.0), |ui| {
+                    ScrollArea::vertical().max_height(150.0).show(ui, |ui| {
+                        egui::Grid::new("tolerance_grid")
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for data_type in data_types {
+                                    let mut tolerance = rules.tolerance_for(data_type);
+                                    let overridden = rules.tolerances.contains_key(data_type);
+                                    ui.label(RichText::new(data_type).size(11.0));
+                                    if ui
+                                        .add(
+                                            egui::DragValue::new(&mut tolerance)
+                                                .range(0.0..=f64::MAX)
+                                                .speed(0.01),
+                                        )
+                                        .changed()
+                                    {
+                                        rules.tolerances.insert(data_type.clone(), tolerance);
+                                        changed = true;
+                                    }
+                                    if overridden
+                                        && ui
+                                            .small_button("↺")
+                                            .on_hover_text("Use the default tolerance")
+                                            .clicked()
+                                    {
+                                        rules.tolerances.remove(data_type);
+                                        changed = true;
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                });
+            }
+        }
+
+        changed
+    }
+
+    /// Draw the filter builder or expression editor; returns true when the filter
+    /// changed and the matching rows should be counted again
+    fn show_row_filter(&mut self, ui: &mut egui::Ui) -> bool {
+        let columns = &self.columns;
+        let filter = &mut self.settings.row_filter;
+        let mut changed = false;
+
+        ui.horizontal(|ui| {
+            changed |= ui
+                .radio_value(&mut filter.mode, FilterMode::Builder, "Builder")
+                .changed();
+            changed |= ui
+                .radio_value(&mut filter.mode, FilterMode::Expression, "Expression")
+                .changed();
+        });
+        ui.add_space(5.0);
+
+        match filter.mode {
+            FilterMode::Builder => {
+                let mut removed = None;
+                for (i, condition) in filter.conditions.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        if i == 0 {
+                            ui.add_sized([48.0, 20.0], egui::Label::new("Where"));
+                        } else {
+                            ComboBox::from_id_salt(("filter_connector", i))
+                                .width(48.0)
+                                .selected_text(condition.connector.name())
+                                .show_ui(ui, |ui| {
+                                    for connector in Connector::ALL {
+                                        changed |= ui
+                                            .selectable_value(
+                                                &mut condition.connector,
+                                                connector,
+                                                connector.name(),
+                                            )
+                                            .changed();
+                                    }
+                                });
+                        }
+
+                        ComboBox::from_id_salt(("filter_column", i))
+                            .width(90.0)
+                            .selected_text(&condition.column)
+                            .show_ui(ui, |ui| {
+                                for column in columns {
+                                    if ui
+                                        .selectable_label(condition.column == *column, column)
+                                        .clicked()
+                                    {
+                                        condition.column = column.clone();
+                                        changed = true;
+                                    }
+                                }
+                            });
+
+                        ComboBox::from_id_salt(("filter_operator", i))
+                            .width(60.0)
+                            .selected_text(condition.operator.symbol())
+                            .show_ui(ui, |ui| {
+                                for operator in FilterOperator::ALL {
+                                    changed |= ui
+                                        .selectable_value(
+                                            &mut condition.operator,
+                                            operator,
+                                            operator.symbol(),
+                                        )
+                                        .changed();
+                                }
+                            });
+
+                        if condition.operator.takes_value() {
+                            // Count once editing is done rather than on every keystroke
+                            changed |= ui
+                                .add(
+                                    egui::TextEdit::singleline(&mut condition.value)
+                                        .desired_width(60.0),
+                                )
+                                .lost_focus();
+                        }
+
+                        if ui.small_button("✖").on_hover_text("Remove").clicked() {
+                            removed = Some(i);
+                        }
+                    });
+                }
+                if let Some(i) = removed {
+                    filter.conditions.remove(i);
+                    changed = true;
+                }
+
+                ui.horizontal(|ui| {
+                    if ui.small_button("+ Add Condition").clicked() {
+                        filter.conditions.push(FilterCondition::default());
+                    }
+                    if !filter.conditions.is_empty() && ui.small_button("Clear").clicked() {
+                        filter.conditions.clear();
+                        changed = true;
+                    }
+                });
+            }
+            FilterMode::Expression => {
+                changed |= ui
+                    .add(
+                        egui::TextEdit::multiline(&mut filter.expression)
+                            .desired_rows(2)
+                            .desired_width(f32::INFINITY)
+                            .font(egui::TextStyle::Monospace)
+                            .hint_text("site == \"FAB2\" and temperature > 25"),
+                    )
+                    .lost_focus();
+                ui.label(
+                    RichText::new(
+                        "and, or, not, == != < <= > >=, + - * /, contains, is null.\n\
+                         Quote column names with spaces in `backticks`.",
+                    )
+                    .size(10.0)
+                    .color(Color32::GRAY),
+                );
+            }
+        }
+
+        let status = match &self.filter_status {
+            FilterStatus::Inactive => None,
+            FilterStatus::Counting => Some(RichText::new("Counting rows...").color(Color32::GRAY)),
+            FilterStatus::Matched { rows, total } => Some(
+                RichText::new(format!("{} of {} rows match", rows, total)).color(if *rows == 0 {
+                    Color32::from_rgb(230, 160, 60)
+                } else {
+                    Color32::from_rgb(40, 167, 69)
+                }),
+            ),
+            FilterStatus::Invalid(error) => {
+                Some(RichText::new(error).color(Color32::from_rgb(220, 53, 69)))
+            }
+        };
+        if let Some(status) = status {
+            ui.add_space(3.0);
+            ui.label(status.size(11.0));
+        }
+
+        changed
+    }
+
+    /// Draw the summary of missing, NaN and unparseable values
+    fn show_data_quality(&self, ui: &mut egui::Ui, quality: &DataQualityReport) {
+        let warn_color = Color32::from_rgb(230, 160, 60);
+
+        ui.label(RichText::new("🩺 Data Quality").size(14.0).strong());
+        ui.add_space(5.0);
+
+        let load_total = quality.total();
+        let heading = if load_total.total() == 0 {
+            RichText::new(format!(
+                "File: {} rows, no missing values",
+                quality.row_count
+            ))
+        } else {
+            RichText::new(format!("File: {}", load_total.describe())).color(warn_color)
+        };
+        ui.collapsing(heading.size(11.0), |ui| {
+            for column in &quality.columns {
+                ui.label(
+                    RichText::new(format!("{}: {}", column.column, column.counts.describe()))
+                        .size(11.0),
+                );
+            }
+        });
+
+        if !self.analysis_exclusions.is_empty() || self.unassigned_rows > 0 {
+            let mut excluded_total = DropCounts::default();
+            for (_, counts) in &self.analysis_exclusions {
+                excluded_total.add(counts);
+            }
+
+            ui.collapsing(
+                RichText::new(format!(
+                    "Analysis: {} values excluded",
+                    excluded_total.total() + self.unassigned_rows
+                ))
+                .size(11.0)
+                .color(warn_color),
+                |ui| {
+                    for (data_type, counts) in &self.analysis_exclusions {
+                        ui.label(
+                            RichText::new(format!("{}: {}", data_type, counts.describe()))
+                                .size(11.0),
+                        );
+                    }
+                    if self.unassigned_rows > 0 {
+                        ui.label(
+                            RichText::new(format!(
+                                "{} values without a group or data type",
+                                self.unassigned_rows
+                            ))
+                            .size(11.0),
+                        );
+                    }
+                },
+            );
+        }
+    }
+
     /// Set progress and status
     pub fn set_progress(&mut self, progress: f32, status: &str) {
         self.progress = progress;
@@ -340,7 +1384,22 @@ impl ControlPanel {
 pub enum ControlPanelAction {
     None,
     BrowseCsv,
+    LoadPattern,
+    LoadSheet,
+    Reload,
+    CsvOptions,
     GroupColumnChanged,
+    FilterChanged,
+    /// A setting that needs no recalculation changed (alpha, mismatch rules, omnibus
+    /// test, effect size, variance test, table columns, CI level, margins or spec
+    /// limits); re-derive the current results from it
+    RulesChanged,
+    /// Alpha changed and the current results depend on it; calculate them again
+    Recalculate,
+    /// Pick a table of per data type equivalence margins
+    ImportMargins,
+    /// Pick a table of per data type spec limits
+    ImportSpecs,
     Calculate,
     ExportPpt,
     OpenPpt,
@@ -0,0 +1,209 @@
+//! CSV Import Options Dialog
+//! Modal window for choosing how CSV files are parsed before loading.
+
+use crate::data::{CsvOptions, TextEncoding};
+use egui::{Color32, ComboBox, RichText, ScrollArea};
+use std::path::PathBuf;
+
+/// Number of raw lines shown in the preview.
+const PREVIEW_LINES: usize = 8;
+
+/// Dialog for editing CSV parsing options of the files about to be loaded.
+#[derive(Default)]
+pub struct CsvOptionsDialog {
+    pub open: bool,
+    pub options: CsvOptions,
+    pub paths: Vec<PathBuf>,
+    preview: Vec<String>,
+    null_text: String,
+    quote_text: String,
+    error: Option<String>,
+}
+
+impl CsvOptionsDialog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open the dialog for the given files, starting from the given options.
+    pub fn open_for(&mut self, paths: Vec<PathBuf>, options: CsvOptions) {
+        self.null_text = options.null_values.join(", ");
+        self.quote_text = options
+            .quote_char
+            .map(|q| (q as char).to_string())
+            .unwrap_or_default();
+        self.options = options;
+        self.paths = paths;
+        self.error = None;
+        self.open = true;
+        self.refresh_preview();
+    }
+
+    /// Re-read the preview lines with the current encoding.
+    fn refresh_preview(&mut self) {
+        self.preview = self
+            .paths
+            .first()
+            .map(|path| CsvOptions::sample_lines(path, self.options.encoding, PREVIEW_LINES))
+            .unwrap_or_default();
+    }
+
+    /// Copy the free-text fields back into the options.
+    fn apply_text_fields(&mut self) {
+        self.options.null_values = self
+            .null_text
+            .split(',')
+            .map(|token| token.trim().to_string())
+            .filter(|token| !token.is_empty())
+            .collect();
+        self.options.quote_char = self.quote_text.trim().bytes().next();
+    }
+
+    /// Draw the dialog
+    pub fn show(&mut self, ctx: &egui::Context) -> CsvDialogAction {
+        let mut action = CsvDialogAction::None;
+        if !self.open {
+            return action;
+        }
+
+        let title = match self.paths.as_slice() {
+            [path] => path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            paths => format!("{} files", paths.len()),
+        };
+
+        egui::Window::new("📄 CSV Import Options")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(RichText::new(&title).size(12.0).color(Color32::GRAY));
+                ui.add_space(8.0);
+
+                let label_width = 110.0;
+                let combo_width = 170.0;
+
+                ui.horizontal(|ui| {
+                    ui.add_sized([label_width, 20.0], egui::Label::new("Delimiter:"));
+                    ComboBox::from_id_salt("csv_delimiter")
+                        .width(combo_width)
+                        .selected_text(self.options.delimiter_name())
+                        .show_ui(ui, |ui| {
+                            for (delimiter, name) in CsvOptions::DELIMITERS {
+                                ui.selectable_value(&mut self.options.delimiter, delimiter, name);
+                            }
+                        });
+                });
+
+                ui.horizontal(|ui| {
+                    ui.add_sized([label_width, 20.0], egui::Label::new("Decimal:"));
+                    ui.radio_value(&mut self.options.decimal_comma, false, "Point (3.14)");
+                    ui.radio_value(&mut self.options.decimal_comma, true, "Comma (3,14)");
+                });
+
+                ui.horizontal(|ui| {
+                    ui.add_sized([label_width, 20.0], egui::Label::new("Quote Char:"));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.quote_text)
+                            .desired_width(30.0)
+                            .char_limit(1),
+                    )
+                    .on_hover_text("Leave empty to disable quoting");
+                });
+
+                ui.horizontal(|ui| {
+                    ui.add_sized([label_width, 20.0], egui::Label::new("Null Values:"));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.null_text)
+                            .desired_width(combo_width)
+                            .hint_text("NA, #N/A, -"),
+                    )
+                    .on_hover_text("Comma-separated cell values read as missing");
+                });
+
+                ui.horizontal(|ui| {
+                    ui.add_sized([label_width, 20.0], egui::Label::new("Encoding:"));
+                    let before = self.options.encoding;
+                    ComboBox::from_id_salt("csv_encoding")
+                        .width(combo_width)
+                        .selected_text(self.options.encoding.name())
+                        .show_ui(ui, |ui| {
+                            for encoding in TextEncoding::ALL {
+                                ui.selectable_value(
+                                    &mut self.options.encoding,
+                                    encoding,
+                                    encoding.name(),
+                                );
+                            }
+                        });
+                    if self.options.encoding != before {
+                        self.refresh_preview();
+                    }
+                });
+
+                // Raw preview of the first lines
+                ui.add_space(8.0);
+                ui.label(RichText::new("Preview").strong());
+                egui::Frame::none()
+                    .fill(ui.visuals().extreme_bg_color)
+                    .rounding(5.0)
+                    .inner_margin(5.0)
+                    .show(ui, |ui| {
+                        ScrollArea::both()
+                            .max_height(140.0)
+                            .max_width(420.0)
+                            .show(ui, |ui| {
+                                for line in &self.preview {
+                                    ui.label(RichText::new(line).monospace().size(11.0));
+                                }
+                            });
+                    });
+
+                if let Some(error) = &self.error {
+                    ui.add_space(5.0);
+                    ui.label(RichText::new(error).color(Color32::from_rgb(220, 80, 80)));
+                }
+
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    if ui.button("🔍 Auto-detect").clicked() {
+                        if let Some(path) = self.paths.first().cloned() {
+                            match CsvOptions::detect(&path) {
+                                Ok(detected) => self.open_for(self.paths.clone(), detected),
+                                Err(e) => self.error = Some(format!("Detection failed: {}", e)),
+                            }
+                        }
+                    }
+
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button("Load").clicked() {
+                            self.apply_text_fields();
+                            match self.options.validate() {
+                                Ok(()) => {
+                                    self.open = false;
+                                    action = CsvDialogAction::Load;
+                                }
+                                Err(e) => self.error = Some(e),
+                            }
+                        }
+                        if ui.button("Cancel").clicked() {
+                            self.open = false;
+                            action = CsvDialogAction::Cancel;
+                        }
+                    });
+                });
+            });
+
+        action
+    }
+}
+
+/// Actions from the CSV options dialog
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CsvDialogAction {
+    None,
+    Load,
+    Cancel,
+}
@@ -0,0 +1,351 @@
+//! Data Preview Widget
+//! Virtualized, scrollable table of the loaded DataFrame with column dtypes,
+//! per-column null counts and min/max, and click-to-sort headers.
+
+use egui::{Align2, Color32, FontId, Rect, RichText, ScrollArea, Sense, Stroke};
+use polars::prelude::*;
+
+/// Table layout
+const COLUMN_WIDTH: f32 = 140.0;
+const INDEX_WIDTH: f32 = 70.0;
+const ROW_HEIGHT: f32 = 20.0;
+const HEADER_HEIGHT: f32 = 76.0;
+const CELL_PADDING: f32 = 6.0;
+
+/// Summary shown in a column header.
+struct ColumnSummary {
+    name: String,
+    dtype: String,
+    nulls: usize,
+    min: String,
+    max: String,
+}
+
+impl ColumnSummary {
+    fn new(column: &Column) -> Self {
+        let series = column.as_materialized_series();
+        let extreme = |value: PolarsResult<Scalar>| {
+            value
+                .ok()
+                .filter(|scalar| !scalar.is_null())
+                .map(|scalar| format_value(scalar.value().clone()))
+                .unwrap_or_else(|| "-".to_string())
+        };
+
+        Self {
+            name: column.name().to_string(),
+            dtype: column.dtype().to_string(),
+            nulls: column.null_count(),
+            min: extreme(series.min_reduce()),
+            max: extreme(series.max_reduce()),
+        }
+    }
+}
+
+/// Sort state of the preview.
+#[derive(Clone, Copy, PartialEq)]
+struct SortKey {
+    column: usize,
+    descending: bool,
+}
+
+/// Scrollable preview of the loaded data.
+#[derive(Default)]
+pub struct DataPreview {
+    /// Data as loaded
+    df: Option<DataFrame>,
+    /// Data as displayed (sorted copy when a sort is active)
+    view: Option<DataFrame>,
+    summaries: Vec<ColumnSummary>,
+    sort: Option<SortKey>,
+    error: Option<String>,
+    /// Rows in the full dataset when only its first rows are previewed
+    total_rows: Option<usize>,
+}
+
+impl DataPreview {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Show a newly loaded DataFrame
+    pub fn set_dataframe(&mut self, df: &DataFrame) {
+        self.summaries = df.get_columns().iter().map(ColumnSummary::new).collect();
+        self.df = Some(df.clone());
+        self.view = Some(df.clone());
+        self.sort = None;
+        self.error = None;
+        self.total_rows = None;
+    }
+
+    /// Mark the shown DataFrame as the first rows of a larger dataset
+    pub fn set_total_rows(&mut self, total_rows: usize) {
+        self.total_rows = Some(total_rows);
+    }
+
+    /// Remove the previewed data
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Cycle a column through ascending, descending and unsorted
+    fn toggle_sort(&mut self, column: usize) {
+        self.sort = match self.sort {
+            Some(SortKey {
+                column: c,
+                descending: false,
+            }) if c == column => Some(SortKey {
+                column,
+                descending: true,
+            }),
+            Some(SortKey {
+                column: c,
+                descending: true,
+            }) if c == column => None,
+            _ => Some(SortKey {
+                column,
+                descending: false,
+            }),
+        };
+
+        let Some(df) = &self.df else {
+            return;
+        };
+        self.error = None;
+        self.view = match self.sort {
+            None => Some(df.clone()),
+            Some(key) => {
+                let name = self.summaries[key.column].name.as_str();
+                let options = SortMultipleOptions::default()
+                    .with_order_descending(key.descending)
+                    .with_nulls_last(true)
+                    .with_maintain_order(true);
+                match df.sort([name], options) {
+                    Ok(sorted) => Some(sorted),
+                    Err(e) => {
+                        self.error = Some(format!("Sort failed: {}", e));
+                        self.sort = None;
+                        Some(df.clone())
+                    }
+                }
+            }
+        };
+    }
+
+    /// Draw the preview table
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        let Some(view) = &self.view else {
+            ui.centered_and_justified(|ui| {
+                ui.label(RichText::new("No Data").size(20.0));
+            });
+            return;
+        };
+
+        let n_rows = view.height();
+        let n_cols = view.width();
+
+        ui.horizontal(|ui| {
+            let size = match self.total_rows {
+                Some(total) if total > n_rows => {
+                    format!("First {} of {} rows × {} columns", n_rows, total, n_cols)
+                }
+                _ => format!("{} rows × {} columns", n_rows, n_cols),
+            };
+            ui.label(RichText::new(size).strong());
+            ui.label(
+                RichText::new("Click a column header to sort")
+                    .size(11.0)
+                    .color(Color32::GRAY),
+            );
+            if let Some(error) = &self.error {
+                ui.label(
+                    RichText::new(error)
+                        .size(11.0)
+                        .color(Color32::from_rgb(220, 53, 69)),
+                );
+            }
+        });
+        ui.add_space(5.0);
+
+        let mut clicked_column = None;
+
+        ScrollArea::both()
+            .auto_shrink([false, false])
+            .show_viewport(ui, |ui, viewport| {
+                let total_size = egui::vec2(
+                    INDEX_WIDTH + n_cols as f32 * COLUMN_WIDTH,
+                    HEADER_HEIGHT + n_rows as f32 * ROW_HEIGHT,
+                );
+                let (content_rect, _) = ui.allocate_exact_size(total_size, Sense::hover());
+                let origin = content_rect.min;
+
+                // Only the rows and columns inside the viewport are drawn
+                let first_col = ((viewport.min.x - INDEX_WIDTH).max(0.0) / COLUMN_WIDTH) as usize;
+                let last_col = (((viewport.max.x - INDEX_WIDTH).max(0.0) / COLUMN_WIDTH).ceil()
+                    as usize)
+                    .min(n_cols);
+                let first_row = (viewport.min.y.max(0.0) / ROW_HEIGHT) as usize;
+                let last_row =
+                    (((viewport.max.y - HEADER_HEIGHT).max(0.0) / ROW_HEIGHT).ceil() as usize + 1)
+                        .min(n_rows);
+
+                let visuals = ui.visuals().clone();
+                let painter = ui.painter();
+                let cell_font = FontId::monospace(12.0);
+                let grid_stroke = Stroke::new(0.5, visuals.widgets.noninteractive.bg_stroke.color);
+
+                let col_x = |col: usize| origin.x + INDEX_WIDTH + col as f32 * COLUMN_WIDTH;
+
+                // Body rows
+                for row in first_row..last_row {
+                    let y = origin.y + HEADER_HEIGHT + row as f32 * ROW_HEIGHT;
+                    let row_rect = Rect::from_min_size(
+                        egui::pos2(origin.x, y),
+                        egui::vec2(total_size.x, ROW_HEIGHT),
+                    );
+                    if row % 2 == 1 {
+                        painter.rect_filled(row_rect, 0.0, visuals.faint_bg_color);
+                    }
+
+                    painter.text(
+                        egui::pos2(origin.x + INDEX_WIDTH - CELL_PADDING, row_rect.center().y),
+                        Align2::RIGHT_CENTER,
+                        row.to_string(),
+                        cell_font.clone(),
+                        Color32::GRAY,
+                    );
+
+                    for col in first_col..last_col {
+                        let cell_rect = Rect::from_min_size(
+                            egui::pos2(col_x(col), y),
+                            egui::vec2(COLUMN_WIDTH, ROW_HEIGHT),
+                        );
+                        let value = view.get_columns()[col]
+                            .get(row)
+                            .map(format_value)
+                            .unwrap_or_default();
+                        let color = if value.is_empty() {
+                            Color32::GRAY
+                        } else {
+                            visuals.text_color()
+                        };
+                        painter.with_clip_rect(cell_rect.shrink(1.0)).text(
+                            egui::pos2(cell_rect.min.x + CELL_PADDING, cell_rect.center().y),
+                            Align2::LEFT_CENTER,
+                            if value.is_empty() { "null" } else { &value },
+                            cell_font.clone(),
+                            color,
+                        );
+                    }
+                }
+
+                // Sticky header drawn over the body at the top of the viewport
+                let header_y = origin.y + viewport.min.y;
+                let header_rect = Rect::from_min_size(
+                    egui::pos2(origin.x, header_y),
+                    egui::vec2(total_size.x, HEADER_HEIGHT),
+                );
+                painter.rect_filled(header_rect, 0.0, visuals.widgets.noninteractive.bg_fill);
+                painter.hline(header_rect.x_range(), header_rect.max.y, grid_stroke);
+
+                for col in first_col..last_col {
+                    let summary = &self.summaries[col];
+                    let cell_rect = Rect::from_min_size(
+                        egui::pos2(col_x(col), header_y),
+                        egui::vec2(COLUMN_WIDTH, HEADER_HEIGHT),
+                    );
+                    let response = ui
+                        .interact(
+                            cell_rect,
+                            ui.id().with(("preview_header", col)),
+                            Sense::click(),
+                        )
+                        .on_hover_text(format!(
+                            "{}\n{}\nnulls: {}\nmin: {}\nmax: {}",
+                            summary.name, summary.dtype, summary.nulls, summary.min, summary.max
+                        ));
+                    if response.hovered() {
+                        painter.rect_filled(cell_rect, 0.0, visuals.widgets.hovered.weak_bg_fill);
+                    }
+                    if response.clicked() {
+                        clicked_column = Some(col);
+                    }
+                    painter.vline(cell_rect.min.x, cell_rect.y_range(), grid_stroke);
+
+                    let arrow = match self.sort {
+                        Some(SortKey { column, descending }) if column == col => {
+                            if descending {
+                                " ⬇"
+                            } else {
+                                " ⬆"
+                            }
+                        }
+                        _ => "",
+                    };
+
+                    let clipped = painter.with_clip_rect(cell_rect.shrink(1.0));
+                    let x = cell_rect.min.x + CELL_PADDING;
+                    let lines = [
+                        (
+                            format!("{}{}", summary.name, arrow),
+                            FontId::proportional(13.0),
+                            visuals.strong_text_color(),
+                        ),
+                        (
+                            summary.dtype.clone(),
+                            FontId::monospace(11.0),
+                            Color32::from_rgb(100, 149, 237),
+                        ),
+                        (
+                            format!("nulls: {}", summary.nulls),
+                            FontId::proportional(11.0),
+                            if summary.nulls > 0 {
+                                Color32::from_rgb(230, 160, 60)
+                            } else {
+                                Color32::GRAY
+                            },
+                        ),
+                        (
+                            format!("{} … {}", summary.min, summary.max),
+                            FontId::proportional(11.0),
+                            Color32::GRAY,
+                        ),
+                    ];
+                    for (i, (text, font, color)) in lines.into_iter().enumerate() {
+                        clipped.text(
+                            egui::pos2(x, cell_rect.min.y + 4.0 + i as f32 * 17.0),
+                            Align2::LEFT_TOP,
+                            text,
+                            font,
+                            color,
+                        );
+                    }
+                }
+            });
+
+        if let Some(col) = clicked_column {
+            self.toggle_sort(col);
+        }
+    }
+}
+
+/// Render a cell value as plain text (nulls as an empty string).
+fn format_value(value: AnyValue) -> String {
+    match value {
+        AnyValue::Null => String::new(),
+        AnyValue::String(s) => s.to_string(),
+        AnyValue::StringOwned(s) => s.to_string(),
+        AnyValue::Float64(v) => format_float(v),
+        AnyValue::Float32(v) => format_float(v as f64),
+        other => other.to_string(),
+    }
+}
+
+/// Format floats compactly without trailing zeros.
+fn format_float(value: f64) -> String {
+    if value.is_finite() && value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{:.1}", value)
+    } else {
+        format!("{}", value)
+    }
+}
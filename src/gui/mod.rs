@@ -3,7 +3,13 @@
 mod app;
 mod chart_viewer;
 mod control_panel;
+mod csv_dialog;
+mod data_preview;
+mod schema_editor;
 
 pub use app::ChartifyApp;
 pub use chart_viewer::ChartViewer;
-pub use control_panel::{ControlPanel, ControlPanelAction};
+pub use control_panel::{ControlPanel, ControlPanelAction, FilterStatus};
+pub use csv_dialog::{CsvDialogAction, CsvOptionsDialog};
+pub use data_preview::DataPreview;
+pub use schema_editor::{SchemaEditor, SchemaEditorAction};
@@ -0,0 +1,142 @@
+//! Schema Editor Widget
+//! Lets the user inspect the loaded column types and override each column's role
+//! before analysis.
+
+use crate::data::{ColumnRole, ColumnSchema};
+use egui::{Color32, ComboBox, RichText, ScrollArea};
+
+/// Editable copy of the column schema.
+#[derive(Default)]
+pub struct SchemaEditor {
+    schema: Vec<ColumnSchema>,
+    /// Roles as last applied, to detect pending edits
+    applied: Vec<ColumnRole>,
+}
+
+impl SchemaEditor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Show the schema of newly loaded or re-cast data
+    pub fn set_schema(&mut self, schema: &[ColumnSchema]) {
+        self.schema = schema.to_vec();
+        self.applied = schema.iter().map(|column| column.role).collect();
+    }
+
+    /// The edited schema
+    pub fn schema(&self) -> Vec<ColumnSchema> {
+        self.schema.clone()
+    }
+
+    /// Whether any role differs from the applied schema
+    fn has_changes(&self) -> bool {
+        self.schema
+            .iter()
+            .zip(&self.applied)
+            .any(|(column, applied)| column.role != *applied)
+    }
+
+    /// Draw the schema editor
+    pub fn show(&mut self, ui: &mut egui::Ui) -> SchemaEditorAction {
+        let mut action = SchemaEditorAction::None;
+
+        if self.schema.is_empty() {
+            ui.centered_and_justified(|ui| {
+                ui.label(RichText::new("No Data").size(20.0));
+            });
+            return action;
+        }
+
+        ui.horizontal(|ui| {
+            ui.label(RichText::new(format!("{} columns", self.schema.len())).strong());
+            ui.label(
+                RichText::new(
+                    "Numeric columns are measurements; categorical columns can be groups",
+                )
+                .size(11.0)
+                .color(Color32::GRAY),
+            );
+        });
+        ui.add_space(5.0);
+
+        ui.horizontal(|ui| {
+            let has_changes = self.has_changes();
+            if ui
+                .add_enabled(has_changes, egui::Button::new("✔ Apply Types"))
+                .clicked()
+            {
+                action = SchemaEditorAction::Apply;
+            }
+            if ui.button("↺ Reset to Inferred").clicked() {
+                for column in &mut self.schema {
+                    column.role = ColumnRole::infer(&column.source_dtype);
+                }
+            }
+            if has_changes {
+                ui.label(
+                    RichText::new("Unapplied changes")
+                        .size(11.0)
+                        .color(Color32::from_rgb(230, 160, 60)),
+                );
+            }
+        });
+        ui.add_space(8.0);
+
+        ScrollArea::vertical()
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                egui::Grid::new("schema_editor_grid")
+                    .striped(true)
+                    .min_col_width(120.0)
+                    .spacing([16.0, 4.0])
+                    .show(ui, |ui| {
+                        ui.label(RichText::new("Column").strong());
+                        ui.label(RichText::new("File Type").strong());
+                        ui.label(RichText::new("Role").strong());
+                        ui.end_row();
+
+                        for (i, column) in self.schema.iter_mut().enumerate() {
+                            let changed = self
+                                .applied
+                                .get(i)
+                                .is_some_and(|applied| *applied != column.role);
+                            let name = RichText::new(&column.name);
+                            ui.label(if column.role == ColumnRole::Ignore {
+                                name.strikethrough().color(Color32::GRAY)
+                            } else {
+                                name
+                            });
+                            ui.label(
+                                RichText::new(column.source_dtype.to_string())
+                                    .monospace()
+                                    .color(Color32::from_rgb(100, 149, 237)),
+                            );
+                            ComboBox::from_id_salt(("column_role", i))
+                                .width(120.0)
+                                .selected_text(if changed {
+                                    RichText::new(column.role.name())
+                                        .color(Color32::from_rgb(230, 160, 60))
+                                } else {
+                                    RichText::new(column.role.name())
+                                })
+                                .show_ui(ui, |ui| {
+                                    for role in ColumnRole::ALL {
+                                        ui.selectable_value(&mut column.role, role, role.name());
+                                    }
+                                });
+                            ui.end_row();
+                        }
+                    });
+            });
+
+        action
+    }
+}
+
+/// Actions from the schema editor
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SchemaEditorAction {
+    None,
+    Apply,
+}
@@ -0,0 +1,9 @@
+//! Chartify Pro - CSV Data Analysis & Interactive Chart Viewer
+//!
+//! Library of the application, shared by the GUI binary and the benchmarks.
+
+pub mod charts;
+pub mod data;
+pub mod gui;
+pub mod ppt;
+pub mod stats;
@@ -5,14 +5,8 @@
 // Hide console window on Windows in release builds
 #![windows_subsystem = "windows"]
 
-mod charts;
-mod data;
-mod gui;
-mod ppt;
-mod stats;
-
+use chartify_pro::gui::ChartifyApp;
 use eframe::egui;
-use gui::ChartifyApp;
 
 fn main() -> eframe::Result<()> {
     // Configure native options
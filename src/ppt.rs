@@ -4,9 +4,9 @@
 //! Uses direct ZIP/XML generation to properly embed images since the ppt-rs
 //! high-level API doesn't fully support image embedding yet.
 
-use std::fs::{self, File};
+use std::fs::File;
 use std::io::Write;
-use std::path::{Path, PathBuf};
+use std::path::Path;
 use zip::write::FileOptions;
 use zip::ZipWriter;
 
@@ -127,127 +127,6 @@ impl PptGenerator {
         Ok(())
     }
 
-    /// Generate PPT with 4 images per slide (2x2 grid layout) - file-based version
-    #[allow(dead_code)]
-    pub fn generate_ppt(
-        image_paths: &[PathBuf],
-        output_path: &Path,
-        title: &str,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        let file = File::create(output_path)?;
-        let mut zip = ZipWriter::new(file);
-        let options = FileOptions::default();
-
-        // Calculate layout (2x2 grid with margins)
-        let margin = EMU_PER_INCH / 2; // 0.5 inch
-        let gap = EMU_PER_INCH / 4; // 0.25 inch
-        let content_width = SLIDE_WIDTH - 2 * margin;
-        let content_height = SLIDE_HEIGHT - 2 * margin;
-        let img_width = (content_width - gap) / 2;
-        let img_height = (content_height - gap) / 2;
-
-        // 2x2 grid positions
-        let positions: [(i64, i64); 4] = [
-            (margin, margin),                                      // Top-left
-            (margin + img_width + gap, margin),                    // Top-right
-            (margin, margin + img_height + gap),                   // Bottom-left
-            (margin + img_width + gap, margin + img_height + gap), // Bottom-right
-        ];
-
-        // Group images into slides (4 per slide)
-        let slides: Vec<_> = image_paths.chunks(4).collect();
-        let slide_count = slides.len();
-
-        // 1. [Content_Types].xml
-        zip.start_file("[Content_Types].xml", options)?;
-        zip.write_all(Self::content_types_xml(slide_count, image_paths.len()).as_bytes())?;
-
-        // 2. _rels/.rels
-        zip.start_file("_rels/.rels", options)?;
-        zip.write_all(Self::rels_xml().as_bytes())?;
-
-        // 3. ppt/_rels/presentation.xml.rels
-        zip.start_file("ppt/_rels/presentation.xml.rels", options)?;
-        zip.write_all(Self::presentation_rels_xml(slide_count).as_bytes())?;
-
-        // 4. ppt/presentation.xml
-        zip.start_file("ppt/presentation.xml", options)?;
-        zip.write_all(Self::presentation_xml(title, slide_count).as_bytes())?;
-
-        // 5. Slides and their relationships
-        let mut global_img_idx = 0;
-        for (slide_idx, chunk) in slides.iter().enumerate() {
-            let slide_num = slide_idx + 1;
-
-            // Calculate image indices for this slide
-            let img_start = global_img_idx;
-            let img_end = global_img_idx + chunk.len();
-            global_img_idx = img_end;
-
-            // Slide relationships
-            zip.start_file(
-                format!("ppt/slides/_rels/slide{}.xml.rels", slide_num),
-                options,
-            )?;
-            let image_ids: Vec<usize> = (img_start..img_end).map(|i| i + 1).collect();
-            zip.write_all(Self::slide_rels_xml(&image_ids).as_bytes())?;
-
-            // Slide content
-            zip.start_file(format!("ppt/slides/slide{}.xml", slide_num), options)?;
-            let slide_positions: Vec<(i64, i64, i64, i64)> = (0..chunk.len())
-                .map(|i| (positions[i].0, positions[i].1, img_width, img_height))
-                .collect();
-            zip.write_all(Self::slide_xml(slide_num, &image_ids, &slide_positions).as_bytes())?;
-        }
-
-        // 6. Slide layouts
-        zip.start_file("ppt/slideLayouts/slideLayout1.xml", options)?;
-        zip.write_all(Self::slide_layout_xml().as_bytes())?;
-
-        zip.start_file("ppt/slideLayouts/_rels/slideLayout1.xml.rels", options)?;
-        zip.write_all(Self::layout_rels_xml().as_bytes())?;
-
-        // 7. Slide master
-        zip.start_file("ppt/slideMasters/slideMaster1.xml", options)?;
-        zip.write_all(Self::slide_master_xml().as_bytes())?;
-
-        zip.start_file("ppt/slideMasters/_rels/slideMaster1.xml.rels", options)?;
-        zip.write_all(Self::master_rels_xml().as_bytes())?;
-
-        // 8. Theme
-        zip.start_file("ppt/theme/theme1.xml", options)?;
-        zip.write_all(Self::theme_xml().as_bytes())?;
-
-        // 9. docProps
-        zip.start_file("docProps/core.xml", options)?;
-        zip.write_all(Self::core_props_xml(title).as_bytes())?;
-
-        zip.start_file("docProps/app.xml", options)?;
-        zip.write_all(Self::app_props_xml(slide_count).as_bytes())?;
-
-        // 10. EMBED IMAGES (this is the key part!)
-        for (idx, img_path) in image_paths.iter().enumerate() {
-            let img_data = fs::read(img_path)?;
-            let ext = img_path
-                .extension()
-                .unwrap_or_default()
-                .to_string_lossy()
-                .to_lowercase();
-            zip.start_file(format!("ppt/media/image{}.{}", idx + 1, ext), options)?;
-            zip.write_all(&img_data)?;
-        }
-
-        zip.finish()?;
-
-        println!(
-            "PPT generated: {} ({} slides, {} images)",
-            output_path.display(),
-            slide_count,
-            image_paths.len()
-        );
-        Ok(())
-    }
-
     fn content_types_xml(slide_count: usize, _image_count: usize) -> String {
         let mut xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
 <Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
@@ -468,41 +347,4 @@ impl PptGenerator {
             slide_count
         )
     }
-
-    /// Export charts to PNG format for PPT embedding
-    pub fn export_charts_as_png(
-        chart_data: &std::collections::HashMap<String, crate::charts::ChartData>,
-        data_type_order: &[String],
-        base_path: &Path,
-    ) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
-        use crate::charts::ChartRenderer;
-
-        let tem_path = base_path.join("tem_png");
-        fs::create_dir_all(&tem_path)?;
-
-        let mut png_paths = Vec::new();
-        let width = 800u32;
-        let height = 600u32;
-
-        for data_type in data_type_order {
-            if let Some(data) = chart_data.get(data_type) {
-                let safe_name: String = data_type
-                    .chars()
-                    .map(|c| {
-                        if c.is_alphanumeric() || c == '_' || c == '-' {
-                            c
-                        } else {
-                            '_'
-                        }
-                    })
-                    .collect();
-
-                let file_path = tem_path.join(format!("{}.png", safe_name));
-                ChartRenderer::render_chart_card_png(data, &file_path, width, height)?;
-                png_paths.push(file_path);
-            }
-        }
-
-        Ok(png_paths)
-    }
 }
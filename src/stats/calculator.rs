@@ -1,28 +1,82 @@
 //! Statistics Calculator Module
-//! Handles statistical computations including descriptive stats and t-tests.
+//! Handles statistical computations including descriptive stats and group comparisons.
 
+use super::capability::{Capability, SpecLimits, SpecTable};
+use super::correction::CorrectionMethod;
+use super::dunnett::{CriticalValue, Dunnett};
+use super::effect_size::{EffectSize, EffectSizeMethod};
+use super::equivalence::{self, EquivalenceMargin, EquivalenceMargins, Outcome};
+use super::hypothesis::{self, Moments, TestMethod, TwoSampleTest, DEFAULT_CI_LEVEL};
+use super::normality::Normality;
+use super::omnibus::{OmnibusMethod, OmnibusResult};
+use super::outliers::OutlierFilter;
+use super::paired::PairedStats;
+use super::resampling::{BootstrapCi, Resampler};
+use super::robust::{self, RobustStats, TRIM_FRACTION};
+use super::rules::MismatchRules;
+use super::variance::{VarianceResult, VarianceTest};
+use crate::data::{DropCounts, PairedGroups, PairedValues};
 use polars::prelude::*;
 use rayon::prelude::*;
-use statrs::distribution::{ContinuousCDF, StudentsT};
 use std::collections::HashMap;
 
-/// Significance threshold for t-test
-pub const SIGNIFICANCE_THRESHOLD: f64 = 0.05;
+/// Values of each group, keyed by data type and then group.
+pub type GroupedValues = HashMap<String, HashMap<String, Vec<f64>>>;
 
 /// Statistics for a single group.
 #[derive(Debug, Clone)]
 pub struct GroupStats {
     pub group_name: String,
     pub count: usize,
+    /// Values dropped as null, NaN or unparseable before the statistics were computed.
+    pub excluded: usize,
+    /// Outliers found by the outlier rule; not part of `count` when excluded.
+    pub outliers: usize,
     pub mean: f64,
     pub median: f64,
     pub std: f64,
     pub variance: f64,
     pub p95: f64,
     pub p05: f64,
-    pub std_diff_from_control: Option<f64>,
+    /// Trimmed and winsorized means, MAD, IQR sigma, skewness and kurtosis, once
+    /// a column shows one of them.
+    pub robust: Option<RobustStats>,
+    /// Hodges–Lehmann shift from the control, once its column is shown.
+    pub hodges_lehmann: Option<f64>,
+    /// Shapiro–Wilk and Anderson–Darling tests of the group's values.
+    pub normality: Normality,
+    /// Effect sizes against the control, one per method the data allows.
+    pub effect_sizes: Vec<EffectSize>,
+    /// The selected effect size (`DataTypeStats::effect_size_method`).
+    pub effect_size: Option<f64>,
+    /// Confidence interval of `effect_size`.
+    pub effect_size_ci: Option<(f64, f64)>,
+    /// Confidence interval of the mean minus the control mean.
+    pub mean_diff_ci: Option<(f64, f64)>,
+    /// Standard deviation over the control's.
+    pub sigma_ratio: Option<f64>,
+    /// Variance tests against the control, one per test the data allows.
+    pub variance_tests: Vec<VarianceResult>,
+    /// P-value of the selected variance test (`DataTypeStats::variance_test`).
+    pub variance_p: Option<f64>,
+    /// BCa bootstrap intervals of the mean and median differences, when resampled.
+    pub bootstrap: Option<BootstrapCi>,
+    /// Comparison with the control on values matched by pair ID, in paired mode.
+    pub paired: Option<PairedStats>,
+    /// Capability against the spec limits of the data type, when it has any.
+    pub capability: Option<Capability>,
+    /// Raw p-value of the comparison with the control group.
     pub p_value: Option<f64>,
+    /// Test that produced `p_value`.
+    pub test: Option<TestMethod>,
+    /// P-value after multiple-testing correction across the run, when one was applied.
+    pub p_adjusted: Option<f64>,
+    /// Whether the group is a mismatch against the control under the `MismatchRules`.
     pub is_significant: bool,
+    /// TOST p-value of the mean lying within the equivalence margin of the control's.
+    pub tost_p: Option<f64>,
+    /// Different, equivalent or neither; replaces `is_significant` for display.
+    pub outcome: Outcome,
 }
 
 impl Default for GroupStats {
@@ -30,15 +84,33 @@ impl Default for GroupStats {
         Self {
             group_name: String::new(),
             count: 0,
+            excluded: 0,
+            outliers: 0,
             mean: f64::NAN,
             median: f64::NAN,
             std: f64::NAN,
             variance: f64::NAN,
             p95: f64::NAN,
             p05: f64::NAN,
-            std_diff_from_control: None,
+            robust: None,
+            hodges_lehmann: None,
+            normality: Normality::default(),
+            effect_sizes: Vec::new(),
+            effect_size: None,
+            effect_size_ci: None,
+            mean_diff_ci: None,
+            sigma_ratio: None,
+            variance_tests: Vec::new(),
+            variance_p: None,
+            bootstrap: None,
+            paired: None,
+            capability: None,
             p_value: None,
+            test: None,
+            p_adjusted: None,
             is_significant: false,
+            tost_p: None,
+            outcome: Outcome::default(),
         }
     }
 }
@@ -49,6 +121,168 @@ pub struct DataTypeStats {
     pub data_type: String,
     pub control_group: String,
     pub group_stats: HashMap<String, GroupStats>,
+    /// Omnibus tests across all groups, when there are enough groups.
+    pub omnibus: Vec<OmnibusResult>,
+    /// Omnibus test shown on the card and used by the mismatch rules.
+    pub omnibus_method: OmnibusMethod,
+    /// Dunnett's test, when it produced the p-values.
+    pub dunnett: Option<Dunnett>,
+    /// Confidence level of `GroupStats::mean_diff_ci` and `effect_size_ci`.
+    pub ci_level: f64,
+    /// Effect size shown in the stats tables and used by the mismatch rules.
+    pub effect_size_method: EffectSizeMethod,
+    /// Whether the p-values fell back to a rank test because normality was rejected.
+    pub normality_fallback: bool,
+    /// Variance test shown in the stats tables and used by the mismatch rules.
+    pub variance_test: VarianceTest,
+    /// Equivalence margin of the TOST p-values, with its value in the data's units.
+    pub equivalence_margin: Option<(EquivalenceMargin, f64)>,
+    /// Spec limits of the capability indices.
+    pub spec_limits: Option<SpecLimits>,
+    /// Whether Cp/Cpk were left out of groups known only from a sample.
+    pub capability_sampled: bool,
+    /// Descriptive columns shown in the stats tables.
+    pub columns: Vec<StatColumn>,
+    /// Outlier rule behind `GroupStats::outliers`, with the α its tests ran at,
+    /// when one was applied.
+    pub outlier_filter: Option<(OutlierFilter, f64)>,
+}
+
+/// Descriptive statistic that can be shown as a stats table column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatColumn {
+    Mean,
+    Median,
+    Std,
+    P05,
+    P95,
+    TrimmedMean,
+    WinsorizedMean,
+    Mad,
+    IqrSigma,
+    Skewness,
+    Kurtosis,
+    HodgesLehmann,
+}
+
+impl StatColumn {
+    pub const ALL: [StatColumn; 12] = [
+        StatColumn::Mean,
+        StatColumn::Median,
+        StatColumn::Std,
+        StatColumn::P05,
+        StatColumn::P95,
+        StatColumn::TrimmedMean,
+        StatColumn::WinsorizedMean,
+        StatColumn::Mad,
+        StatColumn::IqrSigma,
+        StatColumn::Skewness,
+        StatColumn::Kurtosis,
+        StatColumn::HodgesLehmann,
+    ];
+
+    /// Columns shown until the user picks others.
+    pub const DEFAULT: [StatColumn; 5] = [
+        StatColumn::Mean,
+        StatColumn::Median,
+        StatColumn::Std,
+        StatColumn::P05,
+        StatColumn::P95,
+    ];
+
+    /// Whether the column shows one of `GroupStats::robust`.
+    pub fn is_robust(&self) -> bool {
+        matches!(
+            self,
+            StatColumn::TrimmedMean
+                | StatColumn::WinsorizedMean
+                | StatColumn::Mad
+                | StatColumn::IqrSigma
+                | StatColumn::Skewness
+                | StatColumn::Kurtosis
+        )
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            StatColumn::Mean => "Mean",
+            StatColumn::Median => "Median",
+            StatColumn::Std => "Std",
+            StatColumn::P05 => "P05",
+            StatColumn::P95 => "P95",
+            StatColumn::TrimmedMean => "Trim. mean",
+            StatColumn::WinsorizedMean => "Wins. mean",
+            StatColumn::Mad => "MAD",
+            StatColumn::IqrSigma => "IQR σ",
+            StatColumn::Skewness => "Skew",
+            StatColumn::Kurtosis => "Kurt.",
+            StatColumn::HodgesLehmann => "HL shift",
+        }
+    }
+
+    /// What the column holds, for tooltips.
+    pub fn description(&self) -> String {
+        let percent = TRIM_FRACTION * 100.0;
+        match self {
+            StatColumn::Mean => "Arithmetic mean".to_string(),
+            StatColumn::Median => "Median".to_string(),
+            StatColumn::Std => "Sample standard deviation".to_string(),
+            StatColumn::P05 => "5th percentile".to_string(),
+            StatColumn::P95 => "95th percentile".to_string(),
+            StatColumn::TrimmedMean => {
+                format!("Mean without the lowest and highest {percent}% of the values")
+            }
+            StatColumn::WinsorizedMean => {
+                format!("Mean with the lowest and highest {percent}% clamped to the next value")
+            }
+            StatColumn::Mad => {
+                "Median absolute deviation from the median (× 1.4826 estimates σ)".to_string()
+            }
+            StatColumn::IqrSigma => "Interquartile range over 1.349, a robust σ".to_string(),
+            StatColumn::Skewness => "Sample skewness (G1); 0 for symmetric data".to_string(),
+            StatColumn::Kurtosis => "Excess kurtosis (G2); 0 for normal data".to_string(),
+            StatColumn::HodgesLehmann => {
+                "Hodges–Lehmann shift: median of all group minus control differences".to_string()
+            }
+        }
+    }
+
+    /// Value of the column for a group, `None` when it cannot be computed.
+    pub fn value(&self, gs: &GroupStats) -> Option<f64> {
+        let value = match self {
+            StatColumn::Mean => gs.mean,
+            StatColumn::Median => gs.median,
+            StatColumn::Std => gs.std,
+            StatColumn::P05 => gs.p05,
+            StatColumn::P95 => gs.p95,
+            StatColumn::TrimmedMean => gs.robust?.trimmed_mean,
+            StatColumn::WinsorizedMean => gs.robust?.winsorized_mean,
+            StatColumn::Mad => gs.robust?.mad,
+            StatColumn::IqrSigma => gs.robust?.iqr_sigma,
+            StatColumn::Skewness => return gs.robust?.skewness,
+            StatColumn::Kurtosis => return gs.robust?.kurtosis,
+            StatColumn::HodgesLehmann => return gs.hodges_lehmann,
+        };
+        (!value.is_nan()).then_some(value)
+    }
+}
+
+impl GroupStats {
+    /// Count, mean and sample variance.
+    pub fn moments(&self) -> Moments {
+        (self.count as f64, self.mean, self.variance)
+    }
+
+    /// The selected effect size with its confidence interval, e.g. "0.52 [0.10, 0.94]".
+    pub fn effect_size_label(&self, decimals: usize) -> String {
+        match (self.effect_size, self.effect_size_ci) {
+            (Some(value), Some((low, high))) => {
+                format!("{value:.decimals$} [{low:.decimals$}, {high:.decimals$}]")
+            }
+            (Some(value), None) => format!("{value:.decimals$}"),
+            (None, _) => "-".to_string(),
+        }
+    }
 }
 
 impl DataTypeStats {
@@ -66,11 +300,387 @@ impl DataTypeStats {
         groups
     }
 
-    /// Check if any group has significant p-value.
-    pub fn has_significant_results(&self) -> bool {
-        self.group_stats
+    /// Test that produced the p-values of this data type.
+    pub fn test(&self) -> Option<TestMethod> {
+        self.group_stats.values().find_map(|gs| gs.test)
+    }
+
+    /// Whether the groups were compared with the control pair by pair.
+    pub fn is_paired(&self) -> bool {
+        self.group_stats.values().any(|gs| gs.paired.is_some())
+    }
+
+    /// Compare every group with the control on its paired values with a paired
+    /// `test`.
+    pub fn apply_pairing(&mut self, paired: &HashMap<String, PairedValues>, test: TestMethod) {
+        for (group, gs) in self.group_stats.iter_mut() {
+            let Some(values) = paired.get(group) else {
+                continue;
+            };
+            let stats = PairedStats::compute(values);
+            gs.p_value = stats.p_value(test);
+            gs.test = Some(test);
+            gs.paired = Some(stats);
+        }
+    }
+
+    /// Set the p-values of every group to Dunnett's, from the moments of the
+    /// non-empty groups.
+    pub fn apply_dunnett(&mut self, moments: &HashMap<&str, Moments>) {
+        let Some(&control) = moments.get(self.control_group.as_str()) else {
+            return;
+        };
+        let mut groups: Vec<&str> = moments
+            .keys()
+            .copied()
+            .filter(|g| *g != self.control_group)
+            .collect();
+        groups.sort();
+        let group_moments: Vec<Moments> = groups.iter().map(|g| moments[g]).collect();
+        let Some(dunnett) = Dunnett::new(control, &group_moments) else {
+            return;
+        };
+
+        for (group, p) in groups.iter().zip(dunnett.p_values()) {
+            if let Some(gs) = self.group_stats.get_mut(*group) {
+                gs.p_value = Some(p);
+                gs.test = Some(TestMethod::Dunnett);
+            }
+        }
+        self.dunnett = Some(dunnett);
+    }
+
+    /// Replace the p-values of a test assuming normality with Mann–Whitney U when
+    /// normality of any group, the control included, is rejected at `alpha`.
+    /// `values` returns the values of a group. Paired t-tests fall back to the
+    /// Wilcoxon signed-rank test when normality of any group's differences is.
+    pub fn apply_normality_fallback<'a>(
+        &mut self,
+        alpha: f64,
+        values: impl Fn(&str) -> Option<&'a [f64]>,
+    ) {
+        if self.test() == Some(TestMethod::PairedT) {
+            self.apply_paired_normality_fallback(alpha);
+            return;
+        }
+
+        let rejected = self
+            .group_stats
+            .values()
+            .any(|gs| gs.normality.is_rejected(alpha));
+        if !rejected || !self.test().is_some_and(|test| test.assumes_normality()) {
+            return;
+        }
+
+        let control = values(&self.control_group).unwrap_or_default();
+        for (group, gs) in self.group_stats.iter_mut() {
+            if gs.test.is_none() {
+                continue;
+            }
+            let group_values = values(group).unwrap_or_default();
+            gs.p_value = Some(hypothesis::MannWhitneyU.p_value(group_values, control));
+            gs.test = Some(TestMethod::MannWhitney);
+        }
+        self.dunnett = None;
+        self.normality_fallback = true;
+    }
+
+    fn apply_paired_normality_fallback(&mut self, alpha: f64) {
+        let rejected = self
+            .group_stats
+            .values()
+            .filter_map(|gs| gs.paired.as_ref())
+            .any(|paired| paired.normality.is_rejected(alpha));
+        if !rejected {
+            return;
+        }
+
+        for gs in self.group_stats.values_mut() {
+            if let Some(paired) = &gs.paired {
+                gs.p_value = paired.wilcoxon_p;
+                gs.test = Some(TestMethod::WilcoxonSignedRank);
+            }
+        }
+        self.normality_fallback = true;
+    }
+
+    /// Compute the mean difference and effect size confidence intervals at `level`;
+    /// paired groups get the interval of their mean difference.
+    pub fn apply_confidence_level(&mut self, level: f64) {
+        let control = self
+            .group_stats
+            .get(&self.control_group)
+            .map(GroupStats::moments);
+        for (group, gs) in self.group_stats.iter_mut() {
+            gs.mean_diff_ci = match &gs.paired {
+                Some(paired) => {
+                    let (differences, zero) = paired.as_two_sample();
+                    hypothesis::mean_difference_ci(differences, zero, level)
+                }
+                None => control
+                    .filter(|_| *group != self.control_group)
+                    .and_then(|control| {
+                        hypothesis::mean_difference_ci(gs.moments(), control, level)
+                    }),
+            };
+        }
+        self.ci_level = level;
+        self.refresh_effect_sizes();
+    }
+
+    /// Select the effect size shown and used by the mismatch rules.
+    pub fn select_effect_size(&mut self, method: EffectSizeMethod) {
+        self.effect_size_method = method;
+        self.refresh_effect_sizes();
+    }
+
+    fn refresh_effect_sizes(&mut self) {
+        for gs in self.group_stats.values_mut() {
+            let selected = gs
+                .effect_sizes
+                .iter()
+                .find(|es| es.method == self.effect_size_method);
+            gs.effect_size = selected.map(|es| es.value);
+            gs.effect_size_ci = selected.and_then(|es| es.ci(self.ci_level));
+        }
+    }
+
+    /// Column header of the mean difference intervals, e.g. "95% CI".
+    pub fn ci_header(&self) -> String {
+        format!("{}% CI", (self.ci_level * 1000.0).round() / 10.0)
+    }
+
+    /// Result of the selected omnibus test.
+    pub fn omnibus(&self) -> Option<&OmnibusResult> {
+        self.omnibus
             .iter()
-            .any(|(name, gs)| name != &self.control_group && gs.is_significant)
+            .find(|result| result.method == self.omnibus_method)
+    }
+
+    /// Caption naming the test behind the p-values, with Dunnett's critical value.
+    pub fn test_label(&self) -> Option<String> {
+        let test = self.test()?;
+        if self.normality_fallback {
+            return Some(format!("P-values: {} (normality rejected)", test.name()));
+        }
+        Some(match self.dunnett.as_ref().and_then(|d| d.critical) {
+            Some(critical) => format!(
+                "P-values: {} (critical |t| = {:.3} at α = {})",
+                test.name(),
+                critical.t,
+                critical.alpha
+            ),
+            None => format!("P-values: {}", test.name()),
+        })
+    }
+
+    /// Drop Dunnett's critical value when it was computed at another α; only a
+    /// calculation computes it again.
+    pub fn drop_stale_critical_value(&mut self, alpha: f64) {
+        if let Some(dunnett) = self.dunnett.as_mut() {
+            dunnett.critical = dunnett.critical.filter(|critical| critical.alpha == alpha);
+        }
+    }
+
+    /// Select the variance test shown and used by the mismatch rules.
+    pub fn select_variance_test(&mut self, test: VarianceTest) {
+        self.variance_test = test;
+        for gs in self.group_stats.values_mut() {
+            gs.variance_p = gs
+                .variance_tests
+                .iter()
+                .find(|result| result.test == test)
+                .map(|result| result.p_value);
+        }
+    }
+
+    /// Column headers of the bootstrap intervals, e.g. "BCa 95% ΔMean", when resampled.
+    pub fn bootstrap_headers(&self) -> Option<(String, String)> {
+        let level = self.group_stats.values().find_map(|gs| gs.bootstrap)?.level;
+        let percent = (level * 1000.0).round() / 10.0;
+        Some((
+            format!("BCa {percent}% ΔMean"),
+            format!("BCa {percent}% ΔMedian"),
+        ))
+    }
+
+    /// Whether the p-values were adjusted for multiple testing.
+    pub fn has_adjusted_p_values(&self) -> bool {
+        self.group_stats.values().any(|gs| gs.p_adjusted.is_some())
+    }
+
+    /// Flag the groups that are mismatches against the control.
+    pub fn apply_mismatch_rules(&mut self, alpha: f64, rules: &MismatchRules) {
+        let control_mean = self
+            .group_stats
+            .get(&self.control_group)
+            .map_or(f64::NAN, |gs| gs.mean);
+        let omnibus_p = self.omnibus().map(|result| result.p_value);
+        for (group, gs) in self.group_stats.iter_mut() {
+            gs.is_significant = *group != self.control_group
+                && rules.is_mismatch(&self.data_type, gs, control_mean, omnibus_p, alpha);
+        }
+    }
+
+    /// Test every group for equivalence with the control and settle its outcome;
+    /// run after `apply_mismatch_rules`, whose mismatches are the differences.
+    /// Paired groups are tested on their differences.
+    pub fn apply_equivalence(&mut self, alpha: f64, margins: &EquivalenceMargins) {
+        let Some(control) = self.group_stats.get(&self.control_group) else {
+            return;
+        };
+        let control_moments = control.moments();
+        let margin = margins.margin_for(&self.data_type);
+        let absolute = margin.absolute(control.std);
+        self.equivalence_margin = Some((margin, absolute));
+
+        for (group, gs) in self.group_stats.iter_mut() {
+            if *group == self.control_group {
+                continue;
+            }
+            let (group_moments, control_moments) = gs
+                .paired
+                .as_ref()
+                .map_or((gs.moments(), control_moments), PairedStats::as_two_sample);
+            gs.tost_p = equivalence::tost_p_value(group_moments, control_moments, absolute);
+            gs.outcome = if gs.is_significant {
+                Outcome::Different
+            } else if gs.tost_p.is_some_and(|p| p <= alpha) {
+                Outcome::Equivalent
+            } else {
+                Outcome::Inconclusive
+            };
+        }
+    }
+
+    /// Outcome of the data type: different when any group is, equivalent when
+    /// every group is, inconclusive otherwise.
+    pub fn outcome(&self) -> Outcome {
+        let outcomes: Vec<Outcome> = self
+            .group_stats
+            .iter()
+            .filter(|(name, _)| **name != self.control_group)
+            .map(|(_, gs)| gs.outcome)
+            .collect();
+        if outcomes.contains(&Outcome::Different) {
+            Outcome::Different
+        } else if !outcomes.is_empty() && outcomes.iter().all(|o| *o == Outcome::Equivalent) {
+            Outcome::Equivalent
+        } else {
+            Outcome::Inconclusive
+        }
+    }
+
+    /// Caption with the pair IDs found in only one of a group and the control, in
+    /// paired mode, e.g. "Unmatched IDs – B: W07, W12".
+    pub fn paired_label(&self) -> Option<String> {
+        if !self.is_paired() {
+            return None;
+        }
+        let unmatched: Vec<String> = self
+            .get_ordered_groups()
+            .iter()
+            .filter_map(|group| {
+                let paired = self.group_stats.get(group)?.paired.as_ref()?;
+                (!paired.unmatched.is_empty())
+                    .then(|| format!("{}: {}", group, paired.unmatched_label()))
+            })
+            .collect();
+        Some(if unmatched.is_empty() {
+            "Paired by ID: every ID matched".to_string()
+        } else {
+            format!("Unmatched IDs – {}", unmatched.join("; "))
+        })
+    }
+
+    /// Show `columns` in the stats tables, computing the robust statistics and the
+    /// Hodges–Lehmann shifts they show on first use.
+    pub fn select_columns<'a>(
+        &mut self,
+        columns: &[StatColumn],
+        values: impl Fn(&str) -> Option<&'a [f64]>,
+    ) {
+        self.columns = columns.to_vec();
+        let robust = columns.iter().any(StatColumn::is_robust);
+        let hodges_lehmann = columns.contains(&StatColumn::HodgesLehmann);
+        let control = values(&self.control_group);
+        for (group, gs) in self.group_stats.iter_mut() {
+            let Some(values) = values(group) else {
+                continue;
+            };
+            if robust && gs.robust.is_none() {
+                let mut sorted = values.to_vec();
+                sorted.sort_by(f64::total_cmp);
+                gs.robust = Some(RobustStats::compute(&sorted));
+            }
+            if hodges_lehmann && gs.hodges_lehmann.is_none() && *group != self.control_group {
+                gs.hodges_lehmann =
+                    control.and_then(|control| StatsCalculator::hodges_lehmann(values, control));
+            }
+        }
+    }
+
+    /// Compute every group's capability against the spec limits of the data type.
+    /// `values` returns the values of a group in the order they were measured.
+    pub fn apply_spec_limits<'a>(
+        &mut self,
+        specs: &SpecTable,
+        values: impl Fn(&str) -> Option<&'a [f64]>,
+    ) {
+        self.spec_limits = specs.limits_for(&self.data_type);
+        self.capability_sampled = false;
+        for (group, gs) in self.group_stats.iter_mut() {
+            let Some((limits, values)) = self.spec_limits.zip(values(group)) else {
+                gs.capability = None;
+                continue;
+            };
+            gs.capability = Capability::compute(values, &limits);
+            // The moving ranges of a sample skip the rows left out, so they are not
+            // the within spread of consecutive measurements.
+            if values.len() < gs.count {
+                if let Some(capability) = gs.capability.as_mut() {
+                    capability.cp = None;
+                    capability.cpk = None;
+                    self.capability_sampled = true;
+                }
+            }
+        }
+    }
+
+    /// Caption with the spec limits, e.g. "Spec: LSL 1 · USL 2 (Cp/Cpk from moving ranges)".
+    pub fn spec_label(&self) -> Option<String> {
+        let limits = self.spec_limits?;
+        let sampled = if self.capability_sampled {
+            "; no Cp/Cpk for sampled groups, whose moving ranges skip rows"
+        } else {
+            ""
+        };
+        Some(format!(
+            "Spec: {} (Cp/Cpk σ from moving ranges, Pp/Ppk overall σ{sampled})",
+            limits.describe()
+        ))
+    }
+
+    /// Caption with the outlier rule and how many outliers it found.
+    pub fn outlier_label(&self) -> Option<String> {
+        let (filter, alpha) = self.outlier_filter?;
+        let count = self.group_stats.values().map(|gs| gs.outliers).sum();
+        Some(filter.label(count, alpha))
+    }
+
+    /// Caption with the equivalence margin, e.g. "Equivalence margin: ±0.5 × control σ (±0.123)".
+    pub fn equivalence_label(&self) -> Option<String> {
+        let (margin, absolute) = self.equivalence_margin?;
+        Some(match margin.unit {
+            equivalence::MarginUnit::Absolute => {
+                format!("Equivalence margin: {}", margin.describe())
+            }
+            equivalence::MarginUnit::ControlSigma => format!(
+                "Equivalence margin: {} (±{:.4})",
+                margin.describe(),
+                absolute
+            ),
+        })
     }
 }
 
@@ -89,7 +699,7 @@ impl StatsCalculator {
         sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
 
         let mean = values.iter().sum::<f64>() / n as f64;
-        let median = if n % 2 == 0 {
+        let median = if n.is_multiple_of(2) {
             (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0
         } else {
             sorted[n / 2]
@@ -108,20 +718,38 @@ impl StatsCalculator {
         GroupStats {
             group_name: String::new(),
             count: n,
+            excluded: 0,
+            outliers: 0,
             mean,
             median,
             std,
             variance,
             p95,
             p05,
-            std_diff_from_control: None,
+            robust: None,
+            hodges_lehmann: None,
+            normality: Normality::test(&sorted),
+            effect_sizes: Vec::new(),
+            effect_size: None,
+            effect_size_ci: None,
+            mean_diff_ci: None,
+            sigma_ratio: None,
+            variance_tests: Vec::new(),
+            variance_p: None,
+            bootstrap: None,
+            paired: None,
+            capability: None,
             p_value: None,
+            test: None,
+            p_adjusted: None,
             is_significant: false,
+            tost_p: None,
+            outcome: Outcome::default(),
         }
     }
 
     /// Calculate percentile using linear interpolation (NumPy compatible).
-    fn percentile(sorted_values: &[f64], p: f64) -> f64 {
+    pub fn percentile(sorted_values: &[f64], p: f64) -> f64 {
         let n = sorted_values.len();
         if n == 0 {
             return f64::NAN;
@@ -142,200 +770,352 @@ impl StatsCalculator {
         }
     }
 
-    /// Perform Welch's t-test (independent samples, unequal variance).
-    pub fn perform_ttest(group_values: &[f64], control_values: &[f64]) -> (f64, bool) {
-        let n1 = group_values.len() as f64;
-        let n2 = control_values.len() as f64;
-
-        if n1 < 2.0 || n2 < 2.0 {
-            return (f64::NAN, false);
-        }
-
-        let mean1 = group_values.iter().sum::<f64>() / n1;
-        let mean2 = control_values.iter().sum::<f64>() / n2;
-
-        let var1 = group_values
-            .iter()
-            .map(|x| (x - mean1).powi(2))
-            .sum::<f64>()
-            / (n1 - 1.0);
-        let var2 = control_values
-            .iter()
-            .map(|x| (x - mean2).powi(2))
-            .sum::<f64>()
-            / (n2 - 1.0);
-
-        let se = (var1 / n1 + var2 / n2).sqrt();
-        if se == 0.0 {
-            return (1.0, false); // No variance difference
-        }
-
-        let t = (mean1 - mean2) / se;
+    /// Gather the values of every (data_type, group) of prepared long-format data
+    /// with a single group_by.
+    pub fn group_values(df: &DataFrame) -> PolarsResult<GroupedValues> {
+        let grouped = df
+            .clone()
+            .lazy()
+            .group_by([col("data_type"), col("group")])
+            .agg([col("value")])
+            .collect()?;
 
-        // Welch-Satterthwaite degrees of freedom
-        let df_num = (var1 / n1 + var2 / n2).powi(2);
-        let df_denom = (var1 / n1).powi(2) / (n1 - 1.0) + (var2 / n2).powi(2) / (n2 - 1.0);
-        let df = df_num / df_denom;
+        let data_types = grouped.column("data_type")?.cast(&DataType::String)?;
+        let groups = grouped.column("group")?.cast(&DataType::String)?;
+        let values = grouped.column("value")?.list()?.clone();
 
-        // Two-tailed p-value using t-distribution
-        if let Ok(dist) = StudentsT::new(0.0, 1.0, df) {
-            let p_value = 2.0 * (1.0 - dist.cdf(t.abs()));
-            let is_significant = p_value <= SIGNIFICANCE_THRESHOLD;
-            (p_value, is_significant)
-        } else {
-            (f64::NAN, false)
+        let mut result = GroupedValues::new();
+        for ((data_type, group), group_values) in data_types
+            .str()?
+            .into_iter()
+            .zip(groups.str()?)
+            .zip(&values)
+        {
+            let (Some(data_type), Some(group), Some(group_values)) =
+                (data_type, group, group_values)
+            else {
+                continue;
+            };
+            let group_values: Vec<f64> = group_values.f64()?.into_iter().flatten().collect();
+            result
+                .entry(data_type.to_string())
+                .or_default()
+                .insert(group.to_string(), group_values);
         }
-    }
-
-    /// Get values for a specific group from DataFrame.
-    pub fn get_values_for_group(df: &DataFrame, group: &str) -> Vec<f64> {
-        df.clone()
-            .lazy()
-            .filter(col("group").eq(lit(group)))
-            .select([col("value")])
-            .collect()
-            .ok()
-            .and_then(|df| df.column("value").ok().cloned())
-            .map(|col| {
-                col.f64()
-                    .ok()
-                    .map(|ca| ca.into_iter().filter_map(|v| v).collect())
-                    .unwrap_or_default()
-            })
-            .unwrap_or_default()
-    }
 
-    /// Get values for a specific data_type AND group from DataFrame.
-    /// This ensures the quantile plot data matches the statistics table.
-    pub fn get_values_for_data_type_and_group(
-        df: &DataFrame,
-        data_type: &str,
-        group: &str,
-    ) -> Vec<f64> {
-        df.clone()
-            .lazy()
-            .filter(
-                col("data_type")
-                    .eq(lit(data_type))
-                    .and(col("group").eq(lit(group))),
-            )
-            .select([col("value")])
-            .collect()
-            .ok()
-            .and_then(|df| df.column("value").ok().cloned())
-            .map(|col| {
-                col.f64()
-                    .ok()
-                    .map(|ca| ca.into_iter().filter_map(|v| v).collect())
-                    .unwrap_or_default()
-            })
-            .unwrap_or_default()
+        Ok(result)
     }
 
     /// Compute statistics for all groups within a data type.
     pub fn compute_data_type_stats(
-        df: &DataFrame,
         data_type: &str,
+        groups: &HashMap<String, Vec<f64>>,
         control_group: &str,
+        test: TestMethod,
     ) -> DataTypeStats {
-        // Filter for this data type
-        let type_df = df
-            .clone()
-            .lazy()
-            .filter(col("data_type").eq(lit(data_type)))
-            .collect()
-            .unwrap_or_default();
-
-        let groups: Vec<String> = type_df
-            .column("group")
-            .ok()
-            .and_then(|col| col.unique().ok())
-            .map(|unique| {
-                unique
-                    .as_materialized_series()
-                    .iter()
-                    .filter_map(|v| {
-                        if v.is_null() {
-                            None
-                        } else {
-                            Some(v.to_string().trim_matches('"').to_string())
-                        }
-                    })
-                    .collect()
-            })
-            .unwrap_or_default();
-
         let mut group_stats: HashMap<String, GroupStats> = HashMap::new();
 
         // First compute control group stats
-        let control_values = Self::get_values_for_group(&type_df, control_group);
-        let mut control_stats = Self::compute_descriptive_stats(&control_values);
+        let control_values = groups
+            .get(control_group)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let mut control_stats = Self::compute_descriptive_stats(control_values);
         control_stats.group_name = control_group.to_string();
-        let control_std = control_stats.std;
-        let control_mean = control_stats.mean;
+        let control_moments = control_stats.moments();
         group_stats.insert(control_group.to_string(), control_stats);
 
         // Compute stats for other groups
-        for group_name in &groups {
+        for (group_name, values) in groups {
             if group_name == control_group {
                 continue;
             }
 
-            let values = Self::get_values_for_group(&type_df, group_name);
-            let mut gs = Self::compute_descriptive_stats(&values);
+            let mut gs = Self::compute_descriptive_stats(values);
             gs.group_name = group_name.clone();
 
-            // Calculate standardized mean difference
-            if control_std > 0.0 && !control_mean.is_nan() {
-                gs.std_diff_from_control = Some((gs.mean - control_mean) / control_std);
-            }
+            gs.effect_sizes = EffectSizeMethod::compute_all(
+                values,
+                control_values,
+                gs.moments(),
+                control_moments,
+            );
+            gs.sigma_ratio = Self::sigma_ratio(gs.moments(), control_moments);
+            gs.variance_tests =
+                VarianceTest::run_all(values, control_values, gs.moments(), control_moments);
 
-            // Perform t-test
+            // Compare with the control group; permutation p-values come from `resample`
             if !control_values.is_empty() {
-                let (p_value, is_significant) = Self::perform_ttest(&values, &control_values);
-                gs.p_value = Some(p_value);
-                gs.is_significant = is_significant;
+                if let Some(pairwise) = test.pairwise() {
+                    gs.p_value = Some(pairwise.p_value(values, control_values));
+                    gs.test = Some(test);
+                } else if test == TestMethod::Permutation {
+                    gs.test = Some(test);
+                }
             }
 
             group_stats.insert(group_name.clone(), gs);
         }
 
-        DataTypeStats {
+        // Omnibus tests over the non-empty groups, in name order
+        let mut names: Vec<&String> = groups.keys().filter(|g| !groups[*g].is_empty()).collect();
+        names.sort();
+        let values: Vec<&[f64]> = names.iter().map(|g| groups[*g].as_slice()).collect();
+        let moments: Vec<_> = values.iter().map(|v| hypothesis::moments(v)).collect();
+
+        let mut stats = DataTypeStats {
             data_type: data_type.to_string(),
             control_group: control_group.to_string(),
             group_stats,
+            omnibus: OmnibusMethod::run_all(&values, &moments),
+            omnibus_method: OmnibusMethod::default(),
+            dunnett: None,
+            ci_level: DEFAULT_CI_LEVEL,
+            effect_size_method: EffectSizeMethod::default(),
+            normality_fallback: false,
+            variance_test: VarianceTest::default(),
+            equivalence_margin: None,
+            spec_limits: None,
+            capability_sampled: false,
+            columns: StatColumn::DEFAULT.to_vec(),
+            outlier_filter: None,
+        };
+        stats.apply_confidence_level(DEFAULT_CI_LEVEL);
+        stats.select_variance_test(VarianceTest::default());
+        if test == TestMethod::Dunnett {
+            let by_group = names.iter().map(|g| g.as_str()).zip(moments).collect();
+            stats.apply_dunnett(&by_group);
+        }
+        stats
+    }
+
+    /// Hodges–Lehmann shift of a group's values from the control's.
+    pub fn hodges_lehmann(values: &[f64], control: &[f64]) -> Option<f64> {
+        let sort = |values: &[f64]| {
+            let mut sorted = values.to_vec();
+            sorted.sort_by(f64::total_cmp);
+            sorted
+        };
+        robust::hodges_lehmann(&sort(values), &sort(control))
+    }
+
+    /// Ratio of a group's standard deviation to the control's.
+    pub fn sigma_ratio(group: Moments, control: Moments) -> Option<f64> {
+        let (n1, _, var1) = group;
+        let (n0, _, var0) = control;
+        (n1 >= 2.0 && n0 >= 2.0 && var0 > 0.0).then(|| (var1 / var0).sqrt())
+    }
+
+    /// Record how many values of each data type and group were dropped before analysis.
+    pub fn attach_excluded_counts(
+        stats: &mut HashMap<String, DataTypeStats>,
+        excluded: &HashMap<(String, String), DropCounts>,
+    ) {
+        for (data_type, stat) in stats.iter_mut() {
+            for (group, gs) in stat.group_stats.iter_mut() {
+                gs.excluded = excluded
+                    .get(&(data_type.clone(), group.clone()))
+                    .map(|counts| counts.total())
+                    .unwrap_or(0);
+            }
+        }
+    }
+
+    /// Record the outliers of each data type and group found by `filter` at `alpha`.
+    pub fn attach_outliers(
+        stats: &mut HashMap<String, DataTypeStats>,
+        filter: &OutlierFilter,
+        alpha: f64,
+        outliers: &GroupedValues,
+    ) {
+        if !filter.is_active() {
+            return;
+        }
+        for (data_type, stat) in stats.iter_mut() {
+            stat.outlier_filter = Some((*filter, alpha));
+            for (group, gs) in stat.group_stats.iter_mut() {
+                gs.outliers = outliers
+                    .get(data_type)
+                    .and_then(|groups| groups.get(group))
+                    .map_or(0, Vec::len);
+            }
+        }
+    }
+
+    /// Adjust the p-values of all (data_type, group) comparisons as one family; the
+    /// mismatch rules then use the adjusted values. Data types tested with Dunnett
+    /// are left out, as their p-values are already adjusted.
+    pub fn apply_correction(stats: &mut HashMap<String, DataTypeStats>, method: CorrectionMethod) {
+        if method == CorrectionMethod::None {
+            return;
+        }
+
+        let mut comparisons: Vec<&mut GroupStats> = stats
+            .values_mut()
+            .filter(|stat| stat.dunnett.is_none())
+            .flat_map(|stat| {
+                let control_group = &stat.control_group;
+                stat.group_stats
+                    .iter_mut()
+                    .filter(move |(group, _)| *group != control_group)
+                    .map(|(_, gs)| gs)
+            })
+            .filter(|gs| gs.p_value.is_some_and(|p| !p.is_nan()))
+            .collect();
+
+        let raw: Vec<f64> = comparisons
+            .iter()
+            .map(|gs| gs.p_value.unwrap_or(f64::NAN))
+            .collect();
+        for (gs, adjusted) in comparisons.iter_mut().zip(method.adjust(&raw)) {
+            gs.p_adjusted = Some(adjusted);
+        }
+    }
+
+    /// Fall back to Mann–Whitney U in every data type whose normality is rejected
+    /// at `alpha`; run before `apply_correction`. `values` returns the values of a
+    /// (data_type, group).
+    pub fn apply_normality_fallback<'a>(
+        stats: &mut HashMap<String, DataTypeStats>,
+        alpha: f64,
+        values: impl Fn(&str, &str) -> Option<&'a [f64]> + Sync,
+    ) {
+        stats.par_iter_mut().for_each(|(data_type, stat)| {
+            stat.apply_normality_fallback(alpha, |group| values(data_type, group));
+        });
+    }
+
+    /// Bootstrap intervals and permutation p-values of every data type; run before
+    /// `apply_correction`. `progress` is called with (done, total) comparisons.
+    pub fn resample(
+        stats: &mut HashMap<String, DataTypeStats>,
+        grouped: &GroupedValues,
+        resampler: &Resampler,
+        progress: impl Fn(usize, usize) + Sync,
+    ) {
+        resampler.run(
+            stats,
+            |data_type, group| grouped.get(data_type)?.get(group).map(Vec::as_slice),
+            progress,
+        );
+    }
+
+    /// Compare the groups of every data type with the control pair by pair; run
+    /// right after computing the statistics.
+    pub fn apply_pairing(
+        stats: &mut HashMap<String, DataTypeStats>,
+        paired: &PairedGroups,
+        test: TestMethod,
+    ) {
+        stats.par_iter_mut().for_each(|(data_type, stat)| {
+            if let Some(groups) = paired.get(data_type) {
+                stat.apply_pairing(groups, test);
+            }
+        });
+    }
+
+    /// Compute the capability of every group against its data type's spec limits.
+    /// `values` returns the values of a (data_type, group).
+    pub fn apply_spec_limits<'a>(
+        stats: &mut HashMap<String, DataTypeStats>,
+        specs: &SpecTable,
+        values: impl Fn(&str, &str) -> Option<&'a [f64]> + Sync,
+    ) {
+        stats.par_iter_mut().for_each(|(data_type, stat)| {
+            stat.apply_spec_limits(specs, |group| values(data_type, group));
+        });
+    }
+
+    /// Compute the Dunnett critical |t| at `alpha` for every data type tested with it.
+    pub fn attach_critical_values(stats: &mut HashMap<String, DataTypeStats>, alpha: f64) {
+        stats.par_iter_mut().for_each(|(_, stat)| {
+            if let Some(dunnett) = stat.dunnett.as_mut() {
+                dunnett.critical = Some(CriticalValue {
+                    alpha,
+                    t: dunnett.critical_value(alpha),
+                });
+            }
+        });
+    }
+
+    /// Select the effect size of every data type.
+    pub fn select_effect_size(
+        stats: &mut HashMap<String, DataTypeStats>,
+        method: EffectSizeMethod,
+    ) {
+        for stat in stats.values_mut() {
+            stat.select_effect_size(method);
+        }
+    }
+
+    /// Show `columns` in the stats tables of every data type, computing what they
+    /// need from each group's values.
+    pub fn select_columns<'a>(
+        stats: &mut HashMap<String, DataTypeStats>,
+        columns: &[StatColumn],
+        values: impl Fn(&str, &str) -> Option<&'a [f64]> + Sync,
+    ) {
+        stats.par_iter_mut().for_each(|(data_type, stat)| {
+            stat.select_columns(columns, |group| values(data_type, group));
+        });
+    }
+
+    /// Compute the mean difference confidence intervals of every data type at `level`.
+    pub fn apply_confidence_level(stats: &mut HashMap<String, DataTypeStats>, level: f64) {
+        for stat in stats.values_mut() {
+            stat.apply_confidence_level(level);
+        }
+    }
+
+    /// Select the variance test of every data type.
+    pub fn select_variance_test(stats: &mut HashMap<String, DataTypeStats>, test: VarianceTest) {
+        for stat in stats.values_mut() {
+            stat.select_variance_test(test);
+        }
+    }
+
+    /// Select the omnibus test reported for every data type; run before
+    /// `apply_mismatch_rules`.
+    pub fn select_omnibus(stats: &mut HashMap<String, DataTypeStats>, method: OmnibusMethod) {
+        for stat in stats.values_mut() {
+            stat.omnibus_method = method;
+        }
+    }
+
+    /// Flag mismatches in every data type; run after `apply_correction`.
+    pub fn apply_mismatch_rules(
+        stats: &mut HashMap<String, DataTypeStats>,
+        alpha: f64,
+        rules: &MismatchRules,
+    ) {
+        for stat in stats.values_mut() {
+            stat.apply_mismatch_rules(alpha, rules);
+        }
+    }
+
+    /// Test equivalence and settle the outcome in every data type; run after
+    /// `apply_mismatch_rules`.
+    pub fn apply_equivalence(
+        stats: &mut HashMap<String, DataTypeStats>,
+        alpha: f64,
+        margins: &EquivalenceMargins,
+    ) {
+        for stat in stats.values_mut() {
+            stat.apply_equivalence(alpha, margins);
         }
     }
 
     /// Compute statistics for all data types in parallel.
     pub fn compute_all_stats_parallel(
-        df: &DataFrame,
+        grouped: &GroupedValues,
         control_group: &str,
+        test: TestMethod,
     ) -> HashMap<String, DataTypeStats> {
-        let data_types: Vec<String> = df
-            .column("data_type")
-            .ok()
-            .and_then(|col| col.unique().ok())
-            .map(|unique| {
-                unique
-                    .as_materialized_series()
-                    .iter()
-                    .filter_map(|v| {
-                        if v.is_null() {
-                            None
-                        } else {
-                            Some(v.to_string().trim_matches('"').to_string())
-                        }
-                    })
-                    .collect()
-            })
-            .unwrap_or_default();
-
         // Use rayon for parallel computation
-        data_types
+        grouped
             .par_iter()
-            .map(|data_type| {
-                let stats = Self::compute_data_type_stats(df, data_type, control_group);
+            .map(|(data_type, groups)| {
+                let stats = Self::compute_data_type_stats(data_type, groups, control_group, test);
                 (data_type.clone(), stats)
             })
             .collect()
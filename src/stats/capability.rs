@@ -0,0 +1,141 @@
+//! Capability Module
+//! Process capability of each group against the spec limits of its data type:
+//! Cp and Cpk from the within (short-term) spread, Pp and Ppk from the overall
+//! spread, and the share of values out of spec.
+
+use super::hypothesis;
+use std::collections::HashMap;
+
+/// Bias correction d2 of the range of two consecutive values.
+const D2: f64 = 1.128;
+
+/// Spec limits of a data type; one-sided specs leave a limit out.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SpecLimits {
+    pub lsl: Option<f64>,
+    pub target: Option<f64>,
+    pub usl: Option<f64>,
+}
+
+impl SpecLimits {
+    /// The limits and the target with their names.
+    fn lines(&self) -> Vec<(&'static str, f64)> {
+        [
+            ("LSL", self.lsl),
+            ("Target", self.target),
+            ("USL", self.usl),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| Some((name, value?)))
+        .collect()
+    }
+
+    /// Short description such as "LSL 1 · Target 1.5 · USL 2".
+    pub fn describe(&self) -> String {
+        self.lines()
+            .iter()
+            .map(|(name, value)| format!("{name} {value}"))
+            .collect::<Vec<_>>()
+            .join(" · ")
+    }
+
+    /// Whether a value lies outside the limits.
+    pub fn is_out(&self, value: f64) -> bool {
+        self.lsl.is_some_and(|lsl| value < lsl) || self.usl.is_some_and(|usl| value > usl)
+    }
+
+    /// Spec width over six sigma (Cp, Pp); needs both limits.
+    fn potential(&self, sigma: f64) -> Option<f64> {
+        let (lsl, usl) = (self.lsl?, self.usl?);
+        (sigma > 0.0).then(|| (usl - lsl) / (6.0 * sigma))
+    }
+
+    /// Distance from the mean to the nearest limit over three sigma (Cpk, Ppk).
+    fn actual(&self, mean: f64, sigma: f64) -> Option<f64> {
+        if sigma <= 0.0 {
+            return None;
+        }
+        let upper = self.usl.map(|usl| (usl - mean) / (3.0 * sigma));
+        let lower = self.lsl.map(|lsl| (mean - lsl) / (3.0 * sigma));
+        match (upper, lower) {
+            (Some(upper), Some(lower)) => Some(upper.min(lower)),
+            (upper, lower) => upper.or(lower),
+        }
+    }
+}
+
+/// Spec limits per data type.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SpecTable {
+    pub limits: HashMap<String, SpecLimits>,
+}
+
+impl SpecTable {
+    /// Columns read from an imported spec table.
+    pub const TABLE_COLUMNS: [&'static str; 3] = ["lsl", "target", "usl"];
+
+    /// Spec limits of a data type, if it has any.
+    pub fn limits_for(&self, data_type: &str) -> Option<SpecLimits> {
+        self.limits.get(data_type).copied()
+    }
+
+    /// Replace the limits with rows of `TABLE_COLUMNS` values, skipping rows with
+    /// no limit or with LSL above USL. Returns how many were read.
+    pub fn import(&mut self, rows: HashMap<String, Vec<Option<f64>>>) -> usize {
+        self.limits = rows
+            .into_iter()
+            .filter_map(|(data_type, row)| {
+                let value = |i: usize| row.get(i).copied().flatten();
+                let limits = SpecLimits {
+                    lsl: value(0),
+                    target: value(1),
+                    usl: value(2),
+                };
+                let valid = match (limits.lsl, limits.usl) {
+                    (Some(lsl), Some(usl)) => lsl < usl,
+                    (lsl, usl) => lsl.or(usl).is_some(),
+                };
+                valid.then_some((data_type, limits))
+            })
+            .collect();
+        self.limits.len()
+    }
+}
+
+/// Capability indices of one group.
+#[derive(Debug, Clone, Copy)]
+pub struct Capability {
+    pub cp: Option<f64>,
+    pub cpk: Option<f64>,
+    pub pp: Option<f64>,
+    pub ppk: Option<f64>,
+    /// Percentage of values outside the limits
+    pub out_of_spec: f64,
+}
+
+impl Capability {
+    /// Capability of values in the order they were measured. The within sigma is
+    /// the average moving range over d2, the overall sigma the sample standard
+    /// deviation. `None` below two values.
+    pub fn compute(values: &[f64], limits: &SpecLimits) -> Option<Self> {
+        let n = values.len();
+        if n < 2 {
+            return None;
+        }
+
+        let (_, mean, variance) = hypothesis::moments(values);
+        let overall = variance.sqrt();
+        let moving_range =
+            values.windows(2).map(|w| (w[1] - w[0]).abs()).sum::<f64>() / (n - 1) as f64;
+        let within = moving_range / D2;
+        let out = values.iter().filter(|x| limits.is_out(**x)).count();
+
+        Some(Self {
+            cp: limits.potential(within),
+            cpk: limits.actual(mean, within),
+            pp: limits.potential(overall),
+            ppk: limits.actual(mean, overall),
+            out_of_spec: 100.0 * out as f64 / n as f64,
+        })
+    }
+}
@@ -0,0 +1,79 @@
+//! Multiple Testing Correction Module
+//! Adjusts the p-values of all (data_type, group) comparisons of a run so that
+//! testing hundreds of data types does not flood the results with false mismatches.
+
+/// How p-values are adjusted across all comparisons of a run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CorrectionMethod {
+    /// Raw p-values
+    #[default]
+    None,
+    /// Family-wise error rate, p * m
+    Bonferroni,
+    /// Family-wise error rate, step-down; never less powerful than Bonferroni
+    Holm,
+    /// False discovery rate, step-up
+    BenjaminiHochberg,
+}
+
+impl CorrectionMethod {
+    pub const ALL: [CorrectionMethod; 4] = [
+        CorrectionMethod::None,
+        CorrectionMethod::Bonferroni,
+        CorrectionMethod::Holm,
+        CorrectionMethod::BenjaminiHochberg,
+    ];
+
+    /// Human readable name for the control panel.
+    pub fn name(&self) -> &'static str {
+        match self {
+            CorrectionMethod::None => "None",
+            CorrectionMethod::Bonferroni => "Bonferroni",
+            CorrectionMethod::Holm => "Holm",
+            CorrectionMethod::BenjaminiHochberg => "Benjamini–Hochberg",
+        }
+    }
+
+    /// Adjust a family of p-values, returned in the input order.
+    pub fn adjust(&self, p_values: &[f64]) -> Vec<f64> {
+        let m = p_values.len();
+        let m_f = m as f64;
+
+        // Indices ordered by ascending p-value
+        let mut order: Vec<usize> = (0..m).collect();
+        order.sort_by(|&a, &b| {
+            p_values[a]
+                .partial_cmp(&p_values[b])
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        let mut adjusted = vec![0.0; m];
+        match self {
+            CorrectionMethod::None => adjusted.copy_from_slice(p_values),
+            CorrectionMethod::Bonferroni => {
+                for (adj, p) in adjusted.iter_mut().zip(p_values) {
+                    *adj = (p * m_f).min(1.0);
+                }
+            }
+            CorrectionMethod::Holm => {
+                // Running maximum keeps the adjusted values monotone in p
+                let mut running_max: f64 = 0.0;
+                for (rank, &i) in order.iter().enumerate() {
+                    let value = ((m - rank) as f64 * p_values[i]).min(1.0);
+                    running_max = running_max.max(value);
+                    adjusted[i] = running_max;
+                }
+            }
+            CorrectionMethod::BenjaminiHochberg => {
+                // Running minimum from the largest p-value down
+                let mut running_min: f64 = 1.0;
+                for (rank, &i) in order.iter().enumerate().rev() {
+                    let value = (m_f / (rank + 1) as f64 * p_values[i]).min(1.0);
+                    running_min = running_min.min(value);
+                    adjusted[i] = running_min;
+                }
+            }
+        }
+        adjusted
+    }
+}
@@ -0,0 +1,209 @@
+//! Dunnett Module
+//! Dunnett's many-to-one comparison of every group with the control. The group
+//! t statistics share the control and the pooled variance, so they follow a
+//! multivariate t distribution; p-values and critical values integrate it directly.
+
+use super::hypothesis::Moments;
+use statrs::distribution::{ContinuousCDF, Normal};
+use statrs::function::gamma::ln_gamma;
+
+/// Integration nodes per dimension. The integrands decay like a Gaussian, so the
+/// midpoint rule converges quickly.
+const NODES: usize = 120;
+
+/// Half width of the integration range of the common normal component.
+const Z_RANGE: f64 = 7.0;
+
+/// Above this many degrees of freedom the pooled standard deviation is treated
+/// as known.
+const LARGE_DF: f64 = 1e5;
+
+/// Critical |t| of the test at a significance level.
+#[derive(Debug, Clone, Copy)]
+pub struct CriticalValue {
+    pub alpha: f64,
+    pub t: f64,
+}
+
+/// Dunnett's test of several groups against one control.
+#[derive(Debug, Clone)]
+pub struct Dunnett {
+    /// Degrees of freedom of the pooled variance
+    df: f64,
+    /// sqrt(n_i / (n_i + n_0)); the t statistics of groups i and j correlate by λ_i λ_j
+    lambdas: Vec<f64>,
+    t_values: Vec<f64>,
+    /// Set by `StatsCalculator::attach_critical_values`
+    pub critical: Option<CriticalValue>,
+}
+
+impl Dunnett {
+    /// Set up the test from the moments of the control and of each group; `None`
+    /// when there are no error degrees of freedom.
+    pub fn new(control: Moments, groups: &[Moments]) -> Option<Self> {
+        let all = || std::iter::once(&control).chain(groups);
+        let total: f64 = all().map(|(n, _, _)| n).sum();
+        let df = total - (groups.len() + 1) as f64;
+        if groups.is_empty()
+            || control.0 < 1.0
+            || groups.iter().any(|(n, _, _)| *n < 1.0)
+            || df < 1.0
+        {
+            return None;
+        }
+
+        let pooled_var = all().map(|(n, _, var)| (n - 1.0) * var).sum::<f64>() / df;
+        let (n0, mean0, _) = control;
+        let t_values = groups
+            .iter()
+            .map(|(n, mean, _)| {
+                let diff = mean - mean0;
+                let se = (pooled_var * (1.0 / n + 1.0 / n0)).sqrt();
+                if se > 0.0 {
+                    diff / se
+                } else if diff == 0.0 {
+                    0.0
+                } else {
+                    diff.signum() * f64::INFINITY
+                }
+            })
+            .collect();
+        let lambdas = groups
+            .iter()
+            .map(|(n, _, _)| (n / (n + n0)).sqrt())
+            .collect();
+
+        Some(Self {
+            df,
+            lambdas,
+            t_values,
+            critical: None,
+        })
+    }
+
+    /// Two-sided adjusted p-value of each group, P(max |T| ≥ |t_i|).
+    pub fn p_values(&self) -> Vec<f64> {
+        self.t_values
+            .iter()
+            .map(|t| (1.0 - self.max_abs_cdf(t.abs())).clamp(0.0, 1.0))
+            .collect()
+    }
+
+    /// The |t| every group must exceed to be significant at `alpha`.
+    pub fn critical_value(&self, alpha: f64) -> f64 {
+        let (mut low, mut high) = (0.0, 1.0);
+        while 1.0 - self.max_abs_cdf(high) > alpha && high < 1e3 {
+            high *= 2.0;
+        }
+        for _ in 0..24 {
+            let mid = (low + high) / 2.0;
+            if 1.0 - self.max_abs_cdf(mid) > alpha {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        (low + high) / 2.0
+    }
+
+    /// P(max_i |T_i| < c). Each T_i = (λ_i Z + sqrt(1 - λ_i²) Y_i) / S with Z and Y_i
+    /// standard normal and S the pooled standard deviation scale (χ_ν / sqrt(ν)),
+    /// so conditioning on Z and S leaves a product of independent normal probabilities.
+    fn max_abs_cdf(&self, c: f64) -> f64 {
+        if c <= 0.0 {
+            return 0.0;
+        }
+        if c.is_infinite() {
+            return 1.0;
+        }
+
+        let normal = Normal::new(0.0, 1.0).expect("standard normal");
+        let scales: Vec<f64> = self.lambdas.iter().map(|l| (1.0 - l * l).sqrt()).collect();
+        let given_scale = |s: f64| {
+            let h = 2.0 * Z_RANGE / NODES as f64;
+            (0..NODES)
+                .map(|i| {
+                    let z = -Z_RANGE + (i as f64 + 0.5) * h;
+                    let inside: f64 = self
+                        .lambdas
+                        .iter()
+                        .zip(&scales)
+                        .map(|(l, r)| {
+                            normal.cdf((c * s - l * z) / r) - normal.cdf((-c * s - l * z) / r)
+                        })
+                        .product();
+                    (-z * z / 2.0).exp() * inside * h
+                })
+                .sum::<f64>()
+                / (2.0 * std::f64::consts::PI).sqrt()
+        };
+
+        if self.df > LARGE_DF {
+            return given_scale(1.0);
+        }
+
+        // Integrate over the density of S, which concentrates around 1 as ν grows
+        let nu = self.df;
+        let spread = 10.0 / (2.0 * nu).sqrt();
+        let (low, high) = ((1.0 - spread).max(0.0), 1.0 + spread);
+        let h = (high - low) / NODES as f64;
+        let log_norm = nu / 2.0 * (nu / 2.0).ln() + 2f64.ln() - ln_gamma(nu / 2.0);
+        (0..NODES)
+            .map(|i| {
+                let s = low + (i as f64 + 0.5) * h;
+                let density = (log_norm + (nu - 1.0) * s.ln() - nu * s * s / 2.0).exp();
+                density * given_scale(s) * h
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Dunnett's test of `k` groups of `n` values each against a control of `n`.
+    fn balanced(k: usize, n: f64) -> Dunnett {
+        let groups = vec![(n, 0.0, 1.0); k];
+        Dunnett::new((n, 0.0, 1.0), &groups).expect("error degrees of freedom")
+    }
+
+    #[test]
+    fn critical_value_matches_tables_with_known_variance() {
+        // Two-sided α = 0.05, k = 2, df = ∞
+        let dunnett = balanced(2, 100_000.0);
+        assert!(dunnett.df > LARGE_DF);
+        let t = dunnett.critical_value(0.05);
+        assert!((t - 2.212).abs() < 2e-3, "critical |t| = {t}");
+    }
+
+    #[test]
+    fn critical_value_matches_tables_with_estimated_variance() {
+        // Two-sided α = 0.05, k = 4, df = 20
+        let dunnett = balanced(4, 5.0);
+        assert_eq!(dunnett.df, 20.0);
+        let t = dunnett.critical_value(0.05);
+        assert!((t - 2.65).abs() < 5e-3, "critical |t| = {t}");
+    }
+
+    #[test]
+    fn single_group_reduces_to_the_t_test() {
+        // With one group the critical value is the two-sided t quantile, t(0.975, 10) = 2.228
+        let t = balanced(1, 6.0).critical_value(0.05);
+        assert!((t - 2.228).abs() < 2e-3, "critical |t| = {t}");
+    }
+
+    #[test]
+    fn p_value_at_the_critical_value_is_alpha() {
+        for (k, n) in [(2, 100_000.0), (4, 5.0), (3, 12.0)] {
+            let mut dunnett = balanced(k, n);
+            for alpha in [0.01, 0.05, 0.1] {
+                let critical = dunnett.critical_value(alpha);
+                dunnett.t_values = vec![critical; k];
+                for p in dunnett.p_values() {
+                    assert!((p - alpha).abs() < 1e-4, "k = {k}, α = {alpha}: p = {p}");
+                }
+            }
+        }
+    }
+}
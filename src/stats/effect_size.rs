@@ -0,0 +1,148 @@
+//! Effect Size Module
+//! Standardized size of the difference between a group and the control, with
+//! large-sample standard errors for the confidence intervals.
+
+use super::hypothesis::{self, Moments};
+use statrs::distribution::{ContinuousCDF, Normal};
+
+/// How the difference from the control is standardized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EffectSizeMethod {
+    /// (M-C) / pooled standard deviation
+    CohensD,
+    /// Cohen's d with the small-sample bias correction
+    HedgesG,
+    /// (M-C) / control standard deviation
+    #[default]
+    GlassDelta,
+    /// P(X > Y) - P(X < Y) of a group value X and a control value Y
+    CliffsDelta,
+}
+
+/// One effect size of a group against the control.
+#[derive(Debug, Clone, Copy)]
+pub struct EffectSize {
+    pub method: EffectSizeMethod,
+    pub value: f64,
+    /// Large-sample standard error, when the sizes allow one
+    pub se: Option<f64>,
+}
+
+impl EffectSizeMethod {
+    pub const ALL: [EffectSizeMethod; 4] = [
+        EffectSizeMethod::CohensD,
+        EffectSizeMethod::HedgesG,
+        EffectSizeMethod::GlassDelta,
+        EffectSizeMethod::CliffsDelta,
+    ];
+
+    /// Human readable name for the control panel.
+    pub fn name(&self) -> &'static str {
+        match self {
+            EffectSizeMethod::CohensD => "Cohen's d",
+            EffectSizeMethod::HedgesG => "Hedges' g",
+            EffectSizeMethod::GlassDelta => "Glass's Δ",
+            EffectSizeMethod::CliffsDelta => "Cliff's δ",
+        }
+    }
+
+    /// Compute every effect size the data allows. The standardized mean
+    /// differences use the moments; Cliff's delta ranks the values.
+    pub fn compute_all(
+        group: &[f64],
+        control: &[f64],
+        group_moments: Moments,
+        control_moments: Moments,
+    ) -> Vec<EffectSize> {
+        Self::ALL
+            .iter()
+            .filter_map(|method| match method {
+                EffectSizeMethod::CliffsDelta => Self::cliffs_delta(group, control),
+                _ => method.standardized_difference(group_moments, control_moments),
+            })
+            .collect()
+    }
+
+    fn standardized_difference(&self, group: Moments, control: Moments) -> Option<EffectSize> {
+        let (n1, mean1, var1) = group;
+        let (n0, mean0, var0) = control;
+        if n1 < 1.0 || n0 < 1.0 {
+            return None;
+        }
+        let diff = mean1 - mean0;
+
+        let (value, se) = match self {
+            EffectSizeMethod::CohensD | EffectSizeMethod::HedgesG => {
+                let df = n1 + n0 - 2.0;
+                let pooled_sd = (((n1 - 1.0) * var1 + (n0 - 1.0) * var0) / df).sqrt();
+                if df < 1.0 || pooled_sd <= 0.0 {
+                    return None;
+                }
+                let d = diff / pooled_sd;
+                let se = ((n1 + n0) / (n1 * n0) + d * d / (2.0 * (n1 + n0))).sqrt();
+                if *self == EffectSizeMethod::HedgesG {
+                    let j = 1.0 - 3.0 / (4.0 * df - 1.0);
+                    (j * d, j * se)
+                } else {
+                    (d, se)
+                }
+            }
+            EffectSizeMethod::GlassDelta => {
+                let control_sd = var0.sqrt();
+                if control_sd <= 0.0 {
+                    return None;
+                }
+                let delta = diff / control_sd;
+                let se = ((n1 + n0) / (n1 * n0) + delta * delta / (2.0 * (n0 - 1.0))).sqrt();
+                (delta, se)
+            }
+            EffectSizeMethod::CliffsDelta => return None,
+        };
+
+        Some(EffectSize {
+            method: *self,
+            value,
+            se: se.is_finite().then_some(se),
+        })
+    }
+
+    /// Cliff's delta from the Mann–Whitney U statistic, with the Hanley–McNeil
+    /// standard error of the equivalent AUC.
+    fn cliffs_delta(group: &[f64], control: &[f64]) -> Option<EffectSize> {
+        if group.is_empty() || control.is_empty() {
+            return None;
+        }
+
+        let pooled: Vec<f64> = group.iter().chain(control).copied().collect();
+        let (ranks, _) = hypothesis::average_ranks(&pooled);
+        let (n1, n0) = (group.len() as f64, control.len() as f64);
+        let u = ranks[..group.len()].iter().sum::<f64>() - n1 * (n1 + 1.0) / 2.0;
+        let auc = u / (n1 * n0);
+
+        let q1 = auc / (2.0 - auc);
+        let q2 = 2.0 * auc * auc / (1.0 + auc);
+        let var_auc =
+            (auc * (1.0 - auc) + (n1 - 1.0) * (q1 - auc * auc) + (n0 - 1.0) * (q2 - auc * auc))
+                / (n1 * n0);
+
+        Some(EffectSize {
+            method: EffectSizeMethod::CliffsDelta,
+            value: 2.0 * auc - 1.0,
+            se: (var_auc > 0.0).then(|| 2.0 * var_auc.sqrt()),
+        })
+    }
+}
+
+impl EffectSize {
+    /// Normal-approximation confidence interval at `level`; Cliff's delta is
+    /// kept within [-1, 1].
+    pub fn ci(&self, level: f64) -> Option<(f64, f64)> {
+        let se = self.se?;
+        let z = Normal::new(0.0, 1.0).ok()?.inverse_cdf((1.0 + level) / 2.0);
+        let (low, high) = (self.value - z * se, self.value + z * se);
+        Some(match self.method {
+            EffectSizeMethod::CliffsDelta => (low.max(-1.0), high.min(1.0)),
+            _ => (low, high),
+        })
+    }
+}
@@ -0,0 +1,166 @@
+//! Equivalence Module
+//! Two one-sided tests (TOST) of whether a group's mean lies within a margin of
+//! the control's, so that "no significant difference" is not mistaken for a match.
+
+use super::hypothesis::{welch_df, Moments};
+use statrs::distribution::{ContinuousCDF, StudentsT};
+use std::collections::HashMap;
+
+/// Default equivalence margin, in control standard deviations.
+pub const DEFAULT_MARGIN_SIGMA: f64 = 0.5;
+
+/// Unit of an equivalence margin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MarginUnit {
+    /// In the data's own units
+    Absolute,
+    /// In standard deviations of the control group
+    #[default]
+    ControlSigma,
+}
+
+impl MarginUnit {
+    pub const ALL: [MarginUnit; 2] = [MarginUnit::Absolute, MarginUnit::ControlSigma];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            MarginUnit::Absolute => "Absolute",
+            MarginUnit::ControlSigma => "× control σ",
+        }
+    }
+}
+
+/// Largest mean difference that still counts as equivalent.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EquivalenceMargin {
+    pub value: f64,
+    pub unit: MarginUnit,
+}
+
+impl Default for EquivalenceMargin {
+    fn default() -> Self {
+        Self {
+            value: DEFAULT_MARGIN_SIGMA,
+            unit: MarginUnit::ControlSigma,
+        }
+    }
+}
+
+impl EquivalenceMargin {
+    /// The margin in the data's units.
+    pub fn absolute(&self, control_std: f64) -> f64 {
+        match self.unit {
+            MarginUnit::Absolute => self.value,
+            MarginUnit::ControlSigma => self.value * control_std,
+        }
+    }
+
+    /// Short description such as "0.5 × control σ".
+    pub fn describe(&self) -> String {
+        match self.unit {
+            MarginUnit::Absolute => format!("±{}", self.value),
+            MarginUnit::ControlSigma => format!("±{} {}", self.value, self.unit.name()),
+        }
+    }
+}
+
+/// Equivalence margins, with per data type overrides.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EquivalenceMargins {
+    /// Margin for data types without their own
+    pub default: EquivalenceMargin,
+    /// Margin per data type, typically imported from a table
+    pub margins: HashMap<String, EquivalenceMargin>,
+}
+
+impl EquivalenceMargins {
+    /// Columns read from an imported margin table: an absolute margin or one in
+    /// control standard deviations.
+    pub const TABLE_COLUMNS: [&'static str; 2] = ["margin", "margin_sigma"];
+
+    /// Margin of a data type.
+    pub fn margin_for(&self, data_type: &str) -> EquivalenceMargin {
+        self.margins.get(data_type).copied().unwrap_or(self.default)
+    }
+
+    /// Replace the per data type margins with rows of `TABLE_COLUMNS` values; the
+    /// absolute margin wins when a row has both. Returns how many were read.
+    pub fn import(&mut self, rows: HashMap<String, Vec<Option<f64>>>) -> usize {
+        self.margins = rows
+            .into_iter()
+            .filter_map(|(data_type, row)| {
+                let absolute = row
+                    .first()
+                    .copied()
+                    .flatten()
+                    .map(|value| EquivalenceMargin {
+                        value,
+                        unit: MarginUnit::Absolute,
+                    });
+                let sigma = row
+                    .get(1)
+                    .copied()
+                    .flatten()
+                    .map(|value| EquivalenceMargin {
+                        value,
+                        unit: MarginUnit::ControlSigma,
+                    });
+                let margin = absolute.or(sigma).filter(|m| m.value >= 0.0)?;
+                Some((data_type, margin))
+            })
+            .collect();
+        self.margins.len()
+    }
+}
+
+/// Three-state result of comparing a group with the control.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Outcome {
+    /// Within the equivalence margin
+    Equivalent,
+    /// Flagged by the mismatch rules
+    Different,
+    /// Neither shown different nor shown equivalent
+    #[default]
+    Inconclusive,
+}
+
+impl Outcome {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Outcome::Equivalent => "Equivalent",
+            Outcome::Different => "Different",
+            Outcome::Inconclusive => "Inconclusive",
+        }
+    }
+
+    /// Order of the cards: differences first, equivalence last.
+    pub fn rank(&self) -> u8 {
+        match self {
+            Outcome::Different => 0,
+            Outcome::Inconclusive => 1,
+            Outcome::Equivalent => 2,
+        }
+    }
+}
+
+/// TOST p-value of |mean - control mean| < margin with Welch's standard error and
+/// degrees of freedom: the larger of the two one-sided p-values.
+pub fn tost_p_value(group: Moments, control: Moments, margin: f64) -> Option<f64> {
+    let (n1, mean1, var1) = group;
+    let (n0, mean0, var0) = control;
+    if n1 < 2.0 || n0 < 2.0 || !margin.is_finite() {
+        return None;
+    }
+
+    let diff = mean1 - mean0;
+    let se = (var1 / n1 + var0 / n0).sqrt();
+    if se == 0.0 {
+        return Some(if diff.abs() < margin { 0.0 } else { 1.0 });
+    }
+
+    let dist = StudentsT::new(0.0, 1.0, welch_df(group, control)).ok()?;
+    let p_lower = 1.0 - dist.cdf((diff + margin) / se);
+    let p_upper = dist.cdf((diff - margin) / se);
+    Some(p_lower.max(p_upper))
+}
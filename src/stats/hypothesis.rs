@@ -0,0 +1,340 @@
+//! Hypothesis Tests Module
+//! Two-sample tests comparing a group with the control. Every test implements
+//! `TwoSampleTest`, so the comparison used by a run is chosen with `TestMethod`;
+//! Dunnett's test compares all groups with the control at once (see `dunnett`),
+//! the permutation test resamples (see `resampling`) and the paired tests work on
+//! differences matched by ID (see `paired`).
+
+use statrs::distribution::{ContinuousCDF, Normal, StudentsT};
+
+/// Default confidence level of the mean difference intervals.
+pub const DEFAULT_CI_LEVEL: f64 = 0.95;
+
+/// (count, mean, sample variance) of one group.
+pub type Moments = (f64, f64, f64);
+
+/// A two-sided test of a group against the control.
+pub trait TwoSampleTest: Sync {
+    /// Name shown next to the p-values.
+    fn name(&self) -> &'static str;
+
+    /// Two-tailed p-value, NaN when the samples are too small.
+    fn p_value(&self, group: &[f64], control: &[f64]) -> f64;
+
+    /// Two-tailed p-value from the moments of both groups, for tests that only
+    /// need them; `None` when the test needs the values themselves.
+    fn p_value_from_moments(&self, _group: Moments, _control: Moments) -> Option<f64> {
+        None
+    }
+}
+
+/// Student's t-test with pooled variance.
+pub struct StudentT;
+
+/// Welch's t-test, not assuming equal variances.
+pub struct WelchT;
+
+/// Mann–Whitney U test, normal approximation with tie and continuity correction.
+pub struct MannWhitneyU;
+
+/// Two-sample Kolmogorov–Smirnov test, asymptotic distribution.
+pub struct KolmogorovSmirnov;
+
+/// The test comparing each group with the control.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TestMethod {
+    Student,
+    #[default]
+    Welch,
+    MannWhitney,
+    KolmogorovSmirnov,
+    /// Many-to-one comparison with family-wise error control within a data type
+    Dunnett,
+    /// Permutation test of the mean difference, run by the `resampling` engine
+    Permutation,
+    /// One-sample t-test of the differences matched by pair ID
+    PairedT,
+    /// Wilcoxon signed-rank test of the differences matched by pair ID
+    WilcoxonSignedRank,
+}
+
+impl TestMethod {
+    pub const ALL: [TestMethod; 6] = [
+        TestMethod::Student,
+        TestMethod::Welch,
+        TestMethod::MannWhitney,
+        TestMethod::KolmogorovSmirnov,
+        TestMethod::Dunnett,
+        TestMethod::Permutation,
+    ];
+
+    /// Tests of paired data, offered when a pair ID column is set.
+    pub const PAIRED: [TestMethod; 2] = [TestMethod::PairedT, TestMethod::WilcoxonSignedRank];
+
+    /// The implementation of a pairwise test; `None` for Dunnett's test, which
+    /// compares all groups together, the permutation test, which resamples, and
+    /// the paired tests, which need the pairs.
+    pub fn pairwise(&self) -> Option<&'static dyn TwoSampleTest> {
+        match self {
+            TestMethod::Student => Some(&StudentT),
+            TestMethod::Welch => Some(&WelchT),
+            TestMethod::MannWhitney => Some(&MannWhitneyU),
+            TestMethod::KolmogorovSmirnov => Some(&KolmogorovSmirnov),
+            TestMethod::Dunnett
+            | TestMethod::Permutation
+            | TestMethod::PairedT
+            | TestMethod::WilcoxonSignedRank => None,
+        }
+    }
+
+    /// Whether the test assumes normally distributed groups.
+    pub fn assumes_normality(&self) -> bool {
+        matches!(
+            self,
+            TestMethod::Student | TestMethod::Welch | TestMethod::Dunnett | TestMethod::PairedT
+        )
+    }
+
+    /// Whether the test compares values matched by pair ID.
+    pub fn is_paired(&self) -> bool {
+        matches!(self, TestMethod::PairedT | TestMethod::WilcoxonSignedRank)
+    }
+
+    /// Human readable name for the control panel and the stats tables.
+    pub fn name(&self) -> &'static str {
+        match self {
+            TestMethod::Dunnett => "Dunnett",
+            TestMethod::Permutation => "Permutation",
+            TestMethod::PairedT => "Paired t",
+            TestMethod::WilcoxonSignedRank => "Wilcoxon signed-rank",
+            _ => self.pairwise().map_or("", |test| test.name()),
+        }
+    }
+}
+
+/// Count, mean and sample variance of a slice.
+pub fn moments(values: &[f64]) -> Moments {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let variance = if n > 1.0 {
+        values.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0)
+    } else {
+        0.0
+    };
+    (n, mean, variance)
+}
+
+/// Ranks of the values in input order, averaged over ties, and the tie
+/// correction term Σ(t³ - t) over the tie group sizes t.
+pub fn average_ranks(values: &[f64]) -> (Vec<f64>, f64) {
+    let n = values.len();
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&a, &b| {
+        values[a]
+            .partial_cmp(&values[b])
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    let mut ranks = vec![0.0; n];
+    let mut tie_term = 0.0;
+    let mut start = 0;
+    while start < n {
+        let mut end = start + 1;
+        while end < n && values[order[end]] == values[order[start]] {
+            end += 1;
+        }
+        let rank = (start + end + 1) as f64 / 2.0;
+        for &i in &order[start..end] {
+            ranks[i] = rank;
+        }
+        let ties = (end - start) as f64;
+        tie_term += ties.powi(3) - ties;
+        start = end;
+    }
+    (ranks, tie_term)
+}
+
+/// Welch–Satterthwaite degrees of freedom of the difference in means.
+pub fn welch_df(group: Moments, control: Moments) -> f64 {
+    let (n1, _, var1) = group;
+    let (n2, _, var2) = control;
+    let df_num = (var1 / n1 + var2 / n2).powi(2);
+    let df_denom = (var1 / n1).powi(2) / (n1 - 1.0) + (var2 / n2).powi(2) / (n2 - 1.0);
+    df_num / df_denom
+}
+
+/// Confidence interval of the group mean minus the control mean at `level`
+/// (e.g. 0.95), with Welch's standard error and degrees of freedom.
+pub fn mean_difference_ci(group: Moments, control: Moments, level: f64) -> Option<(f64, f64)> {
+    let (n1, mean1, var1) = group;
+    let (n2, mean2, var2) = control;
+    if n1 < 2.0 || n2 < 2.0 {
+        return None;
+    }
+
+    let diff = mean1 - mean2;
+    let se = (var1 / n1 + var2 / n2).sqrt();
+    if se == 0.0 {
+        return Some((diff, diff));
+    }
+
+    let dist = StudentsT::new(0.0, 1.0, welch_df(group, control)).ok()?;
+    let margin = dist.inverse_cdf((1.0 + level) / 2.0) * se;
+    Some((diff - margin, diff + margin))
+}
+
+/// Two-tailed p-value of a t statistic.
+pub fn t_p_value(t: f64, df: f64) -> f64 {
+    match StudentsT::new(0.0, 1.0, df) {
+        Ok(dist) => 2.0 * (1.0 - dist.cdf(t.abs())),
+        Err(_) => f64::NAN,
+    }
+}
+
+impl TwoSampleTest for StudentT {
+    fn name(&self) -> &'static str {
+        "Student's t"
+    }
+
+    fn p_value(&self, group: &[f64], control: &[f64]) -> f64 {
+        self.p_value_from_moments(moments(group), moments(control))
+            .unwrap_or(f64::NAN)
+    }
+
+    fn p_value_from_moments(&self, group: Moments, control: Moments) -> Option<f64> {
+        let (n1, mean1, var1) = group;
+        let (n2, mean2, var2) = control;
+
+        if n1 < 2.0 || n2 < 2.0 {
+            return Some(f64::NAN);
+        }
+
+        let df = n1 + n2 - 2.0;
+        let pooled = ((n1 - 1.0) * var1 + (n2 - 1.0) * var2) / df;
+        let se = (pooled * (1.0 / n1 + 1.0 / n2)).sqrt();
+        if se == 0.0 {
+            return Some(1.0); // No variance difference
+        }
+
+        Some(t_p_value((mean1 - mean2) / se, df))
+    }
+}
+
+impl TwoSampleTest for WelchT {
+    fn name(&self) -> &'static str {
+        "Welch's t"
+    }
+
+    fn p_value(&self, group: &[f64], control: &[f64]) -> f64 {
+        self.p_value_from_moments(moments(group), moments(control))
+            .unwrap_or(f64::NAN)
+    }
+
+    fn p_value_from_moments(&self, group: Moments, control: Moments) -> Option<f64> {
+        let (n1, mean1, var1) = group;
+        let (n2, mean2, var2) = control;
+
+        if n1 < 2.0 || n2 < 2.0 {
+            return Some(f64::NAN);
+        }
+
+        let se = (var1 / n1 + var2 / n2).sqrt();
+        if se == 0.0 {
+            return Some(1.0); // No variance difference
+        }
+
+        Some(t_p_value((mean1 - mean2) / se, welch_df(group, control)))
+    }
+}
+
+impl TwoSampleTest for MannWhitneyU {
+    fn name(&self) -> &'static str {
+        "Mann–Whitney U"
+    }
+
+    fn p_value(&self, group: &[f64], control: &[f64]) -> f64 {
+        let n1 = group.len();
+        let n2 = control.len();
+        if n1 == 0 || n2 == 0 {
+            return f64::NAN;
+        }
+
+        let pooled: Vec<f64> = group.iter().chain(control).copied().collect();
+        let (ranks, tie_term) = average_ranks(&pooled);
+        let rank_sum: f64 = ranks[..n1].iter().sum();
+
+        let n = pooled.len();
+        let (n1, n2, n) = (n1 as f64, n2 as f64, n as f64);
+        let u = rank_sum - n1 * (n1 + 1.0) / 2.0;
+        let mean_u = n1 * n2 / 2.0;
+        let var_u = n1 * n2 / 12.0 * ((n + 1.0) - tie_term / (n * (n - 1.0)));
+        if var_u <= 0.0 {
+            return 1.0; // All values tied
+        }
+
+        let z = ((u - mean_u).abs() - 0.5).max(0.0) / var_u.sqrt();
+        let normal = Normal::new(0.0, 1.0).expect("standard normal");
+        (2.0 * (1.0 - normal.cdf(z))).min(1.0)
+    }
+}
+
+impl TwoSampleTest for KolmogorovSmirnov {
+    fn name(&self) -> &'static str {
+        "Kolmogorov–Smirnov"
+    }
+
+    fn p_value(&self, group: &[f64], control: &[f64]) -> f64 {
+        if group.is_empty() || control.is_empty() {
+            return f64::NAN;
+        }
+
+        let sorted = |values: &[f64]| {
+            let mut sorted = values.to_vec();
+            sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+            sorted
+        };
+        let (a, b) = (sorted(group), sorted(control));
+        let (n1, n2) = (a.len() as f64, b.len() as f64);
+
+        // Largest distance between the empirical CDFs, stepping past ties together
+        let (mut i, mut j) = (0, 0);
+        let mut d: f64 = 0.0;
+        while i < a.len() && j < b.len() {
+            let x = a[i].min(b[j]);
+            while i < a.len() && a[i] <= x {
+                i += 1;
+            }
+            while j < b.len() && b[j] <= x {
+                j += 1;
+            }
+            d = d.max((i as f64 / n1 - j as f64 / n2).abs());
+        }
+
+        // Kolmogorov distribution with Stephens' small-sample correction
+        let en = (n1 * n2 / (n1 + n2)).sqrt();
+        let lambda = (en + 0.12 + 0.11 / en) * d;
+        Self::kolmogorov_q(lambda)
+    }
+}
+
+impl KolmogorovSmirnov {
+    /// Survival function of the Kolmogorov distribution.
+    fn kolmogorov_q(lambda: f64) -> f64 {
+        if lambda < 0.2 {
+            return 1.0;
+        }
+
+        let mut sum = 0.0;
+        let mut sign = 1.0;
+        for j in 1..=100 {
+            let j = j as f64;
+            let term = sign * (-2.0 * j * j * lambda * lambda).exp();
+            sum += term;
+            if term.abs() < 1e-12 {
+                break;
+            }
+            sign = -sign;
+        }
+        (2.0 * sum).clamp(0.0, 1.0)
+    }
+}
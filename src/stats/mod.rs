@@ -1,5 +1,31 @@
 //! Statistics module - Statistical calculations
 
 mod calculator;
+mod capability;
+mod correction;
+mod dunnett;
+mod effect_size;
+mod equivalence;
+mod hypothesis;
+mod normality;
+mod omnibus;
+mod outliers;
+mod paired;
+mod resampling;
+mod robust;
+mod rules;
+mod streaming;
+mod variance;
 
-pub use calculator::{DataTypeStats, StatsCalculator};
+pub use calculator::{DataTypeStats, GroupedValues, StatColumn, StatsCalculator};
+pub use capability::{SpecLimits, SpecTable};
+pub use correction::CorrectionMethod;
+pub use effect_size::EffectSizeMethod;
+pub use equivalence::{EquivalenceMargins, MarginUnit, Outcome};
+pub use hypothesis::{TestMethod, DEFAULT_CI_LEVEL};
+pub use omnibus::OmnibusMethod;
+pub use outliers::{OutlierFilter, OutlierRule};
+pub use resampling::Resampler;
+pub use rules::{MismatchRule, MismatchRules, DEFAULT_ALPHA};
+pub use streaming::StreamingStats;
+pub use variance::VarianceTest;
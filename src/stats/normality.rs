@@ -0,0 +1,315 @@
+//! Normality Module
+//! Shapiro–Wilk and Anderson–Darling tests of each group, quantifying what the
+//! normal quantile plot shows.
+
+use statrs::distribution::{ContinuousCDF, Normal};
+
+/// Largest group the Shapiro–Wilk approximation is valid for.
+pub const SHAPIRO_WILK_MAX_N: usize = 5000;
+
+/// Smallest group either test runs on.
+const MIN_N: usize = 3;
+
+/// Statistic and p-value of one normality test.
+#[derive(Debug, Clone, Copy)]
+pub struct NormalityTest {
+    pub statistic: f64,
+    pub p_value: f64,
+}
+
+/// Normality tests of one group; `None` when the group is too small, constant or,
+/// for Shapiro–Wilk, too large.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Normality {
+    /// W statistic (Royston's approximation)
+    pub shapiro_wilk: Option<NormalityTest>,
+    /// A² statistic with the small-sample adjustment
+    pub anderson_darling: Option<NormalityTest>,
+}
+
+/// Evaluate c[0] + c[1] x + c[2] x² + ...
+fn poly(coefficients: &[f64], x: f64) -> f64 {
+    coefficients.iter().rev().fold(0.0, |acc, c| acc * x + c)
+}
+
+impl Normality {
+    /// Run both tests on sorted values.
+    pub fn test(sorted: &[f64]) -> Self {
+        let n = sorted.len();
+        if n < MIN_N || sorted[n - 1] - sorted[0] <= 0.0 {
+            return Self::default();
+        }
+        let normal = Normal::new(0.0, 1.0).expect("standard normal");
+        Self {
+            shapiro_wilk: (n <= SHAPIRO_WILK_MAX_N)
+                .then(|| Self::shapiro_wilk(sorted, &normal))
+                .flatten(),
+            anderson_darling: Self::anderson_darling(sorted, &normal),
+        }
+    }
+
+    /// P-value of Shapiro–Wilk where it applies, Anderson–Darling otherwise.
+    pub fn p_value(&self) -> Option<f64> {
+        self.shapiro_wilk
+            .or(self.anderson_darling)
+            .map(|test| test.p_value)
+    }
+
+    /// Whether normality is rejected at `alpha`.
+    pub fn is_rejected(&self, alpha: f64) -> bool {
+        self.p_value().is_some_and(|p| p <= alpha)
+    }
+
+    /// Royston's (1995) algorithm AS R94.
+    fn shapiro_wilk(sorted: &[f64], normal: &Normal) -> Option<NormalityTest> {
+        let n = sorted.len();
+        let nf = n as f64;
+
+        // Coefficients from the expected normal order statistics
+        let m: Vec<f64> = (1..=n)
+            .map(|i| normal.inverse_cdf((i as f64 - 0.375) / (nf + 0.25)))
+            .collect();
+        let mut a = vec![0.0; n];
+        if n == 3 {
+            a[2] = 0.5f64.sqrt();
+        } else {
+            let summ2: f64 = m.iter().map(|m| m * m).sum();
+            let ssumm2 = summ2.sqrt();
+            let u = 1.0 / nf.sqrt();
+            let an = m[n - 1] / ssumm2
+                + poly(
+                    &[0.0, 0.221157, -0.147981, -2.071190, 4.434685, -2.706056],
+                    u,
+                );
+            let (fixed, phi) = if n > 5 {
+                let an1 = m[n - 2] / ssumm2
+                    + poly(
+                        &[0.0, 0.042981, -0.293762, -1.752461, 5.682633, -3.582633],
+                        u,
+                    );
+                a[n - 2] = an1;
+                let phi = (summ2 - 2.0 * m[n - 1].powi(2) - 2.0 * m[n - 2].powi(2))
+                    / (1.0 - 2.0 * an * an - 2.0 * an1 * an1);
+                (2, phi)
+            } else {
+                (1, (summ2 - 2.0 * m[n - 1].powi(2)) / (1.0 - 2.0 * an * an))
+            };
+            a[n - 1] = an;
+            for i in n / 2..n - fixed {
+                a[i] = m[i] / phi.sqrt();
+            }
+        }
+        // The coefficients are antisymmetric
+        for i in 0..n / 2 {
+            a[i] = -a[n - 1 - i];
+        }
+        if n % 2 == 1 {
+            a[n / 2] = 0.0;
+        }
+
+        let mean = sorted.iter().sum::<f64>() / nf;
+        let ss: f64 = sorted.iter().map(|x| (x - mean).powi(2)).sum();
+        let b: f64 = a.iter().zip(sorted).map(|(a, x)| a * x).sum();
+        let w = (b * b / ss).min(1.0);
+
+        let p_value = if n == 3 {
+            let pi6 = 6.0 / std::f64::consts::PI;
+            (pi6 * (w.sqrt().asin() - 0.75f64.sqrt().asin())).clamp(0.0, 1.0)
+        } else {
+            let y = (1.0 - w).ln();
+            let (z, mean, sd) = if n <= 11 {
+                let gamma = poly(&[-2.273, 0.459], nf);
+                if y >= gamma {
+                    return Some(NormalityTest {
+                        statistic: w,
+                        p_value: 0.0,
+                    });
+                }
+                (
+                    -(gamma - y).ln(),
+                    poly(&[0.544, -0.39978, 0.025054, -6.714e-4], nf),
+                    poly(&[1.3822, -0.77857, 0.062767, -0.0020322], nf).exp(),
+                )
+            } else {
+                let ln_n = nf.ln();
+                (
+                    y,
+                    poly(&[-1.5861, -0.31082, -0.083751, 0.0038915], ln_n),
+                    poly(&[-0.4803, -0.082676, 0.0030302], ln_n).exp(),
+                )
+            };
+            1.0 - normal.cdf((z - mean) / sd)
+        };
+
+        Some(NormalityTest {
+            statistic: w,
+            p_value,
+        })
+    }
+
+    /// Anderson–Darling with mean and variance estimated from the data, p-values
+    /// from D'Agostino and Stephens (1986).
+    fn anderson_darling(sorted: &[f64], normal: &Normal) -> Option<NormalityTest> {
+        let n = sorted.len();
+        let nf = n as f64;
+        let mean = sorted.iter().sum::<f64>() / nf;
+        let sd = (sorted.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (nf - 1.0)).sqrt();
+        if sd <= 0.0 {
+            return None;
+        }
+
+        // ln Φ(z) and ln(1 - Φ(z)) evaluated on the lower tail to keep precision
+        let ln_cdf = |z: f64| normal.cdf(z).max(f64::MIN_POSITIVE).ln();
+        let z: Vec<f64> = sorted.iter().map(|x| (x - mean) / sd).collect();
+        let sum: f64 = (0..n)
+            .map(|i| (2 * i + 1) as f64 * (ln_cdf(z[i]) + ln_cdf(-z[n - 1 - i])))
+            .sum();
+        let a2 = -nf - sum / nf;
+        let adjusted = a2 * (1.0 + 0.75 / nf + 2.25 / (nf * nf));
+
+        let p_value = if adjusted >= 0.6 {
+            (1.2937 - 5.709 * adjusted + 0.0186 * adjusted * adjusted).exp()
+        } else if adjusted >= 0.34 {
+            (0.9177 - 4.279 * adjusted - 1.38 * adjusted * adjusted).exp()
+        } else if adjusted >= 0.2 {
+            1.0 - (-8.318 + 42.796 * adjusted - 59.938 * adjusted * adjusted).exp()
+        } else {
+            1.0 - (-13.436 + 101.14 * adjusted - 223.73 * adjusted * adjusted).exp()
+        };
+
+        Some(NormalityTest {
+            statistic: adjusted,
+            p_value: p_value.clamp(0.0, 1.0),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `mtcars$mpg` from R's datasets; seven values appear twice.
+    const MPG: [f64; 32] = [
+        21.0, 21.0, 22.8, 21.4, 18.7, 18.1, 14.3, 24.4, 22.8, 19.2, 17.8, 16.4, 17.3, 15.2, 10.4,
+        10.4, 14.7, 32.4, 30.4, 33.9, 21.5, 15.5, 15.2, 13.3, 19.2, 27.3, 26.0, 30.4, 15.8, 19.7,
+        15.0, 21.4,
+    ];
+
+    /// `mtcars$hp`.
+    const HP: [f64; 32] = [
+        110.0, 110.0, 93.0, 110.0, 175.0, 105.0, 245.0, 62.0, 95.0, 123.0, 123.0, 180.0, 180.0,
+        180.0, 205.0, 215.0, 230.0, 66.0, 52.0, 65.0, 97.0, 150.0, 150.0, 245.0, 175.0, 66.0, 91.0,
+        113.0, 264.0, 175.0, 335.0, 109.0,
+    ];
+
+    /// `mtcars$wt`.
+    const WT: [f64; 32] = [
+        2.620, 2.875, 2.320, 3.215, 3.440, 3.460, 3.570, 3.190, 3.150, 3.440, 3.440, 4.070, 3.730,
+        3.780, 5.250, 5.424, 5.345, 2.200, 1.615, 1.835, 2.465, 3.520, 3.435, 3.840, 3.845, 1.935,
+        2.140, 1.513, 3.170, 2.770, 3.570, 2.780,
+    ];
+
+    fn normality(values: &[f64]) -> Normality {
+        let mut sorted = values.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        Normality::test(&sorted)
+    }
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() < tolerance,
+            "{actual} is not within {tolerance} of {expected}"
+        );
+    }
+
+    /// nortest reports A² before the small-sample adjustment.
+    fn unadjusted(test: NormalityTest, n: usize) -> f64 {
+        let n = n as f64;
+        test.statistic / (1.0 + 0.75 / n + 2.25 / (n * n))
+    }
+
+    #[test]
+    fn shapiro_wilk_of_three_values_is_exact() {
+        // W = 27/28 and p = 6/π (asin √W - asin √0.75); R: W = 0.96429, p-value = 0.6369
+        let sw = normality(&[1.0, 2.0, 4.0]).shapiro_wilk.unwrap();
+        assert_close(sw.statistic, 27.0 / 28.0, 1e-12);
+        assert_close(sw.p_value, 0.6369, 1e-4);
+    }
+
+    #[test]
+    fn shapiro_wilk_of_a_small_sample_matches_the_original_paper() {
+        // Weights of 11 men from Shapiro and Wilk (1965): W = 0.79, rejected at 1%
+        let men = [
+            148.0, 154.0, 158.0, 160.0, 161.0, 162.0, 166.0, 170.0, 182.0, 195.0, 236.0,
+        ];
+        let sw = normality(&men).shapiro_wilk.unwrap();
+        assert_close(sw.statistic, 0.79, 5e-3);
+        assert!(sw.p_value < 0.01, "p = {}", sw.p_value);
+    }
+
+    #[test]
+    fn shapiro_wilk_matches_r() {
+        // shapiro.test(mtcars$mpg), with tied values
+        let sw = normality(&MPG).shapiro_wilk.unwrap();
+        assert_close(sw.statistic, 0.94756, 1e-5);
+        assert_close(sw.p_value, 0.1229, 1e-4);
+
+        // shapiro.test(mtcars$hp)
+        let sw = normality(&HP).shapiro_wilk.unwrap();
+        assert_close(sw.statistic, 0.93342, 1e-5);
+        assert_close(sw.p_value, 0.04881, 1e-5);
+
+        // shapiro.test(mtcars$wt)
+        let sw = normality(&WT).shapiro_wilk.unwrap();
+        assert_close(sw.statistic, 0.94326, 1e-5);
+        assert_close(sw.p_value, 0.09265, 1e-5);
+    }
+
+    #[test]
+    fn anderson_darling_matches_nortest() {
+        // nortest::ad.test(mtcars$mpg), with tied values
+        let ad = normality(&MPG).anderson_darling.unwrap();
+        assert_close(unadjusted(ad, MPG.len()), 0.57968, 1e-5);
+        assert_close(ad.p_value, 0.1207, 1e-4);
+    }
+
+    #[test]
+    fn large_samples() {
+        // For uniform values W tends to 12 E[Z Φ(Z)]² = 3 / π
+        let uniform: Vec<f64> = (0..SHAPIRO_WILK_MAX_N)
+            .map(|i| i as f64 / (SHAPIRO_WILK_MAX_N - 1) as f64)
+            .collect();
+        let result = normality(&uniform);
+        let sw = result.shapiro_wilk.unwrap();
+        assert_close(sw.statistic, 3.0 / std::f64::consts::PI, 1e-3);
+        assert!(sw.p_value < 1e-10, "p = {}", sw.p_value);
+        assert!(result.anderson_darling.unwrap().p_value < 1e-10);
+
+        // Expected normal order statistics are as normal as a sample gets
+        let normal = Normal::new(0.0, 1.0).unwrap();
+        let scores = |n: usize| -> Vec<f64> {
+            (1..=n)
+                .map(|i| normal.inverse_cdf((i as f64 - 0.375) / (n as f64 + 0.25)))
+                .collect()
+        };
+        let result = normality(&scores(SHAPIRO_WILK_MAX_N - 1));
+        let sw = result.shapiro_wilk.unwrap();
+        assert!(sw.statistic > 0.9999 && sw.p_value > 0.99, "{sw:?}");
+        assert!(result.anderson_darling.unwrap().p_value > 0.99);
+
+        // Above the Shapiro–Wilk limit only Anderson–Darling runs
+        let result = normality(&scores(SHAPIRO_WILK_MAX_N + 1));
+        assert!(result.shapiro_wilk.is_none());
+        assert_eq!(
+            result.p_value(),
+            result.anderson_darling.map(|ad| ad.p_value)
+        );
+    }
+
+    #[test]
+    fn constant_values_are_not_tested() {
+        let result = normality(&[2.0; 10]);
+        assert!(result.shapiro_wilk.is_none() && result.anderson_darling.is_none());
+        assert!(!result.is_rejected(0.05));
+    }
+}
@@ -0,0 +1,181 @@
+//! Omnibus Tests Module
+//! One "is any group different" answer per data type across all groups, before
+//! looking at the comparisons against the control.
+
+use super::hypothesis::{average_ranks, Moments};
+use statrs::distribution::{ChiSquared, ContinuousCDF, FisherSnedecor};
+
+/// Groups needed for an omnibus test; with two, it repeats the pairwise comparison.
+pub const MIN_OMNIBUS_GROUPS: usize = 3;
+
+/// Omnibus test across all groups of a data type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OmnibusMethod {
+    /// One-way ANOVA, assuming equal variances
+    Anova,
+    /// Welch's ANOVA, not assuming equal variances
+    #[default]
+    WelchAnova,
+    /// Kruskal–Wallis H test on ranks
+    KruskalWallis,
+}
+
+/// Outcome of one omnibus test.
+#[derive(Debug, Clone, Copy)]
+pub struct OmnibusResult {
+    pub method: OmnibusMethod,
+    /// F for the ANOVAs, H for Kruskal–Wallis
+    pub statistic: f64,
+    pub p_value: f64,
+}
+
+impl OmnibusResult {
+    /// One-line summary for the card titles, e.g. "Welch ANOVA F = 4.12, p = 0.0071".
+    pub fn label(&self) -> String {
+        let symbol = match self.method {
+            OmnibusMethod::KruskalWallis => "H",
+            _ => "F",
+        };
+        format!(
+            "{} {} = {:.2}, p = {:.4}",
+            self.method.name(),
+            symbol,
+            self.statistic,
+            self.p_value
+        )
+    }
+}
+
+impl OmnibusMethod {
+    pub const ALL: [OmnibusMethod; 3] = [
+        OmnibusMethod::Anova,
+        OmnibusMethod::WelchAnova,
+        OmnibusMethod::KruskalWallis,
+    ];
+
+    /// Human readable name for the control panel and the card titles.
+    pub fn name(&self) -> &'static str {
+        match self {
+            OmnibusMethod::Anova => "ANOVA",
+            OmnibusMethod::WelchAnova => "Welch ANOVA",
+            OmnibusMethod::KruskalWallis => "Kruskal–Wallis",
+        }
+    }
+
+    /// Run the test on the values and moments of every group. The ANOVAs only use
+    /// the moments; Kruskal–Wallis ranks the values.
+    pub fn run(&self, groups: &[&[f64]], moments: &[Moments]) -> Option<OmnibusResult> {
+        let (statistic, p_value) = match self {
+            OmnibusMethod::Anova => Self::anova(moments)?,
+            OmnibusMethod::WelchAnova => Self::welch_anova(moments)?,
+            OmnibusMethod::KruskalWallis => Self::kruskal_wallis(groups)?,
+        };
+        Some(OmnibusResult {
+            method: *self,
+            statistic,
+            p_value,
+        })
+    }
+
+    /// Run every omnibus test, skipping those the data does not allow.
+    pub fn run_all(groups: &[&[f64]], moments: &[Moments]) -> Vec<OmnibusResult> {
+        if groups.len() < MIN_OMNIBUS_GROUPS {
+            return Vec::new();
+        }
+        Self::ALL
+            .iter()
+            .filter_map(|method| method.run(groups, moments))
+            .collect()
+    }
+
+    fn f_p_value(f: f64, df1: f64, df2: f64) -> Option<f64> {
+        let dist = FisherSnedecor::new(df1, df2).ok()?;
+        Some(1.0 - dist.cdf(f))
+    }
+
+    fn anova(moments: &[Moments]) -> Option<(f64, f64)> {
+        let k = moments.len() as f64;
+        let total: f64 = moments.iter().map(|(n, _, _)| n).sum();
+        if total <= k {
+            return None;
+        }
+
+        let grand_mean = moments.iter().map(|(n, mean, _)| n * mean).sum::<f64>() / total;
+        let between: f64 = moments
+            .iter()
+            .map(|(n, mean, _)| n * (mean - grand_mean).powi(2))
+            .sum();
+        let within: f64 = moments.iter().map(|(n, _, var)| (n - 1.0) * var).sum();
+        if within == 0.0 {
+            // No spread within groups: any difference in means is decisive
+            return Some(if between == 0.0 {
+                (0.0, 1.0)
+            } else {
+                (f64::INFINITY, 0.0)
+            });
+        }
+
+        let (df1, df2) = (k - 1.0, total - k);
+        let f = (between / df1) / (within / df2);
+        Some((f, Self::f_p_value(f, df1, df2)?))
+    }
+
+    fn welch_anova(moments: &[Moments]) -> Option<(f64, f64)> {
+        // Every group needs a variance to be weighted by
+        if moments.iter().any(|(n, _, var)| *n < 2.0 || *var <= 0.0) {
+            return None;
+        }
+
+        let k = moments.len() as f64;
+        let weights: Vec<f64> = moments.iter().map(|(n, _, var)| n / var).collect();
+        let weight_sum: f64 = weights.iter().sum();
+        let weighted_mean = weights
+            .iter()
+            .zip(moments)
+            .map(|(w, (_, mean, _))| w * mean)
+            .sum::<f64>()
+            / weight_sum;
+
+        let between = weights
+            .iter()
+            .zip(moments)
+            .map(|(w, (_, mean, _))| w * (mean - weighted_mean).powi(2))
+            .sum::<f64>()
+            / (k - 1.0);
+        let lambda: f64 = weights
+            .iter()
+            .zip(moments)
+            .map(|(w, (n, _, _))| (1.0 - w / weight_sum).powi(2) / (n - 1.0))
+            .sum();
+
+        let f = between / (1.0 + 2.0 * (k - 2.0) / (k * k - 1.0) * lambda);
+        let (df1, df2) = (k - 1.0, (k * k - 1.0) / (3.0 * lambda));
+        Some((f, Self::f_p_value(f, df1, df2)?))
+    }
+
+    fn kruskal_wallis(groups: &[&[f64]]) -> Option<(f64, f64)> {
+        let pooled: Vec<f64> = groups.iter().flat_map(|g| g.iter().copied()).collect();
+        let total = pooled.len() as f64;
+        if groups.iter().any(|g| g.is_empty()) || total < 2.0 {
+            return None;
+        }
+
+        let (ranks, tie_term) = average_ranks(&pooled);
+        let mut offset = 0;
+        let mut rank_term = 0.0;
+        for group in groups {
+            let rank_sum: f64 = ranks[offset..offset + group.len()].iter().sum();
+            rank_term += rank_sum.powi(2) / group.len() as f64;
+            offset += group.len();
+        }
+
+        let tie_correction = 1.0 - tie_term / (total.powi(3) - total);
+        if tie_correction <= 0.0 {
+            return Some((0.0, 1.0)); // All values tied
+        }
+
+        let h = (12.0 / (total * (total + 1.0)) * rank_term - 3.0 * (total + 1.0)) / tie_correction;
+        let dist = ChiSquared::new(groups.len() as f64 - 1.0).ok()?;
+        Some((h, 1.0 - dist.cdf(h)))
+    }
+}
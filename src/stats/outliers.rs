@@ -0,0 +1,241 @@
+//! Outliers Module
+//! Finds outliers within each group with Tukey fences, the modified z-score,
+//! iterated Grubbs tests or Rosner's generalized ESD, so they can be marked in
+//! the plots or left out of the statistics.
+
+use super::calculator::{GroupedValues, StatsCalculator};
+use super::hypothesis;
+use rayon::prelude::*;
+use statrs::distribution::{ContinuousCDF, StudentsT};
+use std::collections::HashMap;
+
+/// Tukey fences lie this many IQRs beyond the quartiles.
+const TUKEY_K: f64 = 1.5;
+
+/// Modified z-scores beyond this are outliers (Iglewicz and Hoaglin).
+const MODIFIED_Z_LIMIT: f64 = 3.5;
+
+/// Upper bound of the generalized ESD as a share of the values (at least one).
+const ESD_MAX_FRACTION: f64 = 0.1;
+
+/// Positions of values within each group, keyed by data type and then group.
+pub type GroupedIndices = HashMap<String, HashMap<String, Vec<usize>>>;
+
+/// Rule deciding which values of a group are outliers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutlierRule {
+    /// Every value is kept and nothing is marked
+    #[default]
+    None,
+    /// Beyond 1.5 × IQR from the quartiles
+    Tukey,
+    /// |0.6745 (x - median) / MAD| above 3.5
+    ModifiedZ,
+    /// Grubbs test of the most extreme value, repeated until it is not significant
+    Grubbs,
+    /// Rosner's generalized extreme studentized deviate test
+    GeneralizedEsd,
+}
+
+impl OutlierRule {
+    pub const ALL: [OutlierRule; 5] = [
+        OutlierRule::None,
+        OutlierRule::Tukey,
+        OutlierRule::ModifiedZ,
+        OutlierRule::Grubbs,
+        OutlierRule::GeneralizedEsd,
+    ];
+
+    /// Human readable name for the control panel.
+    pub fn name(&self) -> &'static str {
+        match self {
+            OutlierRule::None => "None",
+            OutlierRule::Tukey => "Tukey fences",
+            OutlierRule::ModifiedZ => "Modified z-score",
+            OutlierRule::Grubbs => "Grubbs",
+            OutlierRule::GeneralizedEsd => "Generalized ESD",
+        }
+    }
+
+    /// Whether the rule is a significance test run at α.
+    pub fn uses_alpha(&self) -> bool {
+        matches!(self, OutlierRule::Grubbs | OutlierRule::GeneralizedEsd)
+    }
+
+    /// Indices of the outliers among `values`; the tests run at level `alpha`.
+    /// Groups of fewer than three values have none.
+    pub fn detect(&self, values: &[f64], alpha: f64) -> Vec<usize> {
+        if values.len() < 3 {
+            return Vec::new();
+        }
+
+        match self {
+            OutlierRule::None => Vec::new(),
+            OutlierRule::Tukey => {
+                let sorted = sorted(values);
+                let q1 = StatsCalculator::percentile(&sorted, 25.0);
+                let q3 = StatsCalculator::percentile(&sorted, 75.0);
+                let (low, high) = (q1 - TUKEY_K * (q3 - q1), q3 + TUKEY_K * (q3 - q1));
+                indices_where(values, |x| x < low || x > high)
+            }
+            OutlierRule::ModifiedZ => {
+                let median = StatsCalculator::percentile(&sorted(values), 50.0);
+                let deviations: Vec<f64> = values.iter().map(|x| (x - median).abs()).collect();
+                let mad = StatsCalculator::percentile(&sorted(&deviations), 50.0);
+                if mad == 0.0 {
+                    return Vec::new();
+                }
+                indices_where(values, |x| {
+                    (0.6745 * (x - median) / mad).abs() > MODIFIED_Z_LIMIT
+                })
+            }
+            OutlierRule::Grubbs => extreme_deviates(values, values.len())
+                .into_iter()
+                .take_while(|&(_, g, left)| g > critical_value(left, alpha))
+                .map(|(index, _, _)| index)
+                .collect(),
+            OutlierRule::GeneralizedEsd => {
+                let max_outliers =
+                    ((values.len() as f64 * ESD_MAX_FRACTION).floor() as usize).max(1);
+                let steps = extreme_deviates(values, max_outliers);
+                let found = steps
+                    .iter()
+                    .rposition(|&(_, r, left)| r > critical_value(left, alpha))
+                    .map_or(0, |last| last + 1);
+                steps[..found].iter().map(|&(index, _, _)| index).collect()
+            }
+        }
+    }
+}
+
+/// Outlier rule and whether outliers are left out of the statistics.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct OutlierFilter {
+    pub rule: OutlierRule,
+    /// Leave outliers out of the statistics rather than only marking them
+    pub exclude: bool,
+}
+
+impl OutlierFilter {
+    /// Whether outliers are looked for at all.
+    pub fn is_active(&self) -> bool {
+        self.rule != OutlierRule::None
+    }
+
+    /// Whether outliers are left out of the statistics.
+    pub fn excludes(&self) -> bool {
+        self.is_active() && self.exclude
+    }
+
+    /// Outlier values of each data type and group, with their ascending positions
+    /// among the group's values. When excluding, they are also removed from
+    /// `grouped`.
+    pub fn split(
+        &self,
+        grouped: &mut GroupedValues,
+        alpha: f64,
+    ) -> (GroupedValues, GroupedIndices) {
+        if !self.is_active() {
+            return (GroupedValues::new(), GroupedIndices::new());
+        }
+
+        grouped
+            .par_iter_mut()
+            .map(|(data_type, groups)| {
+                let (outliers, positions) = groups
+                    .iter_mut()
+                    .map(|(group, values)| {
+                        let mut indices = self.rule.detect(values, alpha);
+                        let outliers = indices.iter().map(|&i| values[i]).collect();
+                        indices.sort_unstable();
+                        if self.exclude {
+                            for &i in indices.iter().rev() {
+                                values.remove(i);
+                            }
+                        }
+                        ((group.clone(), outliers), (group.clone(), indices))
+                    })
+                    .unzip();
+                (
+                    (data_type.clone(), outliers),
+                    (data_type.clone(), positions),
+                )
+            })
+            .unzip()
+    }
+
+    /// Caption naming the rule, the α of its tests and what happened to the outliers.
+    pub fn label(&self, count: usize, alpha: f64) -> String {
+        let rule = match self.rule {
+            OutlierRule::Tukey => format!("Tukey fences ({TUKEY_K} × IQR)"),
+            OutlierRule::ModifiedZ => format!("modified z-score (|z| > {MODIFIED_Z_LIMIT})"),
+            rule if rule.uses_alpha() => format!("{} (α = {alpha})", rule.name()),
+            rule => rule.name().to_string(),
+        };
+        let action = if self.exclude {
+            "excluded from the statistics"
+        } else {
+            "marked, included in the statistics"
+        };
+        format!("Outliers by {rule}: {count} {action}")
+    }
+}
+
+fn sorted(values: &[f64]) -> Vec<f64> {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    sorted
+}
+
+fn indices_where(values: &[f64], outlier: impl Fn(f64) -> bool) -> Vec<usize> {
+    (0..values.len()).filter(|&i| outlier(values[i])).collect()
+}
+
+/// Remove the value furthest from the mean of those left, up to `max` times
+/// while three or more are left. Each step gives the index removed, its distance
+/// from the mean in standard deviations and how many values were left. That value
+/// is always the lowest or highest left, so one sort and a running mean and sum of
+/// squares keep each step constant time. Stops early once the rest are all equal.
+fn extreme_deviates(values: &[f64], max: usize) -> Vec<(usize, f64, usize)> {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+
+    let (count, mut mean, variance) = hypothesis::moments(values);
+    let mut sum_sq = variance * (count - 1.0);
+    let (mut low, mut high) = (0, order.len());
+    let mut steps = Vec::new();
+    while steps.len() < max && high - low >= 3 {
+        let left = high - low;
+        let variance = sum_sq / (left - 1) as f64;
+        if variance <= 0.0 {
+            break;
+        }
+
+        let (low_value, high_value) = (values[order[low]], values[order[high - 1]]);
+        let index = if mean - low_value > high_value - mean {
+            low += 1;
+            order[low - 1]
+        } else {
+            high -= 1;
+            order[high]
+        };
+
+        // Take the value out of the running mean and sum of squares
+        let x = values[index];
+        steps.push((index, (x - mean).abs() / variance.sqrt(), left));
+        let next_mean = (mean * left as f64 - x) / (left - 1) as f64;
+        sum_sq = (sum_sq - (x - mean) * (x - next_mean)).max(0.0);
+        mean = next_mean;
+    }
+    steps
+}
+
+/// Two-sided Grubbs critical value for `n` values, which is also the generalized
+/// ESD critical value with `n` values left.
+fn critical_value(n: usize, alpha: f64) -> f64 {
+    let n = n as f64;
+    let t = StudentsT::new(0.0, 1.0, n - 2.0)
+        .map(|dist| dist.inverse_cdf(1.0 - alpha / (2.0 * n)))
+        .unwrap_or(f64::INFINITY);
+    (n - 1.0) * t / (n * (n - 2.0 + t * t)).sqrt()
+}
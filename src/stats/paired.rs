@@ -0,0 +1,121 @@
+//! Paired Module
+//! Paired t and Wilcoxon signed-rank tests of a group against the control on the
+//! differences of values matched by ID, e.g. the same wafer or patient measured
+//! under both conditions.
+
+use super::hypothesis::{self, Moments, TestMethod};
+use super::normality::Normality;
+use crate::data::PairedValues;
+use statrs::distribution::{ContinuousCDF, Normal};
+
+/// Unmatched IDs listed in a caption before the rest are only counted.
+const MAX_LISTED_IDS: usize = 5;
+
+/// Paired comparison of one group with the control.
+#[derive(Debug, Clone)]
+pub struct PairedStats {
+    /// Number of matched IDs
+    pub pairs: usize,
+    /// Mean of the group minus control differences
+    pub mean_diff: f64,
+    /// Sample variance of the differences
+    pub variance_diff: f64,
+    /// Normality of the differences, which the paired t-test assumes
+    pub normality: Normality,
+    /// Paired t-test p-value
+    pub t_p: Option<f64>,
+    /// Wilcoxon signed-rank p-value
+    pub wilcoxon_p: Option<f64>,
+    /// IDs found in only one of the group and the control
+    pub unmatched: Vec<String>,
+}
+
+impl PairedStats {
+    /// Run both paired tests on the differences of a group.
+    pub fn compute(paired: &PairedValues) -> Self {
+        let differences = &paired.differences;
+        let (_, mean_diff, variance_diff) = hypothesis::moments(differences);
+        let mut sorted = differences.clone();
+        sorted.sort_by(f64::total_cmp);
+
+        Self {
+            pairs: differences.len(),
+            mean_diff,
+            variance_diff,
+            normality: Normality::test(&sorted),
+            t_p: paired_t_p_value(differences),
+            wilcoxon_p: wilcoxon_signed_rank_p_value(differences),
+            unmatched: paired.unmatched.clone(),
+        }
+    }
+
+    /// P-value of a paired test; `None` for unpaired tests.
+    pub fn p_value(&self, test: TestMethod) -> Option<f64> {
+        match test {
+            TestMethod::PairedT => self.t_p,
+            TestMethod::WilcoxonSignedRank => self.wilcoxon_p,
+            _ => None,
+        }
+    }
+
+    /// Moments of the differences and of a constant zero with as many values.
+    /// Two-sample formulas with Welch's standard error and degrees of freedom
+    /// reduce to their paired forms on these, so the confidence intervals and
+    /// the TOST reuse them.
+    pub fn as_two_sample(&self) -> (Moments, Moments) {
+        let n = self.pairs as f64;
+        ((n, self.mean_diff, self.variance_diff), (n, 0.0, 0.0))
+    }
+
+    /// Unmatched IDs, the first few listed and the rest counted.
+    pub fn unmatched_label(&self) -> String {
+        let listed = self.unmatched[..self.unmatched.len().min(MAX_LISTED_IDS)].join(", ");
+        match self.unmatched.len().saturating_sub(MAX_LISTED_IDS) {
+            0 => listed,
+            rest => format!("{listed} (+{rest})"),
+        }
+    }
+}
+
+/// Two-tailed paired t-test p-value of the differences; `None` below two pairs.
+pub fn paired_t_p_value(differences: &[f64]) -> Option<f64> {
+    let (n, mean, variance) = hypothesis::moments(differences);
+    if n < 2.0 {
+        return None;
+    }
+
+    let se = (variance / n).sqrt();
+    if se == 0.0 {
+        return Some(if mean == 0.0 { 1.0 } else { 0.0 });
+    }
+    Some(hypothesis::t_p_value(mean / se, n - 1.0))
+}
+
+/// Wilcoxon signed-rank p-value of the differences, normal approximation with tie
+/// and continuity correction; zero differences are dropped. `None` without pairs.
+pub fn wilcoxon_signed_rank_p_value(differences: &[f64]) -> Option<f64> {
+    if differences.is_empty() {
+        return None;
+    }
+
+    let nonzero: Vec<f64> = differences.iter().copied().filter(|d| *d != 0.0).collect();
+    let magnitudes: Vec<f64> = nonzero.iter().map(|d| d.abs()).collect();
+    let (ranks, tie_term) = hypothesis::average_ranks(&magnitudes);
+    let w_plus: f64 = nonzero
+        .iter()
+        .zip(&ranks)
+        .filter(|(d, _)| **d > 0.0)
+        .map(|(_, rank)| rank)
+        .sum();
+
+    let n = nonzero.len() as f64;
+    let mean_w = n * (n + 1.0) / 4.0;
+    let var_w = n * (n + 1.0) * (2.0 * n + 1.0) / 24.0 - tie_term / 48.0;
+    if var_w <= 0.0 {
+        return Some(1.0); // Every difference zero
+    }
+
+    let z = ((w_plus - mean_w).abs() - 0.5).max(0.0) / var_w.sqrt();
+    let normal = Normal::new(0.0, 1.0).expect("standard normal");
+    Some((2.0 * (1.0 - normal.cdf(z))).min(1.0))
+}
@@ -0,0 +1,410 @@
+//! Resampling Module
+//! BCa bootstrap confidence intervals of the mean and median differences and
+//! permutation-test p-values, for groups too small or too skewed for the
+//! parametric results. Each (data_type, group) comparison gets its own RNG seeded
+//! from the run seed and its names, so results do not depend on thread scheduling.
+
+use super::calculator::DataTypeStats;
+use super::hypothesis::TestMethod;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use rayon::prelude::*;
+use statrs::distribution::{ContinuousCDF, Normal};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Default number of bootstrap resamples and permutations.
+pub const DEFAULT_ITERATIONS: usize = 2000;
+
+/// Default run seed.
+pub const DEFAULT_SEED: u64 = 42;
+
+/// BCa bootstrap intervals of one group against the control.
+#[derive(Debug, Clone, Copy)]
+pub struct BootstrapCi {
+    pub level: f64,
+    /// Interval of the mean minus the control mean
+    pub mean_diff: Option<(f64, f64)>,
+    /// Interval of the median minus the control median
+    pub median_diff: Option<(f64, f64)>,
+}
+
+/// What to resample and how often.
+#[derive(Debug, Clone, Copy)]
+pub struct Resampler {
+    pub iterations: usize,
+    pub seed: u64,
+    /// Compute BCa bootstrap intervals
+    pub bootstrap: bool,
+}
+
+impl Default for Resampler {
+    fn default() -> Self {
+        Self {
+            iterations: DEFAULT_ITERATIONS,
+            seed: DEFAULT_SEED,
+            bootstrap: false,
+        }
+    }
+}
+
+/// Summary statistic whose group-minus-control difference is bootstrapped.
+#[derive(Clone, Copy)]
+enum Statistic {
+    Mean,
+    Median,
+}
+
+impl Statistic {
+    fn of(&self, values: &mut [f64]) -> f64 {
+        match self {
+            Statistic::Mean => values.iter().sum::<f64>() / values.len() as f64,
+            Statistic::Median => {
+                let n = values.len();
+                let (_, upper, _) = values.select_nth_unstable_by(n / 2, f64::total_cmp);
+                let upper = *upper;
+                if n % 2 == 1 {
+                    upper
+                } else {
+                    let lower = values[..n / 2]
+                        .iter()
+                        .copied()
+                        .fold(f64::NEG_INFINITY, f64::max);
+                    (lower + upper) / 2.0
+                }
+            }
+        }
+    }
+
+    /// The statistic of every leave-one-out subsample, from the sorted values.
+    fn jackknife(&self, sorted: &[f64]) -> Vec<f64> {
+        let n = sorted.len();
+        match self {
+            Statistic::Mean => {
+                let sum: f64 = sorted.iter().sum();
+                sorted.iter().map(|x| (sum - x) / (n - 1) as f64).collect()
+            }
+            Statistic::Median => (0..n)
+                .map(|k| {
+                    // Element j of the sorted values without position k
+                    let at = |j: usize| if j < k { sorted[j] } else { sorted[j + 1] };
+                    let m = n - 1;
+                    if m % 2 == 1 {
+                        at(m / 2)
+                    } else {
+                        (at(m / 2 - 1) + at(m / 2)) / 2.0
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Stable 64-bit FNV-1a hash, to derive per comparison seeds.
+fn fnv1a(parts: &[&str]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for part in parts {
+        for byte in part.bytes().chain(std::iter::once(0)) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    hash
+}
+
+impl Resampler {
+    /// Whether a run with `test` has anything to resample. The bootstrap resamples
+    /// the groups independently, so paired tests skip it.
+    pub fn is_needed(&self, test: TestMethod) -> bool {
+        (self.bootstrap && !test.is_paired()) || test == TestMethod::Permutation
+    }
+
+    /// Resample every non-control group of every data type in parallel. Fills the
+    /// bootstrap intervals when enabled, and the p-values of data types tested with
+    /// `TestMethod::Permutation`. `values` returns the values of a (data_type, group);
+    /// `progress` is called with (done, total) after each comparison.
+    pub fn run<'a>(
+        &self,
+        stats: &mut HashMap<String, DataTypeStats>,
+        values: impl Fn(&str, &str) -> Option<&'a [f64]> + Sync,
+        progress: impl Fn(usize, usize) + Sync,
+    ) {
+        let total: usize = stats
+            .values()
+            .map(|stat| stat.group_stats.len().saturating_sub(1))
+            .sum();
+        let done = AtomicUsize::new(0);
+
+        stats.par_iter_mut().for_each(|(data_type, stat)| {
+            let control_group = stat.control_group.clone();
+            let control = values(data_type, &control_group).unwrap_or_default();
+            let level = stat.ci_level;
+            let group_stats = stat.group_stats.par_iter_mut();
+
+            group_stats
+                .filter(|(group, _)| **group != control_group)
+                .for_each(|(group, gs)| {
+                    let group_values = values(data_type, group).unwrap_or_default();
+                    let mut rng = ChaCha8Rng::seed_from_u64(self.seed ^ fnv1a(&[data_type, group]));
+
+                    if self.bootstrap {
+                        gs.bootstrap = Some(BootstrapCi {
+                            level,
+                            mean_diff: self.bca(
+                                group_values,
+                                control,
+                                Statistic::Mean,
+                                level,
+                                &mut rng,
+                            ),
+                            median_diff: self.bca(
+                                group_values,
+                                control,
+                                Statistic::Median,
+                                level,
+                                &mut rng,
+                            ),
+                        });
+                    }
+                    if gs.test == Some(TestMethod::Permutation) {
+                        gs.p_value = self.permutation_p(group_values, control, &mut rng);
+                    }
+
+                    let finished = done.fetch_add(1, Ordering::Relaxed) + 1;
+                    progress(finished, total);
+                });
+        });
+    }
+
+    /// Bias-corrected and accelerated bootstrap interval of
+    /// statistic(group) - statistic(control), resampling both independently.
+    fn bca(
+        &self,
+        group: &[f64],
+        control: &[f64],
+        statistic: Statistic,
+        level: f64,
+        rng: &mut ChaCha8Rng,
+    ) -> Option<(f64, f64)> {
+        if group.len() < 2 || control.len() < 2 || self.iterations < 2 {
+            return None;
+        }
+
+        let estimate = statistic.of(&mut group.to_vec()) - statistic.of(&mut control.to_vec());
+
+        let mut resample = |values: &[f64], buffer: &mut Vec<f64>| {
+            buffer.clear();
+            buffer.extend((0..values.len()).map(|_| values[rng.gen_range(0..values.len())]));
+            statistic.of(buffer)
+        };
+        let (mut group_buffer, mut control_buffer) = (Vec::new(), Vec::new());
+        let mut replicates: Vec<f64> = (0..self.iterations)
+            .map(|_| resample(group, &mut group_buffer) - resample(control, &mut control_buffer))
+            .collect();
+        replicates.sort_by(f64::total_cmp);
+
+        let normal = Normal::new(0.0, 1.0).ok()?;
+        let b = replicates.len() as f64;
+
+        // Bias correction: share of replicates below the estimate, ties counted half
+        let below = replicates.iter().filter(|r| **r < estimate).count() as f64;
+        let ties = replicates.iter().filter(|r| **r == estimate).count() as f64;
+        let z0 = normal.inverse_cdf(((below + ties / 2.0) / b).clamp(0.5 / b, 1.0 - 0.5 / b));
+
+        // Acceleration from the jackknife over the observations of both samples
+        let sorted = |values: &[f64]| {
+            let mut sorted = values.to_vec();
+            sorted.sort_by(f64::total_cmp);
+            sorted
+        };
+        let (group_sorted, control_sorted) = (sorted(group), sorted(control));
+        let group_stat = statistic.of(&mut group_sorted.clone());
+        let control_stat = statistic.of(&mut control_sorted.clone());
+        let leave_one_out: Vec<f64> = statistic
+            .jackknife(&group_sorted)
+            .into_iter()
+            .map(|g| g - control_stat)
+            .chain(
+                statistic
+                    .jackknife(&control_sorted)
+                    .into_iter()
+                    .map(|c| group_stat - c),
+            )
+            .collect();
+        let jack_mean = leave_one_out.iter().sum::<f64>() / leave_one_out.len() as f64;
+        let (num, den) = leave_one_out.iter().fold((0.0, 0.0), |(num, den), t| {
+            let d = jack_mean - t;
+            (num + d.powi(3), den + d.powi(2))
+        });
+        let acceleration = if den > 0.0 {
+            num / (6.0 * den.powf(1.5))
+        } else {
+            0.0
+        };
+
+        let adjusted = |tail: f64| {
+            let z = normal.inverse_cdf(tail);
+            normal.cdf(z0 + (z0 + z) / (1.0 - acceleration * (z0 + z)))
+        };
+        let quantile = |p: f64| {
+            let rank = (p * (b - 1.0)).clamp(0.0, b - 1.0);
+            let (lower, upper) = (rank.floor() as usize, rank.ceil() as usize);
+            replicates[lower] + (replicates[upper] - replicates[lower]) * (rank - lower as f64)
+        };
+
+        let tail = (1.0 - level) / 2.0;
+        Some((quantile(adjusted(tail)), quantile(adjusted(1.0 - tail))))
+    }
+
+    /// Two-sided permutation p-value of the difference in means, (k + 1) / (B + 1)
+    /// with k the permutations at least as extreme as the observed split.
+    fn permutation_p(&self, group: &[f64], control: &[f64], rng: &mut ChaCha8Rng) -> Option<f64> {
+        if group.is_empty() || control.is_empty() {
+            return None;
+        }
+
+        let mut pooled: Vec<f64> = group.iter().chain(control).copied().collect();
+        let total: f64 = pooled.iter().sum();
+        let (n1, n0) = (group.len(), control.len());
+        let difference = |group_sum: f64| group_sum / n1 as f64 - (total - group_sum) / n0 as f64;
+        let observed = difference(group.iter().sum()).abs();
+        // Guard against rounding making the observed split look less extreme
+        let tolerance = 1e-12 * observed.max(1.0);
+
+        let n = pooled.len();
+        let mut extreme = 0usize;
+        for _ in 0..self.iterations {
+            // Partial Fisher-Yates: the first n1 slots become a random group
+            for i in 0..n1 {
+                let j = rng.gen_range(i..n);
+                pooled.swap(i, j);
+            }
+            let group_sum: f64 = pooled[..n1].iter().sum();
+            if difference(group_sum).abs() >= observed - tolerance {
+                extreme += 1;
+            }
+        }
+
+        Some((extreme + 1) as f64 / (self.iterations + 1) as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::calculator::{GroupedValues, StatsCalculator};
+
+    /// Expected normal order statistics of `n` values, shifted by `shift`.
+    fn normal_scores(n: usize, shift: f64) -> Vec<f64> {
+        let normal = Normal::new(0.0, 1.0).unwrap();
+        (1..=n)
+            .map(|i| shift + normal.inverse_cdf((i as f64 - 0.375) / (n as f64 + 0.25)))
+            .collect()
+    }
+
+    fn grouped() -> GroupedValues {
+        let groups = [
+            ("A", vec![4.1, 5.2, 3.9, 6.3, 5.0, 4.4, 5.8, 12.0]),
+            ("B", vec![5.9, 6.8, 7.4, 5.1, 6.6, 8.2, 6.0]),
+            ("C", vec![4.0, 4.9, 5.5, 3.8, 4.6, 5.1]),
+        ];
+        let by_group = groups
+            .into_iter()
+            .map(|(group, values)| (group.to_string(), values))
+            .collect();
+        HashMap::from([("x".to_string(), by_group)])
+    }
+
+    /// Group name, mean and median difference intervals and permutation p-value.
+    type Resampled = (String, Option<(f64, f64)>, Option<(f64, f64)>, Option<f64>);
+
+    /// Bootstrap intervals and permutation p-values of a run seeded with `seed`.
+    fn run(seed: u64) -> Vec<Resampled> {
+        let grouped = grouped();
+        let mut stats =
+            StatsCalculator::compute_all_stats_parallel(&grouped, "A", TestMethod::Permutation);
+        let resampler = Resampler {
+            iterations: 999,
+            seed,
+            bootstrap: true,
+        };
+        resampler.run(
+            &mut stats,
+            |data_type, group| grouped.get(data_type)?.get(group).map(Vec::as_slice),
+            |_, _| {},
+        );
+
+        let mut results: Vec<_> = stats["x"]
+            .group_stats
+            .values()
+            .filter(|gs| gs.group_name != "A")
+            .map(|gs| {
+                let bootstrap = gs.bootstrap.unwrap();
+                (
+                    gs.group_name.clone(),
+                    bootstrap.mean_diff,
+                    bootstrap.median_diff,
+                    gs.p_value,
+                )
+            })
+            .collect();
+        results.sort_by(|a, b| a.0.cmp(&b.0));
+        results
+    }
+
+    #[test]
+    fn a_fixed_seed_gives_the_same_results() {
+        let first = run(DEFAULT_SEED);
+        assert_eq!(first.len(), 2);
+        assert_eq!(first, run(DEFAULT_SEED));
+        assert_ne!(first, run(DEFAULT_SEED + 1));
+        for (group, mean_diff, median_diff, p_value) in first {
+            let (low, high) = mean_diff.unwrap();
+            assert!(low < high, "{group}: {low} ≥ {high}");
+            let (low, high) = median_diff.unwrap();
+            assert!(low < high, "{group}: {low} ≥ {high}");
+            assert!(
+                p_value.is_some_and(|p| p > 0.0 && p <= 1.0),
+                "{group}: {p_value:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn bca_of_symmetric_samples_matches_the_normal_interval() {
+        // With symmetric samples there is no bias or skew to correct, so the interval
+        // is close to 1 ± 1.96 × the plug-in standard error of the mean difference
+        let (n, shift) = (50, 1.0);
+        let group = normal_scores(n, shift);
+        let control = normal_scores(n, 0.0);
+        let variance = |values: &[f64]| {
+            let mean = values.iter().sum::<f64>() / n as f64;
+            values.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n as f64
+        };
+        let se = ((variance(&group) + variance(&control)) / n as f64).sqrt();
+
+        let resampler = Resampler {
+            iterations: 20_000,
+            ..Resampler::default()
+        };
+        let mut rng = ChaCha8Rng::seed_from_u64(DEFAULT_SEED);
+        let (low, high) = resampler
+            .bca(&group, &control, Statistic::Mean, 0.95, &mut rng)
+            .unwrap();
+        assert!((low - (shift - 1.96 * se)).abs() < 0.02, "low = {low}");
+        assert!((high - (shift + 1.96 * se)).abs() < 0.02, "high = {high}");
+    }
+
+    #[test]
+    fn equal_values_give_finite_bounds() {
+        let resampler = Resampler::default();
+        let mut rng = ChaCha8Rng::seed_from_u64(DEFAULT_SEED);
+        let (group, control) = ([3.0; 6], [3.0; 5]);
+        for statistic in [Statistic::Mean, Statistic::Median] {
+            let bounds = resampler.bca(&group, &control, statistic, 0.95, &mut rng);
+            assert_eq!(bounds, Some((0.0, 0.0)));
+        }
+        let p = resampler.permutation_p(&group, &control, &mut rng);
+        assert_eq!(p, Some(1.0));
+    }
+}
@@ -0,0 +1,162 @@
+//! Robust Module
+//! Location, spread and shape statistics that hold up against outliers: trimmed
+//! and winsorized means, MAD, IQR-based sigma, skewness, kurtosis and the
+//! Hodges–Lehmann shift between two groups.
+
+use super::calculator::StatsCalculator;
+
+/// Share of values cut (trimmed mean) or clamped (winsorized mean) in each tail.
+pub const TRIM_FRACTION: f64 = 0.1;
+
+/// IQR of the standard normal, dividing an IQR into a sigma estimate.
+const NORMAL_IQR: f64 = 1.349;
+
+/// Robust statistics of one group.
+#[derive(Debug, Clone, Copy)]
+pub struct RobustStats {
+    /// Mean without the lowest and highest `TRIM_FRACTION` of the values
+    pub trimmed_mean: f64,
+    /// Mean with the tails clamped to the `TRIM_FRACTION` quantiles
+    pub winsorized_mean: f64,
+    /// Median absolute deviation from the median
+    pub mad: f64,
+    /// Interquartile range over 1.349
+    pub iqr_sigma: f64,
+    /// Adjusted Fisher–Pearson skewness (G1); needs three values
+    pub skewness: Option<f64>,
+    /// Excess kurtosis (G2); needs four values
+    pub kurtosis: Option<f64>,
+}
+
+impl Default for RobustStats {
+    fn default() -> Self {
+        Self {
+            trimmed_mean: f64::NAN,
+            winsorized_mean: f64::NAN,
+            mad: f64::NAN,
+            iqr_sigma: f64::NAN,
+            skewness: None,
+            kurtosis: None,
+        }
+    }
+}
+
+impl RobustStats {
+    /// Robust statistics of values sorted in ascending order.
+    pub fn compute(sorted: &[f64]) -> Self {
+        let n = sorted.len();
+        if n == 0 {
+            return Self::default();
+        }
+
+        let cut = (TRIM_FRACTION * n as f64).floor() as usize;
+        let kept = &sorted[cut..n - cut];
+        let trimmed_mean = kept.iter().sum::<f64>() / kept.len() as f64;
+        let winsorized_mean = (kept.iter().sum::<f64>()
+            + cut as f64 * (sorted[cut] + sorted[n - 1 - cut]))
+            / n as f64;
+
+        let median = StatsCalculator::percentile(sorted, 50.0);
+        let mut deviations: Vec<f64> = sorted.iter().map(|x| (x - median).abs()).collect();
+        deviations.sort_by(f64::total_cmp);
+        let iqr =
+            StatsCalculator::percentile(sorted, 75.0) - StatsCalculator::percentile(sorted, 25.0);
+
+        let (skewness, kurtosis) = shape(sorted);
+        Self {
+            trimmed_mean,
+            winsorized_mean,
+            mad: StatsCalculator::percentile(&deviations, 50.0),
+            iqr_sigma: iqr / NORMAL_IQR,
+            skewness,
+            kurtosis,
+        }
+    }
+}
+
+/// Sample skewness G1 and excess kurtosis G2, as reported by Excel and SAS.
+fn shape(values: &[f64]) -> (Option<f64>, Option<f64>) {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let central = |power: i32| values.iter().map(|x| (x - mean).powi(power)).sum::<f64>() / n;
+    let m2 = central(2);
+    if m2 <= 0.0 {
+        return (None, None);
+    }
+
+    let g1 = central(3) / m2.powf(1.5);
+    let g2 = central(4) / (m2 * m2) - 3.0;
+    let skewness = (n >= 3.0).then(|| (n * (n - 1.0)).sqrt() / (n - 2.0) * g1);
+    let kurtosis = (n >= 4.0).then(|| ((n + 1.0) * g2 + 6.0) * (n - 1.0) / ((n - 2.0) * (n - 3.0)));
+    (skewness, kurtosis)
+}
+
+/// Hodges–Lehmann shift of a group from the control: the median of every
+/// group minus control difference. Both slices must be sorted ascending; the
+/// median is selected without forming the differences, so large groups stay
+/// cheap. `None` when either group is empty.
+pub fn hodges_lehmann(group: &[f64], control: &[f64]) -> Option<f64> {
+    if group.is_empty() || control.is_empty() {
+        return None;
+    }
+
+    let total = group.len() * control.len();
+    let k = total.div_ceil(2);
+    let lower = kth_difference(group, control, k);
+    Some(if total.is_multiple_of(2) {
+        (lower + kth_difference(group, control, k + 1)) / 2.0
+    } else {
+        lower
+    })
+}
+
+/// The `k`-th smallest (1-based) difference x - y, by bisection on its value.
+fn kth_difference(x: &[f64], y: &[f64], k: usize) -> f64 {
+    let mut lo = x[0] - y[y.len() - 1];
+    let mut hi = x[x.len() - 1] - y[0];
+    if count_at_most(x, y, lo) >= k {
+        return lo;
+    }
+
+    // At most `lo` are fewer than k differences, at most `hi` at least k
+    loop {
+        let mid = lo + (hi - lo) / 2.0;
+        if mid <= lo || mid >= hi {
+            break;
+        }
+        if count_at_most(x, y, mid) >= k {
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+    }
+    largest_at_most(x, y, hi)
+}
+
+/// Number of differences x - y at most `t`.
+fn count_at_most(x: &[f64], y: &[f64], t: f64) -> usize {
+    let mut j = 0;
+    let mut count = 0;
+    for &xi in x {
+        while j < y.len() && xi - y[j] > t {
+            j += 1;
+        }
+        count += y.len() - j;
+    }
+    count
+}
+
+/// Largest difference x - y at most `t`.
+fn largest_at_most(x: &[f64], y: &[f64], t: f64) -> f64 {
+    let mut j = 0;
+    let mut largest = f64::NEG_INFINITY;
+    for &xi in x {
+        while j < y.len() && xi - y[j] > t {
+            j += 1;
+        }
+        if j < y.len() {
+            largest = largest.max(xi - y[j]);
+        }
+    }
+    largest
+}
@@ -0,0 +1,126 @@
+//! Mismatch Rules Module
+//! Decides which groups count as a mismatch against the control. A group is a
+//! mismatch when any rule holds; a rule holds when all of its conditions do.
+
+use super::calculator::GroupStats;
+use std::collections::HashMap;
+
+/// Default significance level.
+pub const DEFAULT_ALPHA: f64 = 0.05;
+
+/// Conditions that must all hold for a group to be a mismatch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MismatchRule {
+    /// p-value (adjusted, when a correction is applied) at or below alpha
+    pub significant: bool,
+    /// |effect size| above this value, in the selected effect size metric
+    pub min_effect_size: Option<f64>,
+    /// |M-C| above the data type's tolerance
+    pub mean_shift: bool,
+    /// Omnibus p-value of the data type at or below alpha
+    pub omnibus: bool,
+    /// Variance test p-value at or below alpha
+    pub variance_shift: bool,
+}
+
+impl Default for MismatchRule {
+    fn default() -> Self {
+        Self {
+            significant: true,
+            min_effect_size: None,
+            mean_shift: false,
+            omnibus: false,
+            variance_shift: false,
+        }
+    }
+}
+
+impl MismatchRule {
+    /// Whether the rule has any condition; empty rules never match.
+    pub fn is_empty(&self) -> bool {
+        !self.significant
+            && self.min_effect_size.is_none()
+            && !self.mean_shift
+            && !self.omnibus
+            && !self.variance_shift
+    }
+
+    /// Whether the group meets every condition. Without an omnibus result (fewer
+    /// than three groups) the omnibus condition falls back to the group's p-value.
+    fn matches(
+        &self,
+        gs: &GroupStats,
+        control_mean: f64,
+        omnibus_p: Option<f64>,
+        alpha: f64,
+        tolerance: f64,
+    ) -> bool {
+        if self.is_empty() {
+            return false;
+        }
+
+        let below_alpha = |p: Option<f64>| p.is_some_and(|p| !p.is_nan() && p <= alpha);
+        let group_p = gs.p_adjusted.or(gs.p_value);
+        let significant = || below_alpha(group_p);
+        let omnibus_significant = || below_alpha(omnibus_p.or(group_p));
+        let large_effect = |min: f64| gs.effect_size.is_some_and(|d| d.abs() > min);
+        let shifted = || (gs.mean - control_mean).abs() > tolerance;
+
+        (!self.significant || significant())
+            && self.min_effect_size.is_none_or(large_effect)
+            && (!self.mean_shift || shifted())
+            && (!self.omnibus || omnibus_significant())
+            && (!self.variance_shift || below_alpha(gs.variance_p))
+    }
+}
+
+/// The rules deciding mismatches, with the mean shift tolerances they use.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MismatchRules {
+    pub rules: Vec<MismatchRule>,
+    /// Tolerance for data types without their own
+    pub default_tolerance: f64,
+    /// Tolerance per data type
+    pub tolerances: HashMap<String, f64>,
+}
+
+impl Default for MismatchRules {
+    /// A single rule flagging significant p-values.
+    fn default() -> Self {
+        Self {
+            rules: vec![MismatchRule::default()],
+            default_tolerance: 0.0,
+            tolerances: HashMap::new(),
+        }
+    }
+}
+
+impl MismatchRules {
+    /// Mean shift tolerance of a data type.
+    pub fn tolerance_for(&self, data_type: &str) -> f64 {
+        self.tolerances
+            .get(data_type)
+            .copied()
+            .unwrap_or(self.default_tolerance)
+    }
+
+    /// Whether any rule tests the mean shift.
+    pub fn uses_mean_shift(&self) -> bool {
+        self.rules.iter().any(|rule| rule.mean_shift)
+    }
+
+    /// Whether a group of the given data type is a mismatch.
+    pub fn is_mismatch(
+        &self,
+        data_type: &str,
+        gs: &GroupStats,
+        control_mean: f64,
+        omnibus_p: Option<f64>,
+        alpha: f64,
+    ) -> bool {
+        let tolerance = self.tolerance_for(data_type);
+        self.rules
+            .iter()
+            .any(|rule| rule.matches(gs, control_mean, omnibus_p, alpha, tolerance))
+    }
+}
@@ -0,0 +1,400 @@
+//! Streaming Statistics Module
+//! Computes group statistics with the Polars streaming engine so that data larger than
+//! memory can be analysed. Counts, means, variances, t-tests, F-tests and Dunnett are exact;
+//! quantiles, plots, rank-based tests, Cliff's delta, normality, Levene and Brown–Forsythe tests
+//! and resampling use a bounded random sample of each group.
+
+use super::calculator::{DataTypeStats, GroupStats, StatColumn, StatsCalculator};
+use super::effect_size::EffectSizeMethod;
+use super::hypothesis::{Moments, TestMethod, DEFAULT_CI_LEVEL};
+use super::normality::Normality;
+use super::omnibus::OmnibusMethod;
+use super::variance::VarianceTest;
+use crate::data::{DataProcessor, DropCounts, ROW_ID_COLUMN};
+use polars::prelude::*;
+use std::collections::HashMap;
+
+/// Maximum number of values kept per (data_type, group) for quantiles and plots.
+pub const SAMPLE_SIZE: usize = 10_000;
+
+/// Sampling rate headroom so that a group almost always yields a full sample.
+const OVERSAMPLING: f64 = 1.2;
+
+/// Rows are hashed into [0, HASH_RANGE) by hashing their id with these fixed seeds,
+/// so runs are reproducible, and keeping the upper 32 bits of the 64-bit hash.
+const HASH_SEEDS: [u64; 4] = [
+    0x243F_6A88_85A3_08D3,
+    0x1319_8A2E_0370_7344,
+    0xA409_3822_299F_31D0,
+    0x082E_FA98_EC4E_6C89,
+];
+const HASH_RANGE: u64 = 1 << 32;
+
+/// Hash, row id and value of a row that passed its group's hash threshold.
+type SampledRow = (u64, u64, f64);
+
+/// Exact moments of one (data_type, group), from the streaming aggregations.
+#[derive(Debug, Clone, Copy, Default)]
+struct GroupSummary {
+    count: usize,
+    mean: f64,
+    /// Sum of squared deviations from the mean.
+    m2: f64,
+}
+
+impl GroupSummary {
+    fn variance(&self) -> f64 {
+        if self.count > 1 {
+            self.m2 / (self.count - 1) as f64
+        } else {
+            0.0
+        }
+    }
+
+    fn moments(&self) -> Moments {
+        (self.count as f64, self.mean, self.variance())
+    }
+}
+
+/// Statistics of scanned data, plus what was left out.
+pub struct StreamingResult {
+    pub stats: HashMap<String, DataTypeStats>,
+    /// Uniform sample of at most `SAMPLE_SIZE` usable values per (data_type, group).
+    pub samples: HashMap<(String, String), Vec<f64>>,
+    /// Dropped values keyed by (data_type, group).
+    pub excluded: HashMap<(String, String), DropCounts>,
+    /// Rows dropped because their group or data type was missing.
+    pub unassigned: usize,
+}
+
+/// Computes statistics from a lazy long-format frame without collecting it.
+pub struct StreamingStats;
+
+impl StreamingStats {
+    /// Compute statistics for every data type and group of a long-format scan
+    /// (as built by `DataProcessor::prepare_lazy` from a streaming scan, so that it
+    /// carries the `__row_id` column).
+    ///
+    /// The data is streamed three times: counts and sums, squared deviations from
+    /// the group means, and a hash-based sample of each group's values. Tests that
+    /// need the values themselves run on the samples.
+    pub fn compute(
+        long: LazyFrame,
+        control_group: &str,
+        test: TestMethod,
+    ) -> PolarsResult<StreamingResult> {
+        let usable = DataProcessor::usable_value();
+
+        // Pass 1: counts, drop reasons and sums
+        let mut aggs = DataProcessor::drop_count_aggs();
+        aggs.extend([
+            usable.clone().cast(DataType::UInt64).sum().alias("count"),
+            when(usable.clone())
+                .then(col("value"))
+                .otherwise(lit(0.0))
+                .sum()
+                .alias("sum"),
+        ]);
+        let totals = long
+            .clone()
+            .group_by([col("data_type"), col("group")])
+            .agg(aggs)
+            .with_streaming(true)
+            .collect()?;
+        let (excluded, unassigned) = DataProcessor::read_drop_counts(&totals)?;
+
+        let mut summaries: HashMap<(String, String), GroupSummary> = HashMap::new();
+        let data_types = totals.column("data_type")?.str()?;
+        let groups = totals.column("group")?.str()?;
+        let counts = totals.column("count")?.cast(&DataType::UInt64)?;
+        let counts = counts.u64()?;
+        let sums = totals.column("sum")?.f64()?;
+
+        for i in 0..totals.height() {
+            let (Some(data_type), Some(group), Some(count), Some(sum)) =
+                (data_types.get(i), groups.get(i), counts.get(i), sums.get(i))
+            else {
+                continue;
+            };
+            if count > 0 {
+                summaries.insert(
+                    (data_type.to_string(), group.to_string()),
+                    GroupSummary {
+                        count: count as usize,
+                        mean: sum / count as f64,
+                        m2: 0.0,
+                    },
+                );
+            }
+        }
+
+        // Per-group mean and sampling threshold, joined back onto the usable rows
+        let keys: Vec<&(String, String)> = summaries.keys().collect();
+        let params = DataFrame::new(vec![
+            Column::new(
+                "data_type".into(),
+                keys.iter().map(|(dt, _)| dt.as_str()).collect::<Vec<_>>(),
+            ),
+            Column::new(
+                "group".into(),
+                keys.iter().map(|(_, g)| g.as_str()).collect::<Vec<_>>(),
+            ),
+            Column::new(
+                "mean".into(),
+                keys.iter()
+                    .map(|key| summaries[*key].mean)
+                    .collect::<Vec<_>>(),
+            ),
+            Column::new(
+                "threshold".into(),
+                keys.iter()
+                    .map(|key| Self::sample_threshold(summaries[*key].count))
+                    .collect::<Vec<_>>(),
+            ),
+        ])?;
+
+        let key_cols = [col("data_type"), col("group")];
+        let usable_rows = long.filter(usable).join(
+            params.lazy(),
+            key_cols.clone(),
+            key_cols.clone(),
+            JoinArgs::new(JoinType::Inner),
+        );
+
+        // Pass 2: squared deviations from the group means
+        let deviation = col("value") - col("mean");
+        let m2 = usable_rows
+            .clone()
+            .group_by(key_cols.clone())
+            .agg([(deviation.clone() * deviation).sum().alias("m2")])
+            .with_streaming(true)
+            .collect()?;
+
+        let m2_data_types = m2.column("data_type")?.str()?;
+        let m2_groups = m2.column("group")?.str()?;
+        let m2_values = m2.column("m2")?.f64()?;
+        for i in 0..m2.height() {
+            if let (Some(data_type), Some(group), Some(value)) =
+                (m2_data_types.get(i), m2_groups.get(i), m2_values.get(i))
+            {
+                if let Some(summary) =
+                    summaries.get_mut(&(data_type.to_string(), group.to_string()))
+                {
+                    summary.m2 = value;
+                }
+            }
+        }
+
+        // Pass 3: rows whose hash falls below their group's threshold
+        let [k0, k1, k2, k3] = HASH_SEEDS;
+        let hash = (col(ROW_ID_COLUMN).hash(k0, k1, k2, k3) / lit(HASH_RANGE)).alias("hash");
+        let sampled = usable_rows
+            .with_column(hash)
+            .filter(col("hash").lt(col("threshold")))
+            .select([
+                col("data_type"),
+                col("group"),
+                col("value"),
+                col("hash"),
+                col(ROW_ID_COLUMN).cast(DataType::UInt64),
+            ])
+            .with_streaming(true)
+            .collect()?;
+        let samples = Self::bottom_k_samples(&sampled)?;
+
+        let stats = Self::assemble_stats(&summaries, &samples, control_group, test);
+
+        Ok(StreamingResult {
+            stats,
+            samples,
+            excluded,
+            unassigned,
+        })
+    }
+
+    /// Hash threshold selecting roughly `OVERSAMPLING * SAMPLE_SIZE` of `count` rows
+    /// (all of them for small groups).
+    fn sample_threshold(count: usize) -> u64 {
+        let rate = OVERSAMPLING * SAMPLE_SIZE as f64 / count as f64;
+        if count <= SAMPLE_SIZE || rate >= 1.0 {
+            HASH_RANGE
+        } else {
+            (rate * HASH_RANGE as f64).ceil() as u64
+        }
+    }
+
+    /// Keep the `SAMPLE_SIZE` values with the smallest hashes in each group. The
+    /// hashes are independent of the row order, so this is a uniform random sample;
+    /// the kept values are put back in file order for the moving-range σ of Cp/Cpk.
+    fn bottom_k_samples(sampled: &DataFrame) -> PolarsResult<HashMap<(String, String), Vec<f64>>> {
+        let data_types = sampled.column("data_type")?.str()?;
+        let groups = sampled.column("group")?.str()?;
+        let values = sampled.column("value")?.f64()?;
+        let hashes = sampled.column("hash")?.u64()?;
+        let row_ids = sampled.column(ROW_ID_COLUMN)?.u64()?;
+
+        let mut by_group: HashMap<(String, String), Vec<SampledRow>> = HashMap::new();
+        for i in 0..sampled.height() {
+            if let (Some(data_type), Some(group), Some(value), Some(hash), Some(row_id)) = (
+                data_types.get(i),
+                groups.get(i),
+                values.get(i),
+                hashes.get(i),
+                row_ids.get(i),
+            ) {
+                by_group
+                    .entry((data_type.to_string(), group.to_string()))
+                    .or_default()
+                    .push((hash, row_id, value));
+            }
+        }
+
+        Ok(by_group
+            .into_iter()
+            .map(|(key, mut rows)| {
+                rows.sort_by_key(|(hash, _, _)| *hash);
+                rows.truncate(SAMPLE_SIZE);
+                rows.sort_by_key(|(_, row_id, _)| *row_id);
+                (key, rows.into_iter().map(|(_, _, value)| value).collect())
+            })
+            .collect())
+    }
+
+    /// Build per data type statistics from the exact moments and the samples,
+    /// mirroring `StatsCalculator::compute_data_type_stats`.
+    fn assemble_stats(
+        summaries: &HashMap<(String, String), GroupSummary>,
+        samples: &HashMap<(String, String), Vec<f64>>,
+        control_group: &str,
+        test: TestMethod,
+    ) -> HashMap<String, DataTypeStats> {
+        let mut data_types: Vec<&String> = summaries.keys().map(|(dt, _)| dt).collect();
+        data_types.sort();
+        data_types.dedup();
+
+        data_types
+            .into_iter()
+            .map(|data_type| {
+                let group_stats_for = |group: &str| {
+                    let key = (data_type.clone(), group.to_string());
+                    let summary = summaries.get(&key).copied().unwrap_or_default();
+                    let sample = samples.get(&key).map(Vec::as_slice).unwrap_or_default();
+                    (summary, sample, Self::group_stats(group, &summary, sample))
+                };
+
+                let mut group_stats: HashMap<String, GroupStats> = HashMap::new();
+
+                let (control_summary, control_sample, control_stats) =
+                    group_stats_for(control_group);
+                group_stats.insert(control_group.to_string(), control_stats);
+
+                for (dt, group) in summaries.keys() {
+                    if dt != data_type || group == control_group {
+                        continue;
+                    }
+
+                    let (summary, sample, mut gs) = group_stats_for(group);
+
+                    gs.effect_sizes = EffectSizeMethod::compute_all(
+                        sample,
+                        control_sample,
+                        summary.moments(),
+                        control_summary.moments(),
+                    );
+                    gs.sigma_ratio =
+                        StatsCalculator::sigma_ratio(summary.moments(), control_summary.moments());
+                    gs.variance_tests = VarianceTest::run_all(
+                        sample,
+                        control_sample,
+                        summary.moments(),
+                        control_summary.moments(),
+                    );
+
+                    // Compare with the control group; permutation p-values come from resampling
+                    if control_summary.count > 0 {
+                        if let Some(pairwise) = test.pairwise() {
+                            let p_value = pairwise
+                                .p_value_from_moments(summary.moments(), control_summary.moments())
+                                .unwrap_or_else(|| pairwise.p_value(sample, control_sample));
+                            gs.p_value = Some(p_value);
+                            gs.test = Some(test);
+                        } else if test == TestMethod::Permutation {
+                            gs.test = Some(test);
+                        }
+                    }
+
+                    group_stats.insert(group.clone(), gs);
+                }
+
+                // Omnibus tests: exact moments, samples for the rank-based test
+                let mut groups: Vec<&String> = summaries
+                    .keys()
+                    .filter(|(dt, _)| dt == data_type)
+                    .map(|(_, group)| group)
+                    .collect();
+                groups.sort();
+                let (values, moments): (Vec<&[f64]>, Vec<Moments>) = groups
+                    .iter()
+                    .map(|group| {
+                        let (summary, sample, _) = group_stats_for(group);
+                        (sample, summary.moments())
+                    })
+                    .unzip();
+
+                let mut stats = DataTypeStats {
+                    data_type: data_type.clone(),
+                    control_group: control_group.to_string(),
+                    group_stats,
+                    omnibus: OmnibusMethod::run_all(&values, &moments),
+                    omnibus_method: OmnibusMethod::default(),
+                    dunnett: None,
+                    ci_level: DEFAULT_CI_LEVEL,
+                    effect_size_method: EffectSizeMethod::default(),
+                    normality_fallback: false,
+                    variance_test: VarianceTest::default(),
+                    equivalence_margin: None,
+                    spec_limits: None,
+                    capability_sampled: false,
+                    columns: StatColumn::DEFAULT.to_vec(),
+                    outlier_filter: None,
+                };
+                stats.apply_confidence_level(DEFAULT_CI_LEVEL);
+                stats.select_variance_test(VarianceTest::default());
+                if test == TestMethod::Dunnett {
+                    let by_group = groups.iter().map(|g| g.as_str()).zip(moments).collect();
+                    stats.apply_dunnett(&by_group);
+                }
+
+                (data_type.clone(), stats)
+            })
+            .collect()
+    }
+
+    /// Descriptive statistics of one group; quantiles come from the sample,
+    /// which holds every value when the group is small.
+    fn group_stats(group: &str, summary: &GroupSummary, sample: &[f64]) -> GroupStats {
+        if summary.count == 0 {
+            return GroupStats {
+                group_name: group.to_string(),
+                ..GroupStats::default()
+            };
+        }
+
+        let mut sorted = sample.to_vec();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        let variance = summary.variance();
+
+        GroupStats {
+            group_name: group.to_string(),
+            count: summary.count,
+            mean: summary.mean,
+            median: StatsCalculator::percentile(&sorted, 50.0),
+            std: variance.sqrt(),
+            variance,
+            p95: StatsCalculator::percentile(&sorted, 95.0),
+            p05: StatsCalculator::percentile(&sorted, 5.0),
+            normality: Normality::test(&sorted),
+            ..GroupStats::default()
+        }
+    }
+}
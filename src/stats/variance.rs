@@ -0,0 +1,104 @@
+//! Variance Tests Module
+//! Tests of equal spread between a group and the control, for process changes
+//! that widen or narrow a distribution without moving its mean.
+
+use super::calculator::StatsCalculator;
+use super::hypothesis::{self, Moments};
+use super::omnibus::OmnibusMethod;
+use statrs::distribution::{ContinuousCDF, FisherSnedecor};
+
+/// Test of a group's variance against the control's.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VarianceTest {
+    /// Ratio of the sample variances; assumes normal groups
+    FTest,
+    /// ANOVA on absolute deviations from the group means
+    Levene,
+    /// ANOVA on absolute deviations from the group medians, robust to skew
+    #[default]
+    BrownForsythe,
+}
+
+/// P-value of one variance test of a group against the control.
+#[derive(Debug, Clone, Copy)]
+pub struct VarianceResult {
+    pub test: VarianceTest,
+    pub p_value: f64,
+}
+
+impl VarianceTest {
+    pub const ALL: [VarianceTest; 3] = [
+        VarianceTest::FTest,
+        VarianceTest::Levene,
+        VarianceTest::BrownForsythe,
+    ];
+
+    /// Human readable name for the control panel and the stats tables.
+    pub fn name(&self) -> &'static str {
+        match self {
+            VarianceTest::FTest => "F-test",
+            VarianceTest::Levene => "Levene",
+            VarianceTest::BrownForsythe => "Brown–Forsythe",
+        }
+    }
+
+    /// Run every variance test the data allows. The F-test only needs the
+    /// moments; Levene and Brown–Forsythe use the values.
+    pub fn run_all(
+        group: &[f64],
+        control: &[f64],
+        group_moments: Moments,
+        control_moments: Moments,
+    ) -> Vec<VarianceResult> {
+        Self::ALL
+            .iter()
+            .filter_map(|test| {
+                let p_value = match test {
+                    VarianceTest::FTest => Self::f_test(group_moments, control_moments),
+                    _ => test.deviation_anova(group, control),
+                }?;
+                Some(VarianceResult {
+                    test: *test,
+                    p_value,
+                })
+            })
+            .collect()
+    }
+
+    /// Two-sided F-test of the variance ratio.
+    fn f_test(group: Moments, control: Moments) -> Option<f64> {
+        let (n1, _, var1) = group;
+        let (n0, _, var0) = control;
+        if n1 < 2.0 || n0 < 2.0 || var0 <= 0.0 {
+            return None;
+        }
+        let dist = FisherSnedecor::new(n1 - 1.0, n0 - 1.0).ok()?;
+        let cdf = dist.cdf(var1 / var0);
+        Some((2.0 * cdf.min(1.0 - cdf)).min(1.0))
+    }
+
+    /// One-way ANOVA on the absolute deviations from each sample's center.
+    fn deviation_anova(&self, group: &[f64], control: &[f64]) -> Option<f64> {
+        if group.len() < 2 || control.len() < 2 {
+            return None;
+        }
+        let deviations = |values: &[f64]| {
+            let center = match self {
+                VarianceTest::BrownForsythe => Self::median(values),
+                _ => values.iter().sum::<f64>() / values.len() as f64,
+            };
+            let deviations: Vec<f64> = values.iter().map(|x| (x - center).abs()).collect();
+            hypothesis::moments(&deviations)
+        };
+        let moments = [deviations(group), deviations(control)];
+        OmnibusMethod::Anova
+            .run(&[], &moments)
+            .map(|result| result.p_value)
+    }
+
+    fn median(values: &[f64]) -> f64 {
+        let mut sorted = values.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        StatsCalculator::percentile(&sorted, 50.0)
+    }
+}